@@ -0,0 +1,600 @@
+//! Planning of byte range responses.
+//!
+//! [`RangePlanner`] decides how to respond to a request with a `Range`
+//! header, following [RFC 9110 section
+//! 14](https://www.rfc-editor.org/rfc/rfc9110#section-14). When several
+//! ranges are sent, [`MultipartByteRanges`] frames them as a
+//! `multipart/byteranges` body.
+//!
+//! # Example
+//!
+//! ```
+//! use headers::byte_ranges::{RangePlan, RangePlanner};
+//! use headers::{ContentRange, Range};
+//!
+//! let range = Range::bytes(0..100).unwrap();
+//! let plan = RangePlanner::new(1000).plan(&range, None);
+//! assert_eq!(
+//!     plan,
+//!     RangePlan::Single(ContentRange::bytes(0..100, 1000).unwrap())
+//! );
+//! ```
+
+use std::cmp;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use http::HeaderValue;
+
+use crate::util::is_token;
+use crate::{ContentLength, ContentRange, ContentType, ETag, Header, IfRange, LastModified, Range};
+
+/// The default maximum number of parts in a `multipart/byteranges` response.
+const DEFAULT_MAX_RANGES: usize = 16;
+
+/// Plans the response to a range request for a representation.
+#[derive(Clone, Debug)]
+pub struct RangePlanner {
+    len: u64,
+    etag: Option<ETag>,
+    last_modified: Option<LastModified>,
+    max_ranges: usize,
+}
+
+/// How to respond to a range request.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RangePlan {
+    /// Ignore the `Range` header, and respond with `200 OK` and the full
+    /// representation.
+    Full,
+    /// Respond with `206 Partial Content` and a single part, described by
+    /// the `Content-Range` header.
+    Single(ContentRange),
+    /// Respond with `206 Partial Content` and a `multipart/byteranges` body,
+    /// with one part for each `Content-Range`, in ascending order.
+    Multipart(Vec<ContentRange>),
+    /// Respond with `416 Range Not Satisfiable`, and the `Content-Range`
+    /// header.
+    Unsatisfiable(ContentRange),
+}
+
+impl RangePlanner {
+    /// Create a planner for a representation of `len` bytes.
+    pub fn new(len: u64) -> RangePlanner {
+        RangePlanner {
+            len,
+            etag: None,
+            last_modified: None,
+            max_ranges: DEFAULT_MAX_RANGES,
+        }
+    }
+
+    /// Set the current `ETag` of the representation, to evaluate `If-Range`.
+    pub fn etag(mut self, etag: ETag) -> Self {
+        self.etag = Some(etag);
+        self
+    }
+
+    /// Set the current `Last-Modified` date of the representation, to
+    /// evaluate `If-Range`.
+    pub fn last_modified(mut self, last_modified: LastModified) -> Self {
+        self.last_modified = Some(last_modified);
+        self
+    }
+
+    /// Set the maximum number of parts to send, after coalescing the
+    /// requested ranges. Requests for more parts are answered with the full
+    /// representation.
+    ///
+    /// Defaults to 16.
+    pub fn max_ranges(mut self, max_ranges: usize) -> Self {
+        self.max_ranges = max_ranges;
+        self
+    }
+
+    /// Plan the response to a request with the given `Range` and `If-Range`
+    /// headers.
+    ///
+    /// The `Range` header is ignored if `If-Range` doesn't match the current
+    /// validators, or if it is invalid. Overlapping and adjacent ranges are
+    /// coalesced.
+    pub fn plan(&self, range: &Range, if_range: Option<&IfRange>) -> RangePlan {
+        if let Some(if_range) = if_range {
+            if if_range.is_modified(self.etag.as_ref(), self.last_modified.as_ref()) {
+                return RangePlan::Full;
+            }
+        }
+
+        let specs = match range.byte_range_specs() {
+            Some(specs) => specs,
+            None => return RangePlan::Full,
+        };
+
+        let mut ranges = specs
+            .into_iter()
+            .filter_map(|spec| self.satisfiable(spec))
+            .collect::<Vec<_>>();
+        ranges.sort_unstable();
+
+        let mut coalesced: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match coalesced.last_mut() {
+                Some(last) if start <= last.1 + 1 => last.1 = cmp::max(last.1, end),
+                _ => coalesced.push((start, end)),
+            }
+        }
+
+        let content_range = |(start, end): (u64, u64)| {
+            ContentRange::bytes(start..=end, self.len).expect("range is bounded")
+        };
+
+        match coalesced.len() {
+            0 => RangePlan::Unsatisfiable(ContentRange::unsatisfied_bytes(self.len)),
+            1 => RangePlan::Single(content_range(coalesced[0])),
+            n if n > self.max_ranges => RangePlan::Full,
+            _ => RangePlan::Multipart(coalesced.into_iter().map(content_range).collect()),
+        }
+    }
+
+    /// Resolve a byte-range-spec to the inclusive range of bytes it selects,
+    /// if satisfiable.
+    fn satisfiable(&self, spec: (Option<u64>, Option<u64>)) -> Option<(u64, u64)> {
+        if self.len == 0 {
+            return None;
+        }
+        let last = self.len - 1;
+
+        match spec {
+            (Some(first), end) if first <= last => {
+                Some((first, end.map(|end| cmp::min(end, last)).unwrap_or(last)))
+            }
+            (None, Some(suffix)) if suffix > 0 => Some((self.len.saturating_sub(suffix), last)),
+            _ => None,
+        }
+    }
+}
+
+/// The framing of a `multipart/byteranges` response body.
+///
+/// Each part is sent as a header block with its `Content-Type` and
+/// `Content-Range`, followed by the selected bytes of the representation.
+/// The header blocks are generated up front, so the exact `Content-Length`
+/// of the body is known before any data is read.
+///
+/// # Example
+///
+/// ```
+/// use bytes::Bytes;
+/// use headers::byte_ranges::{MultipartByteRanges, RangePlan, RangePlanner};
+/// use headers::{ContentType, HeaderMap, HeaderMapExt, Range};
+///
+/// let file = Bytes::from_static(b"0123456789");
+///
+/// let mut req = HeaderMap::new();
+/// req.insert("range", "bytes=0-1, 8-".parse().unwrap());
+/// let range = req.typed_get::<Range>().unwrap();
+///
+/// let ranges = match RangePlanner::new(10).plan(&range, None) {
+///     RangePlan::Multipart(ranges) => ranges,
+///     _ => unreachable!(),
+/// };
+///
+/// let multipart = MultipartByteRanges::new(&ranges, ContentType::text())
+///     .with_boundary("THIS_STRING_SEPARATES");
+/// let body = multipart.body(&file).collect::<Vec<_>>().concat();
+///
+/// assert_eq!(
+///     body,
+///     &b"--THIS_STRING_SEPARATES\r\n\
+///        content-type: text/plain\r\n\
+///        content-range: bytes 0-1/10\r\n\
+///        \r\n\
+///        01\r\n\
+///        --THIS_STRING_SEPARATES\r\n\
+///        content-type: text/plain\r\n\
+///        content-range: bytes 8-9/10\r\n\
+///        \r\n\
+///        89\r\n\
+///        --THIS_STRING_SEPARATES--\r\n"[..]
+/// );
+/// assert_eq!(multipart.content_length().0, body.len() as u64);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultipartByteRanges {
+    boundary: String,
+    content_type: ContentType,
+    len: u64,
+    parts: Vec<Part>,
+}
+
+/// A part of a `multipart/byteranges` body.
+#[derive(Clone, Debug)]
+pub struct Part {
+    header_block: Bytes,
+    start: u64,
+    end: u64,
+}
+
+impl MultipartByteRanges {
+    /// Frame the given ranges of a representation, each part having the
+    /// given `Content-Type`.
+    ///
+    /// The length of the representation is the complete length of the
+    /// ranges. A random boundary is generated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the ranges is an unsatisfied `Content-Range`, if
+    /// the ranges don't all have the same known complete length, or if a
+    /// range is reversed or ends past the complete length.
+    pub fn new(ranges: &[ContentRange], content_type: ContentType) -> Self {
+        let len = ranges
+            .first()
+            .map(|range| range.bytes_len().expect("complete length of Content-Range"))
+            .unwrap_or(0);
+        let ranges = ranges
+            .iter()
+            .map(|range| {
+                assert_eq!(range.bytes_len(), Some(len), "mismatched complete length");
+                let (start, end) = range.bytes_range().expect("satisfied Content-Range");
+                assert!(start <= end && end < len, "invalid Content-Range");
+                (start, end)
+            })
+            .collect::<Vec<_>>();
+
+        let mut multipart = MultipartByteRanges {
+            boundary: random_boundary(),
+            content_type,
+            len,
+            parts: Vec::with_capacity(ranges.len()),
+        };
+        multipart.parts = ranges
+            .into_iter()
+            .enumerate()
+            .map(|(i, (start, end))| multipart.part(i == 0, start, end))
+            .collect();
+        multipart
+    }
+
+    /// Use the given boundary instead of a random one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the boundary is empty, longer than 70 characters, or
+    /// contains characters not allowed by
+    /// [RFC 2046](https://www.rfc-editor.org/rfc/rfc2046#section-5.1.1).
+    pub fn with_boundary(mut self, boundary: &str) -> Self {
+        assert!(is_valid_boundary(boundary), "invalid multipart boundary");
+        self.boundary = boundary.to_owned();
+        self.parts = self
+            .parts
+            .iter()
+            .enumerate()
+            .map(|(i, part)| self.part(i == 0, part.start, part.end))
+            .collect();
+        self
+    }
+
+    /// The boundary delimiting the parts.
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// The `Content-Type` of the response, `multipart/byteranges` with the
+    /// boundary parameter.
+    pub fn content_type(&self) -> ContentType {
+        let mime = if is_token(&self.boundary) {
+            format!("multipart/byteranges; boundary={}", self.boundary)
+        } else {
+            format!("multipart/byteranges; boundary=\"{}\"", self.boundary)
+        };
+        mime.parse::<mime::Mime>().expect("valid boundary").into()
+    }
+
+    /// The exact `Content-Length` of the response body.
+    pub fn content_length(&self) -> ContentLength {
+        let len = self
+            .parts
+            .iter()
+            .map(|part| part.header_block.len() as u64 + part.end - part.start + 1)
+            .sum::<u64>()
+            + self.closing_delimiter().len() as u64;
+        ContentLength(len)
+    }
+
+    /// The parts of the body, in order.
+    pub fn parts(&self) -> &[Part] {
+        &self.parts
+    }
+
+    /// The delimiter closing the body, sent after the last part.
+    pub fn closing_delimiter(&self) -> Bytes {
+        Bytes::from(format!("\r\n--{}--\r\n", self.boundary))
+    }
+
+    /// Iterate the chunks of the body, slicing the data of each part from
+    /// the full representation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `source` is shorter than the length of the representation.
+    pub fn body<'a>(&'a self, source: &'a Bytes) -> impl Iterator<Item = Bytes> + 'a {
+        assert!(source.len() as u64 >= self.len, "source is too short");
+        self.parts
+            .iter()
+            .flat_map(move |part| {
+                let data = source.slice(part.start as usize..=part.end as usize);
+                vec![part.header_block.clone(), data]
+            })
+            .chain(std::iter::once(self.closing_delimiter()))
+    }
+
+    fn part(&self, first: bool, start: u64, end: u64) -> Part {
+        let mut buf = BytesMut::new();
+        if !first {
+            buf.put_slice(b"\r\n");
+        }
+        buf.put_slice(b"--");
+        buf.put_slice(self.boundary.as_bytes());
+        buf.put_slice(b"\r\n");
+
+        let content_range = ContentRange::bytes(start..=end, self.len).expect("range is bounded");
+        put_header(&mut buf, &self.content_type);
+        put_header(&mut buf, &content_range);
+        buf.put_slice(b"\r\n");
+
+        Part {
+            header_block: buf.freeze(),
+            start,
+            end,
+        }
+    }
+}
+
+impl Part {
+    /// The delimiter and headers of this part, sent before its data.
+    pub fn header_block(&self) -> &Bytes {
+        &self.header_block
+    }
+
+    /// The first and last byte of the representation sent in this part.
+    ///
+    /// Note that this byte range is inclusive on both ends.
+    pub fn range(&self) -> (u64, u64) {
+        (self.start, self.end)
+    }
+}
+
+fn put_header<H: Header>(buf: &mut BytesMut, header: &H) {
+    let mut values = Vec::<HeaderValue>::new();
+    header.encode(&mut values);
+    for value in values {
+        buf.put_slice(H::name().as_str().as_bytes());
+        buf.put_slice(b": ");
+        buf.put_slice(value.as_bytes());
+        buf.put_slice(b"\r\n");
+    }
+}
+
+fn random_boundary() -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_usize(COUNTER.fetch_add(1, Ordering::Relaxed));
+    if let Ok(elapsed) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        hasher.write_u128(elapsed.as_nanos());
+    }
+    let high = hasher.finish();
+    hasher.write_u64(high);
+    format!("{:016x}{:016x}", high, hasher.finish())
+}
+
+fn is_valid_boundary(boundary: &str) -> bool {
+    !boundary.is_empty()
+        && boundary.len() <= 70
+        && !boundary.ends_with(' ')
+        && boundary
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"'()+_,-./:=? ".contains(&b))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::common::test_decode;
+
+    fn range(s: &str) -> Range {
+        test_decode(&[s]).unwrap()
+    }
+
+    fn bytes(start: u64, end: u64, len: u64) -> ContentRange {
+        ContentRange::bytes(start..=end, len).unwrap()
+    }
+
+    #[test]
+    fn single() {
+        let planner = RangePlanner::new(1000);
+        assert_eq!(
+            planner.plan(&range("bytes=0-499"), None),
+            RangePlan::Single(bytes(0, 499, 1000))
+        );
+        assert_eq!(
+            planner.plan(&range("bytes=500-"), None),
+            RangePlan::Single(bytes(500, 999, 1000))
+        );
+        assert_eq!(
+            planner.plan(&range("bytes=900-2000"), None),
+            RangePlan::Single(bytes(900, 999, 1000))
+        );
+        assert_eq!(
+            planner.plan(&range("bytes=-100"), None),
+            RangePlan::Single(bytes(900, 999, 1000))
+        );
+
+        // A suffix longer than the representation selects all of it.
+        assert_eq!(
+            planner.plan(&range("bytes=-5000"), None),
+            RangePlan::Single(bytes(0, 999, 1000))
+        );
+    }
+
+    #[test]
+    fn multipart() {
+        let planner = RangePlanner::new(1000);
+        assert_eq!(
+            planner.plan(&range("bytes=500-599, 0-99"), None),
+            RangePlan::Multipart(vec![bytes(0, 99, 1000), bytes(500, 599, 1000)])
+        );
+    }
+
+    #[test]
+    fn coalesce() {
+        let planner = RangePlanner::new(1000);
+        assert_eq!(
+            planner.plan(&range("bytes=0-99, 50-199, 200-299"), None),
+            RangePlan::Single(bytes(0, 299, 1000))
+        );
+        assert_eq!(
+            planner.plan(&range("bytes=0-99, 10-20, 500-, -100"), None),
+            RangePlan::Multipart(vec![bytes(0, 99, 1000), bytes(500, 999, 1000)])
+        );
+    }
+
+    #[test]
+    fn unsatisfiable() {
+        let planner = RangePlanner::new(1000);
+        let expected = RangePlan::Unsatisfiable(ContentRange::unsatisfied_bytes(1000));
+        assert_eq!(planner.plan(&range("bytes=1000-"), None), expected);
+        assert_eq!(planner.plan(&range("bytes=-0"), None), expected);
+
+        // Unsatisfiable ranges are skipped if any other one is satisfiable.
+        assert_eq!(
+            planner.plan(&range("bytes=2000-, 0-9"), None),
+            RangePlan::Single(bytes(0, 9, 1000))
+        );
+
+        let planner = RangePlanner::new(0);
+        assert_eq!(
+            planner.plan(&range("bytes=0-"), None),
+            RangePlan::Unsatisfiable(ContentRange::unsatisfied_bytes(0))
+        );
+    }
+
+    #[test]
+    fn invalid_is_ignored() {
+        let planner = RangePlanner::new(1000);
+        assert_eq!(planner.plan(&range("bytes=10-5"), None), RangePlan::Full);
+        assert_eq!(planner.plan(&range("bytes=a-b"), None), RangePlan::Full);
+    }
+
+    #[test]
+    fn max_ranges() {
+        let planner = RangePlanner::new(1000).max_ranges(2);
+        assert_eq!(
+            planner.plan(&range("bytes=0-0, 10-10, 20-20"), None),
+            RangePlan::Full
+        );
+
+        // The limit applies after coalescing.
+        assert_eq!(
+            planner.plan(&range("bytes=0-0, 1-1, 20-20"), None),
+            RangePlan::Multipart(vec![bytes(0, 1, 1000), bytes(20, 20, 1000)])
+        );
+    }
+
+    #[test]
+    fn if_range() {
+        let etag = "\"a\"".parse::<ETag>().unwrap();
+        let planner = RangePlanner::new(1000).etag(etag.clone());
+        let range = range("bytes=0-9");
+
+        assert_eq!(
+            planner.plan(&range, Some(&IfRange::etag(etag))),
+            RangePlan::Single(bytes(0, 9, 1000))
+        );
+
+        let other = "\"b\"".parse::<ETag>().unwrap();
+        assert_eq!(
+            planner.plan(&range, Some(&IfRange::etag(other))),
+            RangePlan::Full
+        );
+
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let planner = RangePlanner::new(1000).last_modified(time.into());
+        assert_eq!(
+            planner.plan(&range, Some(&IfRange::date(time))),
+            RangePlan::Single(bytes(0, 9, 1000))
+        );
+    }
+
+    #[test]
+    fn multipart_body() {
+        let file = Bytes::from_static(b"abcdefghijklmnopqrstuvwxyz");
+        let ranges = vec![bytes(0, 2, 26), bytes(10, 11, 26), bytes(25, 25, 26)];
+        let multipart =
+            MultipartByteRanges::new(&ranges, ContentType::octet_stream()).with_boundary("b");
+
+        assert_eq!(multipart.parts().len(), 3);
+        assert_eq!(multipart.parts()[1].range(), (10, 11));
+        assert_eq!(
+            multipart.parts()[1].header_block(),
+            &b"\r\n--b\r\ncontent-type: application/octet-stream\r\ncontent-range: bytes 10-11/26\r\n\r\n"[..]
+        );
+
+        let body = multipart.body(&file).collect::<Vec<_>>().concat();
+        assert!(body.starts_with(b"--b\r\ncontent-type: application/octet-stream\r\n"));
+        assert!(body.ends_with(b"\r\n\r\nz\r\n--b--\r\n"));
+        assert_eq!(multipart.content_length(), ContentLength(body.len() as u64));
+    }
+
+    #[test]
+    fn multipart_content_type() {
+        let ranges = vec![bytes(0, 0, 10), bytes(5, 5, 10)];
+        let multipart = MultipartByteRanges::new(&ranges, ContentType::text());
+        let other = MultipartByteRanges::new(&ranges, ContentType::text());
+        assert_ne!(multipart.boundary(), other.boundary());
+        assert_eq!(
+            multipart.content_type().to_string(),
+            format!("multipart/byteranges; boundary={}", multipart.boundary())
+        );
+
+        let multipart = multipart.with_boundary("a boundary");
+        assert_eq!(
+            multipart.content_type().to_string(),
+            "multipart/byteranges; boundary=\"a boundary\""
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid multipart boundary")]
+    fn multipart_invalid_boundary() {
+        let ranges = vec![bytes(0, 0, 10), bytes(5, 5, 10)];
+        let _ = MultipartByteRanges::new(&ranges, ContentType::text()).with_boundary("a\"b");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid Content-Range")]
+    fn multipart_range_past_end() {
+        let ranges = vec![bytes(0, 200, 100)];
+        let _ = MultipartByteRanges::new(&ranges, ContentType::text());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid Content-Range")]
+    fn multipart_reversed_range() {
+        let ranges = vec![bytes(5, 2, 10)];
+        let _ = MultipartByteRanges::new(&ranges, ContentType::text());
+    }
+
+    #[test]
+    #[should_panic(expected = "mismatched complete length")]
+    fn multipart_mismatched_lengths() {
+        let ranges = vec![bytes(0, 0, 10), bytes(5, 5, 20)];
+        let _ = MultipartByteRanges::new(&ranges, ContentType::text());
+    }
+}
@@ -0,0 +1,333 @@
+use std::iter::FromIterator;
+
+use http::{HeaderName, HeaderValue};
+use mime::{self, Mime};
+
+use crate::util::{self, FlatCsv, Quality, QualityValue, TryFromValues};
+use crate::{Error, Header};
+
+/// `Accept` header, defined in [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-12.5.1)
+///
+/// The `Accept` header field can be used by user agents to specify
+/// response media types that are acceptable.  Accept header fields can
+/// be used to indicate that the request is specifically limited to a
+/// small set of desired types, as in the case of a request for an
+/// in-line image
+///
+/// # ABNF
+///
+/// ```text
+/// Accept = #( media-range [ weight ] )
+///
+/// media-range    = ( "*/*"
+///                  / ( type "/" "*" )
+///                  / ( type "/" subtype )
+///                  ) parameters
+/// ```
+///
+/// # Example values
+/// * `audio/*; q=0.2, audio/basic`
+/// * `text/plain; q=0.5, text/html, text/x-dvi; q=0.8, text/x-c`
+///
+/// # Examples
+///
+/// ```
+/// use headers::{Accept, HeaderMap, HeaderMapExt};
+///
+/// let mut headers = HeaderMap::new();
+///
+/// headers.typed_insert(Accept::json());
+/// ```
+///
+/// ```
+/// use headers::{Accept, Quality, QualityValue};
+///
+/// let accept = vec![
+///     QualityValue::from(mime::TEXT_HTML),
+///     QualityValue::new(mime::TEXT_XML, Quality::from_u16(900).unwrap()),
+///     QualityValue::new(mime::STAR_STAR, Quality::from_u16(800).unwrap()),
+/// ]
+/// .into_iter()
+/// .collect::<Accept>();
+///
+/// let available = [mime::APPLICATION_JSON, mime::TEXT_XML];
+/// assert_eq!(accept.negotiate(&available), Some(&mime::TEXT_XML));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Accept(FlatCsv);
+
+impl Header for Accept {
+    fn name() -> &'static HeaderName {
+        &::http::header::ACCEPT
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let csv = FlatCsv::try_from_values(values)?;
+        for item in csv.iter().filter(|item| !item.is_empty()) {
+            item.parse::<QualityValue<Mime>>()?;
+        }
+        Ok(Accept(csv))
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(::std::iter::once((&self.0).into()));
+    }
+}
+
+impl Accept {
+    /// A constructor to easily create `Accept: */*`.
+    pub fn star() -> Accept {
+        Accept(HeaderValue::from_static("*/*").into())
+    }
+
+    /// A constructor to easily create `Accept: application/json`.
+    pub fn json() -> Accept {
+        Accept(HeaderValue::from_static("application/json").into())
+    }
+
+    /// A constructor to easily create `Accept: text/*`.
+    pub fn text() -> Accept {
+        Accept(HeaderValue::from_static("text/*").into())
+    }
+
+    /// A constructor to easily create `Accept: image/*`.
+    pub fn image() -> Accept {
+        Accept(HeaderValue::from_static("image/*").into())
+    }
+
+    /// Iterate the media ranges of this header, with their weights.
+    pub fn iter(&self) -> impl Iterator<Item = QualityValue<Mime>> + '_ {
+        self.0
+            .iter()
+            .filter(|item| !item.is_empty())
+            .filter_map(|item| item.parse().ok())
+    }
+
+    /// Select the best media type from the types the server can produce.
+    ///
+    /// Each available type is weighted by the most specific media range
+    /// that matches it, where `type/subtype` with parameters is more specific
+    /// than `type/subtype`, which is more specific than `type/*`, which is
+    /// more specific than `*/*`. The available type with the highest weight
+    /// wins, with ties going to the type listed first by the server.
+    ///
+    /// Returns `None` if none of the available types are acceptable, which
+    /// usually means a `406 Not Acceptable` response.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use headers::{Accept, HeaderMap, HeaderMapExt};
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("accept", "text/*;q=0.5, text/csv, */*;q=0.1".parse().unwrap());
+    /// let accept = headers.typed_get::<Accept>().unwrap();
+    ///
+    /// let available = [mime::APPLICATION_JSON, mime::TEXT_HTML, mime::TEXT_CSV];
+    /// assert_eq!(accept.negotiate(&available), Some(&mime::TEXT_CSV));
+    /// ```
+    pub fn negotiate<'a, I>(&self, available: I) -> Option<&'a Mime>
+    where
+        I: IntoIterator<Item = &'a Mime>,
+    {
+        let ranges = self.iter().collect::<Vec<_>>();
+        let mut best: Option<(&'a Mime, Quality)> = None;
+
+        for mime in available {
+            let quality = if ranges.is_empty() {
+                // An empty list is treated the same as `*/*`.
+                Quality::ONE
+            } else {
+                let most_specific = ranges.iter().fold(None, |best, range| {
+                    match (best, specificity(range.value(), mime)) {
+                        (Some((prev, _)), Some(spec)) if spec <= prev => best,
+                        (_, Some(spec)) => Some((spec, range.quality())),
+                        (best, None) => best,
+                    }
+                });
+                match most_specific {
+                    Some((_, quality)) => quality,
+                    None => continue,
+                }
+            };
+
+            if quality == Quality::ZERO {
+                continue;
+            }
+
+            if best.map(|(_, q)| quality > q).unwrap_or(true) {
+                best = Some((mime, quality));
+            }
+        }
+
+        best.map(|(mime, _)| mime)
+    }
+}
+
+/// How specific `range` is when matching `mime`, or `None` if it doesn't match.
+fn specificity(range: &Mime, mime: &Mime) -> Option<usize> {
+    if range.type_() == mime::STAR {
+        return Some(0);
+    }
+    if !range
+        .type_()
+        .as_str()
+        .eq_ignore_ascii_case(mime.type_().as_str())
+    {
+        return None;
+    }
+
+    if range.subtype() == mime::STAR {
+        return Some(1);
+    }
+    if !range
+        .subtype()
+        .as_str()
+        .eq_ignore_ascii_case(mime.subtype().as_str())
+    {
+        return None;
+    }
+
+    let mut params = 0;
+    for (name, value) in range.params() {
+        match mime.get_param(name.as_str()) {
+            Some(ref v) if *v == value.as_str() => params += 1,
+            _ => return None,
+        }
+    }
+
+    Some(2 + params)
+}
+
+impl FromIterator<QualityValue<Mime>> for Accept {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = QualityValue<Mime>>,
+    {
+        let flat = iter.into_iter().map(util::fmt).collect();
+        Accept(flat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    fn q(thousandths: u16) -> Quality {
+        Quality::from_u16(thousandths).unwrap()
+    }
+
+    fn accept(s: &str) -> Accept {
+        test_decode(&[s]).unwrap()
+    }
+
+    #[test]
+    fn decode_rfc_examples() {
+        let items = accept("audio/*; q=0.2, audio/basic")
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![
+                QualityValue::new("audio/*".parse().unwrap(), q(200)),
+                QualityValue::from("audio/basic".parse::<Mime>().unwrap()),
+            ]
+        );
+
+        let items = accept("text/plain; q=0.5, text/html, text/x-dvi; q=0.8, text/x-c")
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![
+                QualityValue::new(mime::TEXT_PLAIN, q(500)),
+                QualityValue::from(mime::TEXT_HTML),
+                QualityValue::new("text/x-dvi".parse().unwrap(), q(800)),
+                QualityValue::from("text/x-c".parse::<Mime>().unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_params() {
+        let items = accept("text/plain; charset=utf-8; q=0.5")
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![QualityValue::new(mime::TEXT_PLAIN_UTF_8, q(500))]
+        );
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(test_decode::<Accept>(&["text/html, nope"]), None);
+        assert_eq!(test_decode::<Accept>(&["text/html;q=2"]), None);
+    }
+
+    #[test]
+    fn encode() {
+        let accept = vec![
+            QualityValue::from(mime::TEXT_HTML),
+            QualityValue::new(mime::STAR_STAR, q(100)),
+        ]
+        .into_iter()
+        .collect::<Accept>();
+
+        let headers = test_encode(accept);
+        assert_eq!(headers["accept"], "text/html, */*; q=0.1");
+    }
+
+    #[test]
+    fn negotiate_specificity() {
+        // RFC 9110 Section 12.5.1
+        let accept = accept(
+            "text/*;q=0.3, text/plain;q=0.7, text/plain;format=flowed, \
+             text/plain;format=fixed;q=0.4, */*;q=0.5",
+        );
+
+        let flowed: Mime = "text/plain;format=flowed".parse().unwrap();
+        let fixed: Mime = "text/plain;format=fixed".parse().unwrap();
+        let html = mime::TEXT_HTML;
+        let jpeg = mime::IMAGE_JPEG;
+
+        assert_eq!(
+            accept.negotiate(&[fixed.clone(), flowed.clone()]),
+            Some(&flowed)
+        );
+        assert_eq!(
+            accept.negotiate(&[fixed.clone(), mime::TEXT_PLAIN]),
+            Some(&mime::TEXT_PLAIN)
+        );
+        assert_eq!(accept.negotiate(&[html.clone(), jpeg.clone()]), Some(&jpeg));
+        assert_eq!(accept.negotiate(&[html, fixed.clone()]), Some(&fixed));
+    }
+
+    #[test]
+    fn negotiate_excluded() {
+        let accept = accept("application/json, */*;q=0");
+        assert_eq!(accept.negotiate(&[mime::TEXT_HTML]), None);
+        assert_eq!(
+            accept.negotiate(&[mime::TEXT_HTML, mime::APPLICATION_JSON]),
+            Some(&mime::APPLICATION_JSON)
+        );
+    }
+
+    #[test]
+    fn negotiate_ties_prefer_server_order() {
+        let accept = accept("text/html, application/json");
+        assert_eq!(
+            accept.negotiate(&[mime::APPLICATION_JSON, mime::TEXT_HTML]),
+            Some(&mime::APPLICATION_JSON)
+        );
+    }
+
+    #[test]
+    fn negotiate_nothing_available() {
+        assert_eq!(Accept::star().negotiate(&[]), None);
+        assert_eq!(Accept::image().negotiate(&[mime::TEXT_CSV]), None);
+    }
+
+    bench_header!(bench, Accept, "text/plain; q=0.5, text/html");
+}
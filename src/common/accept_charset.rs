@@ -0,0 +1,231 @@
+use std::iter::FromIterator;
+
+use http::{HeaderName, HeaderValue};
+
+use crate::util::{self, Charset, FlatCsv, Quality, QualityValue, TryFromValues};
+use crate::{Error, Header};
+
+/// `Accept-Charset` header, defined in
+/// [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-12.5.2)
+///
+/// The `Accept-Charset` header field can be sent by a user agent to
+/// indicate what charsets are acceptable in textual response content.
+/// This field allows user agents capable of understanding more
+/// comprehensive or special-purpose charsets to signal that capability
+/// to an origin server that is capable of representing information in
+/// those charsets.
+///
+/// # ABNF
+///
+/// ```text
+/// Accept-Charset = #( ( token / "*" ) [ weight ] )
+/// ```
+///
+/// # Example values
+/// * `iso-8859-5, unicode-1-1;q=0.8`
+///
+/// # Examples
+///
+/// ```
+/// use headers::{AcceptCharset, Charset, Quality, QualityValue};
+///
+/// let accept_charset = vec![
+///     QualityValue::new(Charset::US_ASCII, Quality::from_u16(900).unwrap()),
+///     QualityValue::new(Charset::ISO_8859_10, Quality::from_u16(200).unwrap()),
+/// ]
+/// .into_iter()
+/// .collect::<AcceptCharset>();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct AcceptCharset(FlatCsv);
+
+impl Header for AcceptCharset {
+    fn name() -> &'static HeaderName {
+        &::http::header::ACCEPT_CHARSET
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let csv = FlatCsv::try_from_values(values)?;
+        for item in csv.iter().filter(|item| !item.is_empty()) {
+            item.parse::<QualityValue<Charset>>()?;
+        }
+        Ok(AcceptCharset(csv))
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(::std::iter::once((&self.0).into()));
+    }
+}
+
+impl AcceptCharset {
+    /// Iterate the charsets of this header, with their weights.
+    pub fn iter(&self) -> impl Iterator<Item = QualityValue<Charset>> + '_ {
+        self.0
+            .iter()
+            .filter(|item| !item.is_empty())
+            .filter_map(|item| item.parse().ok())
+    }
+
+    /// Select the preferred charset from the charsets the server supports.
+    ///
+    /// Each supported charset is weighted by its entry in this header, or by
+    /// `*` if it isn't listed. Charsets that are neither listed nor matched
+    /// by `*` are not acceptable, unless the header has no entries at all.
+    /// The charset with the highest weight wins, with ties going to the
+    /// charset listed first by the server.
+    ///
+    /// Returns `None` if none of the supported charsets is acceptable. A
+    /// server may then respond with `406 Not Acceptable`, or disregard the
+    /// header and use its default charset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use headers::{AcceptCharset, Charset, HeaderMap, HeaderMapExt};
+    ///
+    /// let mut req = HeaderMap::new();
+    /// req.insert("accept-charset", "iso-8859-1, *;q=0.5".parse().unwrap());
+    /// let accept_charset = req.typed_get::<AcceptCharset>().unwrap();
+    ///
+    /// let supported = vec![Charset::UTF_8, Charset::ISO_8859_1];
+    /// assert_eq!(accept_charset.negotiate(supported), Some(Charset::ISO_8859_1));
+    /// ```
+    pub fn negotiate<I>(&self, supported: I) -> Option<Charset>
+    where
+        I: IntoIterator<Item = Charset>,
+    {
+        let prefs = self.iter().collect::<Vec<_>>();
+        let quality_of = |charset: &Charset| {
+            prefs
+                .iter()
+                .find(|pref| pref.value() == charset)
+                .map(QualityValue::quality)
+        };
+        let any = if prefs.is_empty() {
+            Some(Quality::ONE)
+        } else {
+            prefs
+                .iter()
+                .find(|pref| pref.value().is_any())
+                .map(QualityValue::quality)
+        };
+
+        let mut best: Option<(Charset, Quality)> = None;
+        for charset in supported.into_iter().filter(|charset| !charset.is_any()) {
+            let quality = match quality_of(&charset).or(any) {
+                Some(quality) if quality != Quality::ZERO => quality,
+                _ => continue,
+            };
+
+            if best.as_ref().map(|(_, q)| quality > *q).unwrap_or(true) {
+                best = Some((charset, quality));
+            }
+        }
+
+        best.map(|(charset, _)| charset)
+    }
+}
+
+impl FromIterator<QualityValue<Charset>> for AcceptCharset {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = QualityValue<Charset>>,
+    {
+        let flat = iter.into_iter().map(util::fmt).collect();
+        AcceptCharset(flat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    fn accept_charset(s: &str) -> AcceptCharset {
+        test_decode(&[s]).unwrap()
+    }
+
+    fn charset(s: &str) -> Charset {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn decode_rfc_example() {
+        let items = accept_charset("iso-8859-5, unicode-1-1;q=0.8")
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![
+                QualityValue::from(Charset::ISO_8859_5),
+                QualityValue::new(charset("unicode-1-1"), Quality::from_u16(800).unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(test_decode::<AcceptCharset>(&["utf-8;q=x"]), None);
+        assert_eq!(test_decode::<AcceptCharset>(&["utf 8"]), None);
+    }
+
+    #[test]
+    fn encode() {
+        let accept_charset = vec![
+            QualityValue::from(Charset::UTF_8),
+            QualityValue::new(Charset::ANY, Quality::from_u16(100).unwrap()),
+        ]
+        .into_iter()
+        .collect::<AcceptCharset>();
+
+        let headers = test_encode(accept_charset);
+        assert_eq!(headers["accept-charset"], "UTF-8, *; q=0.1");
+    }
+
+    #[test]
+    fn negotiate_listed() {
+        let header = accept_charset("utf-8;q=0.7, iso-8859-1");
+        assert_eq!(
+            header.negotiate(vec![Charset::UTF_8, Charset::ISO_8859_1]),
+            Some(Charset::ISO_8859_1)
+        );
+
+        // Unlisted charsets are not acceptable without `*`.
+        assert_eq!(header.negotiate(vec![Charset::SHIFT_JIS]), None);
+    }
+
+    #[test]
+    fn negotiate_star() {
+        let header = accept_charset("iso-8859-1;q=0.5, *");
+        assert_eq!(
+            header.negotiate(vec![Charset::ISO_8859_1, Charset::UTF_8]),
+            Some(Charset::UTF_8)
+        );
+
+        let header = accept_charset("utf-8;q=0, *");
+        assert_eq!(
+            header.negotiate(vec![Charset::UTF_8, Charset::US_ASCII]),
+            Some(Charset::US_ASCII)
+        );
+
+        let header = accept_charset("utf-8, *;q=0");
+        assert_eq!(header.negotiate(vec![Charset::US_ASCII]), None);
+    }
+
+    #[test]
+    fn negotiate_ties_prefer_server_order() {
+        let header = accept_charset("iso-8859-1, utf-8");
+        assert_eq!(
+            header.negotiate(vec![Charset::UTF_8, Charset::ISO_8859_1]),
+            Some(Charset::UTF_8)
+        );
+    }
+
+    #[test]
+    fn negotiate_empty_accepts_anything() {
+        let header = accept_charset("");
+        assert_eq!(header.negotiate(vec![Charset::UTF_8]), Some(Charset::UTF_8));
+    }
+
+    bench_header!(bench, AcceptCharset, "iso-8859-5, unicode-1-1;q=0.8");
+}
@@ -0,0 +1,294 @@
+use std::iter::FromIterator;
+
+use http::{HeaderName, HeaderValue};
+
+use crate::util::{self, Encoding, FlatCsv, Quality, QualityValue, TryFromValues};
+use crate::{Error, Header};
+
+/// `Accept-Encoding` header, defined in
+/// [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-12.5.3)
+///
+/// The `Accept-Encoding` header field can be used by user agents to
+/// indicate what response content-codings are
+/// acceptable in the response.  An  `identity` token is used as a synonym
+/// for "no encoding" in order to communicate when no encoding is
+/// preferred.
+///
+/// # ABNF
+///
+/// ```text
+/// Accept-Encoding  = #( codings [ weight ] )
+/// codings          = content-coding / "identity" / "*"
+/// ```
+///
+/// # Example values
+/// * `compress, gzip`
+/// * ``
+/// * `*`
+/// * `compress;q=0.5, gzip;q=1`
+/// * `gzip;q=1.0, identity; q=0.5, *;q=0`
+///
+/// # Examples
+///
+/// ```
+/// use headers::{AcceptEncoding, Encoding, Quality, QualityValue};
+///
+/// let accept_enc = vec![
+///     QualityValue::from(Encoding::Brotli),
+///     QualityValue::new(Encoding::Gzip, Quality::from_u16(600).unwrap()),
+///     QualityValue::new(Encoding::EncodingExt("*".to_owned()), Quality::ZERO),
+/// ]
+/// .into_iter()
+/// .collect::<AcceptEncoding>();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct AcceptEncoding(FlatCsv);
+
+impl Header for AcceptEncoding {
+    fn name() -> &'static HeaderName {
+        &::http::header::ACCEPT_ENCODING
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let csv = FlatCsv::try_from_values(values)?;
+        for item in csv.iter().filter(|item| !item.is_empty()) {
+            item.parse::<QualityValue<Encoding>>()?;
+        }
+        Ok(AcceptEncoding(csv))
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(::std::iter::once((&self.0).into()));
+    }
+}
+
+impl AcceptEncoding {
+    /// Iterate the codings of this header, with their weights.
+    pub fn iter(&self) -> impl Iterator<Item = QualityValue<Encoding>> + '_ {
+        self.0
+            .iter()
+            .filter(|item| !item.is_empty())
+            .filter_map(|item| item.parse().ok())
+    }
+
+    /// Select the preferred coding from the codings the server supports.
+    ///
+    /// Each supported coding is weighted by its entry in this header, or by
+    /// `*` if it isn't listed. Codings that are neither listed nor matched by
+    /// `*` are not acceptable. The coding with the highest weight wins, with
+    /// ties going to the coding listed first by the server.
+    ///
+    /// `identity` (no encoding) is always considered after the supported
+    /// codings, and is acceptable unless excluded with `identity;q=0`, or
+    /// with `*;q=0` without a more specific `identity` entry.
+    ///
+    /// Returns `None` if nothing is acceptable, not even `identity`, which
+    /// usually means a `406 Not Acceptable` response.
+    ///
+    /// Note that a request without an `Accept-Encoding` header at all means
+    /// any coding is acceptable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    ///
+    /// use headers::{AcceptEncoding, ContentEncoding, Encoding, HeaderMap, HeaderMapExt};
+    ///
+    /// let mut req = HeaderMap::new();
+    /// req.insert("accept-encoding", "gzip, br;q=0.9, *;q=0".parse().unwrap());
+    /// let accept_enc = req.typed_get::<AcceptEncoding>().unwrap();
+    ///
+    /// let supported = vec![Encoding::Zstd, Encoding::Brotli, Encoding::Gzip];
+    /// let mut res = HeaderMap::new();
+    /// match accept_enc.negotiate(supported) {
+    ///     Some(Encoding::Identity) => (),
+    ///     Some(coding) => res.typed_insert(ContentEncoding::try_from(coding).unwrap()),
+    ///     None => unreachable!("would be 406 Not Acceptable"),
+    /// }
+    ///
+    /// assert!(res.typed_get::<ContentEncoding>().unwrap().contains("gzip"));
+    /// ```
+    pub fn negotiate<I>(&self, supported: I) -> Option<Encoding>
+    where
+        I: IntoIterator<Item = Encoding>,
+    {
+        let prefs = self.iter().collect::<Vec<_>>();
+        let quality_of = |coding: &Encoding| {
+            prefs
+                .iter()
+                .find(|pref| pref.value() == coding)
+                .map(QualityValue::quality)
+        };
+        let any = prefs
+            .iter()
+            .find(|pref| pref.value().is_any())
+            .map(QualityValue::quality);
+
+        let mut best: Option<(Encoding, Quality)> = None;
+        let mut candidates = supported
+            .into_iter()
+            .filter(|coding| !coding.is_any())
+            .collect::<Vec<_>>();
+        if !candidates.contains(&Encoding::Identity) {
+            candidates.push(Encoding::Identity);
+        }
+
+        for coding in candidates {
+            let quality = match quality_of(&coding).or(any) {
+                Some(quality) => quality,
+                None if coding == Encoding::Identity => Quality::ONE,
+                None => continue,
+            };
+
+            if quality == Quality::ZERO {
+                continue;
+            }
+
+            if best.as_ref().map(|(_, q)| quality > *q).unwrap_or(true) {
+                best = Some((coding, quality));
+            }
+        }
+
+        best.map(|(coding, _)| coding)
+    }
+}
+
+impl FromIterator<QualityValue<Encoding>> for AcceptEncoding {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = QualityValue<Encoding>>,
+    {
+        let flat = iter.into_iter().map(util::fmt).collect();
+        AcceptEncoding(flat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    fn accept_encoding(s: &str) -> AcceptEncoding {
+        test_decode(&[s]).unwrap()
+    }
+
+    fn q(thousandths: u16) -> Quality {
+        Quality::from_u16(thousandths).unwrap()
+    }
+
+    fn all() -> Vec<Encoding> {
+        vec![Encoding::Zstd, Encoding::Brotli, Encoding::Gzip]
+    }
+
+    #[test]
+    fn decode_rfc_examples() {
+        let items = accept_encoding("compress, gzip").iter().collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![
+                QualityValue::from(Encoding::Compress),
+                QualityValue::from(Encoding::Gzip),
+            ]
+        );
+
+        assert_eq!(accept_encoding("").iter().count(), 0);
+
+        let items = accept_encoding("gzip;q=1.0, identity; q=0.5, *;q=0")
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![
+                QualityValue::from(Encoding::Gzip),
+                QualityValue::new(Encoding::Identity, q(500)),
+                QualityValue::new(Encoding::EncodingExt("*".to_owned()), Quality::ZERO),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(test_decode::<AcceptEncoding>(&["gzip;q=nope"]), None);
+    }
+
+    #[test]
+    fn encode() {
+        let accept_enc = vec![
+            QualityValue::from(Encoding::Brotli),
+            QualityValue::new(Encoding::Gzip, q(800)),
+        ]
+        .into_iter()
+        .collect::<AcceptEncoding>();
+
+        let headers = test_encode(accept_enc);
+        assert_eq!(headers["accept-encoding"], "br, gzip; q=0.8");
+    }
+
+    #[test]
+    fn negotiate_by_quality() {
+        let accept_enc = accept_encoding("gzip;q=0.5, br, zstd;q=0.8");
+        assert_eq!(accept_enc.negotiate(all()), Some(Encoding::Brotli));
+    }
+
+    #[test]
+    fn negotiate_ties_prefer_server_order() {
+        let accept_enc = accept_encoding("gzip, deflate, br");
+        assert_eq!(accept_enc.negotiate(all()), Some(Encoding::Brotli));
+        assert_eq!(
+            accept_enc.negotiate(vec![Encoding::Gzip, Encoding::Brotli]),
+            Some(Encoding::Gzip)
+        );
+    }
+
+    #[test]
+    fn negotiate_unlisted_falls_back_to_identity() {
+        let accept_enc = accept_encoding("deflate");
+        assert_eq!(accept_enc.negotiate(all()), Some(Encoding::Identity));
+
+        // An empty value means only identity is acceptable.
+        let accept_enc = accept_encoding("");
+        assert_eq!(accept_enc.negotiate(all()), Some(Encoding::Identity));
+    }
+
+    #[test]
+    fn negotiate_star() {
+        let accept_enc = accept_encoding("*");
+        assert_eq!(accept_enc.negotiate(all()), Some(Encoding::Zstd));
+
+        let accept_enc = accept_encoding("gzip;q=0.5, *;q=0.8");
+        assert_eq!(
+            accept_enc.negotiate(vec![Encoding::Gzip, Encoding::Brotli]),
+            Some(Encoding::Brotli)
+        );
+
+        let accept_enc = accept_encoding("br;q=0, *");
+        assert_eq!(
+            accept_enc.negotiate(vec![Encoding::Brotli, Encoding::Gzip]),
+            Some(Encoding::Gzip)
+        );
+    }
+
+    #[test]
+    fn negotiate_identity_excluded() {
+        let accept_enc = accept_encoding("deflate, identity;q=0");
+        assert_eq!(accept_enc.negotiate(all()), None);
+
+        let accept_enc = accept_encoding("deflate, *;q=0");
+        assert_eq!(accept_enc.negotiate(all()), None);
+
+        let accept_enc = accept_encoding("identity;q=0.1, *;q=0");
+        assert_eq!(accept_enc.negotiate(all()), Some(Encoding::Identity));
+
+        let accept_enc = accept_encoding("gzip, identity;q=0");
+        assert_eq!(accept_enc.negotiate(all()), Some(Encoding::Gzip));
+    }
+
+    #[test]
+    fn negotiate_identity_preferred() {
+        let accept_enc = accept_encoding("gzip;q=0.5, identity");
+        assert_eq!(accept_enc.negotiate(all()), Some(Encoding::Identity));
+    }
+
+    bench_header!(bench, AcceptEncoding, "gzip, deflate, br;q=0.9, *;q=0.1");
+}
@@ -0,0 +1,369 @@
+use std::cmp::Reverse;
+use std::iter::FromIterator;
+
+use http::{HeaderName, HeaderValue};
+
+use crate::util::{self, FlatCsv, LanguageRange, Quality, QualityValue, TryFromValues};
+use crate::{Error, Header};
+
+/// `Accept-Language` header, defined in
+/// [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-12.5.4)
+///
+/// The `Accept-Language` header field can be used by user agents to
+/// indicate the set of natural languages that are preferred in the
+/// response.
+///
+/// Language ranges can be matched against the locales a server has
+/// available, using the
+/// [RFC4647](https://www.rfc-editor.org/rfc/rfc4647#section-3) matching
+/// schemes: basic filtering, extended filtering, and lookup.
+///
+/// # ABNF
+///
+/// ```text
+/// Accept-Language = #( language-range [ weight ] )
+/// language-range  = <language-range, see [RFC4647], Section 2.1>
+/// ```
+///
+/// # Example values
+/// * `da, en-gb;q=0.8, en;q=0.7`
+/// * `en-us;q=1.0, en;q=0.5, fr`
+///
+/// # Examples
+///
+/// ```
+/// use headers::{AcceptLanguage, LanguageRange, Quality, QualityValue};
+///
+/// let accept_lang = vec![
+///     QualityValue::from("da".parse::<LanguageRange>().unwrap()),
+///     QualityValue::new("en-GB".parse().unwrap(), Quality::from_u16(800).unwrap()),
+///     QualityValue::new("en".parse().unwrap(), Quality::from_u16(700).unwrap()),
+/// ]
+/// .into_iter()
+/// .collect::<AcceptLanguage>();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct AcceptLanguage(FlatCsv);
+
+impl Header for AcceptLanguage {
+    fn name() -> &'static HeaderName {
+        &::http::header::ACCEPT_LANGUAGE
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let csv = FlatCsv::try_from_values(values)?;
+        for item in csv.iter().filter(|item| !item.is_empty()) {
+            item.parse::<QualityValue<LanguageRange>>()?;
+        }
+        Ok(AcceptLanguage(csv))
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(::std::iter::once((&self.0).into()));
+    }
+}
+
+impl AcceptLanguage {
+    /// Iterate the language ranges of this header, with their weights.
+    pub fn iter(&self) -> impl Iterator<Item = QualityValue<LanguageRange>> + '_ {
+        self.0
+            .iter()
+            .filter(|item| !item.is_empty())
+            .filter_map(|item| item.parse().ok())
+    }
+
+    /// Filter the available language tags, using
+    /// [basic filtering](https://www.rfc-editor.org/rfc/rfc4647#section-3.3.1).
+    ///
+    /// Returns every available tag matched by a range, ordered by the weight
+    /// of the range that matched it, and then by the order of `available`.
+    /// Tags matched by a range with `q=0` are never returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use headers::{AcceptLanguage, HeaderMap, HeaderMapExt};
+    ///
+    /// let mut req = HeaderMap::new();
+    /// req.insert("accept-language", "fr;q=0.5, de-de".parse().unwrap());
+    /// let accept_lang = req.typed_get::<AcceptLanguage>().unwrap();
+    ///
+    /// let available = ["en", "fr-CA", "de-DE-1996", "de-Latn-DE"];
+    /// assert_eq!(accept_lang.filter_basic(&available), [&"de-DE-1996", &"fr-CA"]);
+    /// ```
+    pub fn filter_basic<'a, T, I>(&self, available: I) -> Vec<&'a T>
+    where
+        T: AsRef<str> + ?Sized + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.filter(available, LanguageRange::matches_basic)
+    }
+
+    /// Filter the available language tags, using
+    /// [extended filtering](https://www.rfc-editor.org/rfc/rfc4647#section-3.3.2).
+    ///
+    /// This is like [`filter_basic`](AcceptLanguage::filter_basic), except
+    /// that ranges can match tags with extra subtags in between, such as
+    /// `de-DE` matching `de-Latn-DE`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use headers::{AcceptLanguage, HeaderMap, HeaderMapExt};
+    ///
+    /// let mut req = HeaderMap::new();
+    /// req.insert("accept-language", "de-*-DE, *-CH;q=0.5".parse().unwrap());
+    /// let accept_lang = req.typed_get::<AcceptLanguage>().unwrap();
+    ///
+    /// let available = ["fr-CH", "de-Latn-DE", "de-AT"];
+    /// assert_eq!(accept_lang.filter_extended(&available), [&"de-Latn-DE", &"fr-CH"]);
+    /// ```
+    pub fn filter_extended<'a, T, I>(&self, available: I) -> Vec<&'a T>
+    where
+        T: AsRef<str> + ?Sized + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.filter(available, LanguageRange::matches_extended)
+    }
+
+    /// Find the single best available language tag, using
+    /// [lookup](https://www.rfc-editor.org/rfc/rfc4647#section-3.4).
+    ///
+    /// Each range, in order of weight, is compared to the available tags,
+    /// removing subtags from the end of the range until a tag is equal to
+    /// it. For example, `de-CH-1996` tries `de-CH-1996`, `de-CH` and then
+    /// `de`. The wildcard `*` is skipped, as is any tag matched by a range
+    /// with `q=0`.
+    ///
+    /// Returns `None` if nothing matches, in which case the caller should
+    /// use its default locale.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use headers::{AcceptLanguage, HeaderMap, HeaderMapExt};
+    ///
+    /// let mut req = HeaderMap::new();
+    /// req.insert("accept-language", "de-CH-1996, fr;q=0.8".parse().unwrap());
+    /// let accept_lang = req.typed_get::<AcceptLanguage>().unwrap();
+    ///
+    /// assert_eq!(accept_lang.lookup(&["en", "fr", "de"]), Some(&"de"));
+    /// assert_eq!(accept_lang.lookup(&["en", "it"]), None);
+    /// ```
+    pub fn lookup<'a, T, I>(&self, available: I) -> Option<&'a T>
+    where
+        T: AsRef<str> + ?Sized + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        let (prefs, excluded) = self.priority_list();
+        let available = available
+            .into_iter()
+            .filter(|tag| !excluded.iter().any(|ex| ex.matches_basic(tag.as_ref())))
+            .collect::<Vec<_>>();
+
+        for range in prefs.iter().filter(|range| !range.is_wildcard()) {
+            // Wildcards other than the primary subtag are ignored by lookup.
+            let mut subtags = range
+                .as_str()
+                .split('-')
+                .filter(|sub| *sub != "*")
+                .collect::<Vec<_>>();
+
+            while !subtags.is_empty() {
+                let candidate = subtags.join("-");
+                if let Some(tag) = available
+                    .iter()
+                    .find(|tag| tag.as_ref().eq_ignore_ascii_case(&candidate))
+                {
+                    return Some(*tag);
+                }
+
+                subtags.pop();
+                // A singleton can't be the last subtag, so drop it as well.
+                if subtags.last().map(|sub| sub.len() == 1).unwrap_or(false) {
+                    subtags.pop();
+                }
+            }
+        }
+
+        None
+    }
+
+    fn filter<'a, T, I, F>(&self, available: I, matches: F) -> Vec<&'a T>
+    where
+        T: AsRef<str> + ?Sized + 'a,
+        I: IntoIterator<Item = &'a T>,
+        F: Fn(&LanguageRange, &str) -> bool,
+    {
+        let (prefs, excluded) = self.priority_list();
+        let available = available
+            .into_iter()
+            .filter(|tag| !excluded.iter().any(|ex| matches(ex, tag.as_ref())))
+            .collect::<Vec<_>>();
+
+        let mut matched = vec![false; available.len()];
+        let mut filtered = Vec::new();
+        for range in &prefs {
+            for (idx, tag) in available.iter().enumerate() {
+                if !matched[idx] && matches(range, tag.as_ref()) {
+                    matched[idx] = true;
+                    filtered.push(*tag);
+                }
+            }
+        }
+        filtered
+    }
+
+    /// Returns the acceptable ranges sorted by weight, and the ranges
+    /// excluded with `q=0`.
+    fn priority_list(&self) -> (Vec<LanguageRange>, Vec<LanguageRange>) {
+        let mut prefs = Vec::new();
+        let mut excluded = Vec::new();
+        for item in self.iter() {
+            if item.quality() != Quality::ZERO {
+                prefs.push(item);
+            } else if !item.value().is_wildcard() {
+                excluded.push(item.into_value());
+            }
+        }
+
+        // A stable sort keeps the header order for equal weights.
+        prefs.sort_by_key(|item| Reverse(item.quality()));
+        let prefs = prefs.into_iter().map(QualityValue::into_value).collect();
+        (prefs, excluded)
+    }
+}
+
+impl FromIterator<QualityValue<LanguageRange>> for AcceptLanguage {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = QualityValue<LanguageRange>>,
+    {
+        let flat = iter.into_iter().map(util::fmt).collect();
+        AcceptLanguage(flat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    fn accept_language(s: &str) -> AcceptLanguage {
+        test_decode(&[s]).unwrap()
+    }
+
+    fn range(s: &str) -> LanguageRange {
+        s.parse().unwrap()
+    }
+
+    fn q(thousandths: u16) -> Quality {
+        Quality::from_u16(thousandths).unwrap()
+    }
+
+    #[test]
+    fn decode_rfc_example() {
+        let items = accept_language("da, en-gb;q=0.8, en;q=0.7")
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![
+                QualityValue::from(range("da")),
+                QualityValue::new(range("en-GB"), q(800)),
+                QualityValue::new(range("en"), q(700)),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(test_decode::<AcceptLanguage>(&["en_US"]), None);
+        assert_eq!(test_decode::<AcceptLanguage>(&["en;q=2"]), None);
+    }
+
+    #[test]
+    fn encode() {
+        let accept_lang = vec![
+            QualityValue::from(range("en-US")),
+            QualityValue::new(range("en"), q(500)),
+            QualityValue::from(range("fr")),
+        ]
+        .into_iter()
+        .collect::<AcceptLanguage>();
+
+        let headers = test_encode(accept_lang);
+        assert_eq!(headers["accept-language"], "en-US, en; q=0.5, fr");
+    }
+
+    #[test]
+    fn filter_basic_orders_by_weight() {
+        let accept_lang = accept_language("en;q=0.5, de, fr;q=0.8");
+        let available = ["en-GB", "fr", "de-AT", "it", "de"];
+        assert_eq!(
+            accept_lang.filter_basic(&available),
+            [&"de-AT", &"de", &"fr", &"en-GB"]
+        );
+    }
+
+    #[test]
+    fn filter_basic_exclusions() {
+        let accept_lang = accept_language("*, de-CH;q=0");
+        let available = ["de", "de-CH", "de-CH-1996", "fr"];
+        assert_eq!(accept_lang.filter_basic(&available), [&"de", &"fr"]);
+
+        // `*;q=0` excludes nothing by itself, it just isn't acceptable.
+        let accept_lang = accept_language("fr, *;q=0");
+        assert_eq!(accept_lang.filter_basic(&available), [&"fr"]);
+    }
+
+    #[test]
+    fn filter_extended_skips_subtags() {
+        let accept_lang = accept_language("de-DE");
+        let available = ["de-Latn-DE", "de-DE", "de-x-DE", "de"];
+        assert_eq!(
+            accept_lang.filter_extended(&available),
+            [&"de-Latn-DE", &"de-DE"]
+        );
+        assert_eq!(accept_lang.filter_basic(&available), [&"de-DE"]);
+    }
+
+    #[test]
+    fn filter_owned_strings() {
+        let accept_lang = accept_language("en");
+        let available = vec!["en-US".to_owned(), "fr".to_owned()];
+        assert_eq!(accept_lang.filter_basic(&available), [&available[0]]);
+    }
+
+    #[test]
+    fn lookup_truncates() {
+        let accept_lang = accept_language("de-CH-1996");
+        assert_eq!(accept_lang.lookup(&["de-CH", "de"]), Some(&"de-CH"));
+        assert_eq!(accept_lang.lookup(&["de", "en"]), Some(&"de"));
+        assert_eq!(accept_lang.lookup(&["de-CH-1996-x"]), None);
+    }
+
+    #[test]
+    fn lookup_drops_trailing_singleton() {
+        let accept_lang = accept_language("zh-Hant-CN-x-private1-private2");
+        assert_eq!(
+            accept_lang.lookup(&["zh-Hant-CN-x", "zh-Hant-CN"]),
+            Some(&"zh-Hant-CN")
+        );
+    }
+
+    #[test]
+    fn lookup_by_weight() {
+        let accept_lang = accept_language("fr-CA;q=0.5, en-US");
+        assert_eq!(accept_lang.lookup(&["fr", "en"]), Some(&"en"));
+        assert_eq!(accept_lang.lookup(&["fr", "it"]), Some(&"fr"));
+    }
+
+    #[test]
+    fn lookup_skips_wildcard_and_exclusions() {
+        let accept_lang = accept_language("*, en-US, en;q=0");
+        assert_eq!(accept_lang.lookup(&["en", "fr"]), None);
+    }
+
+    bench_header!(bench, AcceptLanguage, "en-us;q=1.0, en;q=0.5, fr");
+}
@@ -0,0 +1,292 @@
+use http::{HeaderName, HeaderValue};
+
+use super::authorization::{
+    find_auth_param, parse_auth_param_list, set_auth_param, AuthParam, Digest, FmtAuthParams, Qop,
+};
+use crate::util;
+use crate::{Error, Header};
+
+static AUTHENTICATION_INFO: HeaderName = HeaderName::from_static("authentication-info");
+
+/// `Authentication-Info` header, defined in
+/// [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-11.6.3)
+///
+/// The `Authentication-Info` header field is sent by a server to
+/// communicate some information regarding the successful authentication
+/// of the client's request. Its parameters depend on the authentication
+/// scheme. For Digest, as defined in
+/// [RFC7616](https://www.rfc-editor.org/rfc/rfc7616#section-3.5), they are:
+///
+/// * `nextnonce`, the nonce the client should use for its next request.
+/// * `rspauth`, proving that the server knows the password too.
+/// * `qop`, `cnonce` and `nc`, repeated from the credentials.
+///
+/// # ABNF
+///
+/// ```text
+/// Authentication-Info = #auth-param
+/// ```
+///
+/// # Example values
+///
+/// * `nextnonce="47364c23432d2e131a5fb210812c"`
+/// * `rspauth="6629fae49393a05397450978507c4ef1", cnonce="0a4f113b", nc=00000001, qop=auth`
+///
+/// # Example
+///
+/// ```
+/// use headers::AuthenticationInfo;
+///
+/// let info = AuthenticationInfo::new().with_nextnonce("47364c23432d2e131a5fb210812c");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuthenticationInfo {
+    params: Vec<AuthParam>,
+}
+
+impl Header for AuthenticationInfo {
+    fn name() -> &'static HeaderName {
+        &AUTHENTICATION_INFO
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let mut params = Vec::new();
+        for value in values {
+            let value = std::str::from_utf8(value.as_bytes()).map_err(|_| Error::invalid())?;
+            parse_auth_param_list(value, &mut params).ok_or_else(Error::invalid)?;
+        }
+        Ok(AuthenticationInfo { params })
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(::std::iter::once(util::fmt(FmtAuthParams(&self.params))));
+    }
+}
+
+impl AuthenticationInfo {
+    /// Create an `Authentication-Info` header without any parameters.
+    pub fn new() -> Self {
+        AuthenticationInfo::default()
+    }
+
+    /// Create an `Authentication-Info` header for credentials of Digest
+    /// Authentication, which the server verified.
+    ///
+    /// It has the `rspauth`, `qop`, `cnonce` and `nc` parameters, unless
+    /// the credentials have no quality of protection. The body is the body
+    /// of the response, which is only used with `auth-int`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use headers::authorization::{Digest, DigestChallenge};
+    /// use headers::AuthenticationInfo;
+    /// use http::Method;
+    ///
+    /// # let challenge = DigestChallenge::new("api", "abc");
+    /// # let digest = Digest::new(&challenge, "Mufasa", "/", "xyz")
+    /// #     .sign(&Method::GET, "Circle of Life", b"");
+    /// if digest.verify(&Method::GET, "Mufasa", "Circle of Life", b"") {
+    ///     let info = AuthenticationInfo::for_digest(&digest, "Mufasa", "Circle of Life", b"")
+    ///         .with_nextnonce("def");
+    /// }
+    /// ```
+    pub fn for_digest(digest: &Digest, username: &str, password: &str, body: &[u8]) -> Self {
+        let mut info = AuthenticationInfo::new();
+        if let (Some(qop), Some(cnonce), Some(nc)) = (digest.qop(), digest.cnonce(), digest.nc()) {
+            info = info
+                .with_rspauth(&digest.rspauth(username, password, body))
+                .with_cnonce(cnonce)
+                .with_nc(nc)
+                .with_qop(qop);
+        }
+        info
+    }
+
+    /// Get the value of a parameter.
+    ///
+    /// Parameter names are compared case-insensitively.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        find_auth_param(&self.params, name)
+    }
+
+    /// Iterate the parameters, as name and value pairs.
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params
+            .iter()
+            .map(|param| (param.name.as_str(), param.value.as_str()))
+    }
+
+    /// Get the nonce the client should use for its next request.
+    pub fn nextnonce(&self) -> Option<&str> {
+        self.param("nextnonce")
+    }
+
+    /// Get the quality of protection of the credentials.
+    pub fn qop(&self) -> Option<Qop> {
+        self.param("qop").and_then(|qop| qop.parse().ok())
+    }
+
+    /// Get the `rspauth`, a hash in hexadecimal.
+    ///
+    /// See [`Digest::rspauth`].
+    pub fn rspauth(&self) -> Option<&str> {
+        self.param("rspauth")
+    }
+
+    /// Get the nonce chosen by the client.
+    pub fn cnonce(&self) -> Option<&str> {
+        self.param("cnonce")
+    }
+
+    /// Get the number of requests the client sent with its nonce.
+    pub fn nc(&self) -> Option<u32> {
+        self.param("nc")
+            .filter(|nc| nc.len() == 8)
+            .and_then(|nc| u32::from_str_radix(nc, 16).ok())
+    }
+
+    /// Set a parameter, replacing any previous value. The value is sent as
+    /// a quoted string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not a token, or the value contains control
+    /// characters.
+    pub fn with_param(mut self, name: &str, value: &str) -> Self {
+        set_auth_param(&mut self.params, name, value, true);
+        self
+    }
+
+    /// Set the nonce the client should use for its next request.
+    pub fn with_nextnonce(self, nextnonce: &str) -> Self {
+        self.with_param("nextnonce", nextnonce)
+    }
+
+    /// Set the quality of protection of the credentials.
+    pub fn with_qop(mut self, qop: Qop) -> Self {
+        set_auth_param(&mut self.params, "qop", &qop.to_string(), false);
+        self
+    }
+
+    /// Set the `rspauth`.
+    pub fn with_rspauth(self, rspauth: &str) -> Self {
+        self.with_param("rspauth", rspauth)
+    }
+
+    /// Set the nonce chosen by the client.
+    pub fn with_cnonce(self, cnonce: &str) -> Self {
+        self.with_param("cnonce", cnonce)
+    }
+
+    /// Set the number of requests the client sent with its nonce.
+    pub fn with_nc(mut self, nc: u32) -> Self {
+        set_auth_param(&mut self.params, "nc", &format!("{:08x}", nc), false);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+    use md5::{Digest as _, Md5};
+
+    use super::super::authorization::{Authorization, Digest};
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    fn md5_hex(data: &str) -> String {
+        Md5::digest(data)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    #[test]
+    fn decode() {
+        let info = test_decode::<AuthenticationInfo>(&[
+            r#"rspauth="6629fae49393a05397450978507c4ef1", cnonce="0a4f113b""#,
+            r#"nc=00000001, qop=auth,, nextnonce="47364c23432d2e131a5fb210812c""#,
+        ])
+        .unwrap();
+        assert_eq!(info.rspauth(), Some("6629fae49393a05397450978507c4ef1"));
+        assert_eq!(info.cnonce(), Some("0a4f113b"));
+        assert_eq!(info.nc(), Some(1));
+        assert_eq!(info.qop(), Some(Qop::Auth));
+        assert_eq!(info.nextnonce(), Some("47364c23432d2e131a5fb210812c"));
+        assert_eq!(info.params().count(), 5);
+
+        let info = test_decode::<AuthenticationInfo>(&[""]).unwrap();
+        assert_eq!(info, AuthenticationInfo::new());
+    }
+
+    #[test]
+    fn decode_invalid() {
+        for s in &[
+            "nextnonce",
+            "nextnonce=\"a\" rspauth=\"b\"",
+            "nextnonce=\"a\", nextnonce=\"b\"",
+            "Digest nextnonce=\"a\"",
+        ] {
+            assert_eq!(
+                test_decode::<AuthenticationInfo>(&[s]),
+                None,
+                "{:?} should be invalid",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn encode() {
+        let info = AuthenticationInfo::new()
+            .with_nextnonce("abc")
+            .with_qop(Qop::AuthInt)
+            .with_nc(255)
+            .with_nextnonce("def");
+        assert_eq!(
+            test_encode(info)["authentication-info"],
+            r#"nextnonce="def", qop=auth-int, nc=000000ff"#
+        );
+
+        assert_eq!(
+            test_encode(AuthenticationInfo::new())["authentication-info"],
+            ""
+        );
+    }
+
+    #[test]
+    fn for_digest() {
+        let auth = test_decode::<Authorization<Digest>>(&[
+            "Digest username=\"Mufasa\", realm=\"testrealm@host.com\", \
+             nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", uri=\"/dir/index.html\", \
+             qop=auth, nc=00000001, cnonce=\"0a4f113b\", \
+             response=\"6629fae49393a05397450978507c4ef1\"",
+        ])
+        .unwrap();
+        assert!(auth.0.verify(&Method::GET, "Mufasa", "Circle Of Life", b""));
+
+        let ha1 = md5_hex("Mufasa:testrealm@host.com:Circle Of Life");
+        let ha2 = md5_hex(":/dir/index.html");
+        let rspauth = md5_hex(&format!(
+            "{}:dcd98b7102dd2f0e8b11d0f600bfb0c093:00000001:0a4f113b:auth:{}",
+            ha1, ha2
+        ));
+
+        let info = AuthenticationInfo::for_digest(&auth.0, "Mufasa", "Circle Of Life", b"");
+        assert_eq!(info.rspauth(), Some(rspauth.as_str()));
+        assert_eq!(
+            test_encode(info)["authentication-info"],
+            format!(
+                r#"rspauth="{}", cnonce="0a4f113b", nc=00000001, qop=auth"#,
+                rspauth
+            )
+        );
+    }
+
+    bench_header!(
+        bench,
+        AuthenticationInfo,
+        r#"rspauth="6629fae49393a05397450978507c4ef1", cnonce="0a4f113b", nc=00000001, qop=auth"#
+    );
+}
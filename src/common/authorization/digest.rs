@@ -0,0 +1,848 @@
+use std::fmt;
+use std::str::FromStr;
+
+use http::{HeaderValue, Method};
+use md5::Md5;
+use sha2::{Digest as _, Sha256};
+
+use super::{find_auth_param, parse_auth_params, AuthParam, Challenge, Credentials, FmtAuthParams};
+use crate::util;
+
+/// Credential holder for Digest Authentication, defined in
+/// [RFC7616](https://www.rfc-editor.org/rfc/rfc7616#section-3.4)
+///
+/// The client computes the `response` from the password and a challenge
+/// of the server, so the password is never sent. The server computes the
+/// expected `response` the same way, to verify it.
+///
+/// # Example
+///
+/// ```
+/// use headers::authorization::{Digest, DigestChallenge};
+/// use headers::Authorization;
+/// use http::Method;
+///
+/// // Server
+/// let challenge = DigestChallenge::new("api@example.org", "7ypf/xlj9XXwfDPEoM4URrv");
+///
+/// // Client
+/// let digest = Digest::new(&challenge, "Mufasa", "/dir/index.html", "f2/wE4q74E6z")
+///     .sign(&Method::GET, "Circle of Life", b"");
+/// let auth = Authorization(digest);
+///
+/// // Server
+/// assert!(auth.0.verify(&Method::GET, "Mufasa", "Circle of Life", b""));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Digest {
+    username: String,
+    realm: String,
+    nonce: String,
+    uri: String,
+    response: String,
+    algorithm: Option<DigestAlgorithm>,
+    qop: Option<Qop>,
+    nc: Option<u32>,
+    cnonce: Option<String>,
+    opaque: Option<String>,
+    userhash: bool,
+}
+
+/// A challenge for Digest Authentication, defined in
+/// [RFC7616](https://www.rfc-editor.org/rfc/rfc7616#section-3.3)
+///
+/// It is sent in a `WWW-Authenticate` or `Proxy-Authenticate` header, by
+/// converting it into a [`Challenge`].
+///
+/// # Example
+///
+/// ```
+/// use headers::authorization::{DigestAlgorithm, DigestChallenge};
+/// use headers::WwwAuthenticate;
+///
+/// let challenge = DigestChallenge::new("api@example.org", "7ypf/xlj9XXwfDPEoM4URrv")
+///     .with_algorithm(DigestAlgorithm::Sha256)
+///     .with_opaque("FQhe/qaU925kfnzjCev0ciny7QMk");
+/// let www_auth = WwwAuthenticate::new(challenge.into());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DigestChallenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    stale: bool,
+    algorithm: Option<DigestAlgorithm>,
+    qop: Vec<Qop>,
+    userhash: bool,
+}
+
+/// The hash algorithm of Digest Authentication.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DigestAlgorithm {
+    /// `MD5`, the default when no algorithm is given.
+    Md5,
+    /// `MD5-sess`
+    Md5Sess,
+    /// `SHA-256`
+    Sha256,
+    /// `SHA-256-sess`
+    Sha256Sess,
+}
+
+/// The quality of protection of Digest Authentication.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Qop {
+    /// `auth`, authentication only.
+    Auth,
+    /// `auth-int`, authentication with integrity protection of the body.
+    AuthInt,
+}
+
+impl Digest {
+    /// Create credentials answering a challenge, for a request to `uri`.
+    ///
+    /// `cnonce` should be a random value chosen by the client. The
+    /// credentials must be signed with [`sign`](Digest::sign) before being
+    /// sent.
+    ///
+    /// `auth` is preferred over `auth-int` if the challenge offers both.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the username, uri or cnonce contains control characters.
+    pub fn new(challenge: &DigestChallenge, username: &str, uri: &str, cnonce: &str) -> Self {
+        assert!(is_param_value(username), "invalid username");
+        assert!(is_param_value(uri), "invalid uri");
+        assert!(is_param_value(cnonce), "invalid cnonce");
+
+        let qop = if challenge.qop.contains(&Qop::Auth) {
+            Some(Qop::Auth)
+        } else if challenge.qop.contains(&Qop::AuthInt) {
+            Some(Qop::AuthInt)
+        } else {
+            None
+        };
+
+        Digest {
+            username: username.to_owned(),
+            realm: challenge.realm.clone(),
+            nonce: challenge.nonce.clone(),
+            uri: uri.to_owned(),
+            response: String::new(),
+            algorithm: challenge.algorithm,
+            qop,
+            nc: qop.map(|_| 1),
+            cnonce: qop.map(|_| cnonce.to_owned()),
+            opaque: challenge.opaque.clone(),
+            userhash: challenge.userhash,
+        }
+    }
+
+    /// Set the number of requests sent with the nonce of the challenge,
+    /// including this one. It starts at 1.
+    pub fn with_nc(mut self, nc: u32) -> Self {
+        if self.qop.is_some() {
+            self.nc = Some(nc);
+        }
+        self
+    }
+
+    /// Compute the `response` of these credentials, for a request with the
+    /// given method and body.
+    ///
+    /// The body is only used with the `auth-int` quality of protection. If
+    /// the challenge asked for a hashed username, the username is replaced
+    /// with its hash.
+    pub fn sign(mut self, method: &Method, password: &str, body: &[u8]) -> Self {
+        let username = self.username.clone();
+        self.response = self.expected_response(method, &username, password, body);
+        if self.userhash {
+            self.username = self.hash_username(&username);
+        }
+        self
+    }
+
+    /// Compute the `response` that the client should have sent, given the
+    /// password of the user.
+    ///
+    /// `username` is the actual name of the user, which differs from
+    /// [`username`](Digest::username) when it is hashed.
+    pub fn expected_response(
+        &self,
+        method: &Method,
+        username: &str,
+        password: &str,
+        body: &[u8],
+    ) -> String {
+        self.compute_response(method.as_str(), username, password, body)
+    }
+
+    /// Compute the `rspauth` parameter of the `Authentication-Info` header,
+    /// which proves to the client that the server knows the password too.
+    ///
+    /// The body is the body of the response, which is only used with the
+    /// `auth-int` quality of protection.
+    pub fn rspauth(&self, username: &str, password: &str, body: &[u8]) -> String {
+        self.compute_response("", username, password, body)
+    }
+
+    fn compute_response(
+        &self,
+        method: &str,
+        username: &str,
+        password: &str,
+        body: &[u8],
+    ) -> String {
+        let algorithm = self.algorithm();
+        let cnonce = self.cnonce.as_deref().unwrap_or("");
+
+        let mut ha1 = algorithm.hash(format!("{}:{}:{}", username, self.realm, password));
+        if algorithm.is_session() {
+            ha1 = algorithm.hash(format!("{}:{}:{}", ha1, self.nonce, cnonce));
+        }
+
+        let a2 = match self.qop {
+            Some(Qop::AuthInt) => format!("{}:{}:{}", method, self.uri, algorithm.hash(body)),
+            _ => format!("{}:{}", method, self.uri),
+        };
+        let ha2 = algorithm.hash(a2);
+
+        match self.qop {
+            Some(qop) => algorithm.hash(format!(
+                "{}:{}:{:08x}:{}:{}:{}",
+                ha1,
+                self.nonce,
+                self.nc.unwrap_or(0),
+                cnonce,
+                qop,
+                ha2
+            )),
+            None => algorithm.hash(format!("{}:{}:{}", ha1, self.nonce, ha2)),
+        }
+    }
+
+    /// Check if the `response` of these credentials is the expected one.
+    ///
+    /// The server must also check that the nonce is one it issued and is
+    /// still valid, and that the uri matches the request.
+    pub fn verify(&self, method: &Method, username: &str, password: &str, body: &[u8]) -> bool {
+        let expected = self.expected_response(method, username, password, body);
+        constant_time_eq(expected.as_bytes(), self.response.as_bytes())
+    }
+
+    /// Check if these credentials are for the given user, comparing the
+    /// hash of the username if it is hashed.
+    pub fn is_username(&self, username: &str) -> bool {
+        if self.userhash {
+            self.username
+                .eq_ignore_ascii_case(&self.hash_username(username))
+        } else {
+            self.username == username
+        }
+    }
+
+    /// View the username, which is hashed if [`userhash`](Digest::userhash)
+    /// is true.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// View the protection space.
+    pub fn realm(&self) -> &str {
+        &self.realm
+    }
+
+    /// View the nonce of the challenge.
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    /// View the uri of the request.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// View the `response`, a hash in hexadecimal.
+    pub fn response(&self) -> &str {
+        &self.response
+    }
+
+    /// Get the hash algorithm.
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        self.algorithm.unwrap_or(DigestAlgorithm::Md5)
+    }
+
+    /// Get the quality of protection, which is missing for clients of
+    /// [RFC2069](https://www.rfc-editor.org/rfc/rfc2069).
+    pub fn qop(&self) -> Option<Qop> {
+        self.qop
+    }
+
+    /// Get the number of requests sent with this nonce.
+    pub fn nc(&self) -> Option<u32> {
+        self.nc
+    }
+
+    /// View the nonce chosen by the client.
+    pub fn cnonce(&self) -> Option<&str> {
+        self.cnonce.as_deref()
+    }
+
+    /// View the opaque value of the challenge.
+    pub fn opaque(&self) -> Option<&str> {
+        self.opaque.as_deref()
+    }
+
+    /// Check if the username is hashed.
+    pub fn userhash(&self) -> bool {
+        self.userhash
+    }
+
+    fn hash_username(&self, username: &str) -> String {
+        self.algorithm()
+            .hash(format!("{}:{}", username, self.realm))
+    }
+
+    fn to_params(&self) -> Vec<AuthParam> {
+        let mut params = Vec::new();
+        let mut push = |name: &str, value: &str, quoted: bool| {
+            params.push(AuthParam {
+                name: name.to_owned(),
+                value: value.to_owned(),
+                quoted,
+            });
+        };
+
+        push("username", &self.username, true);
+        push("realm", &self.realm, true);
+        push("uri", &self.uri, true);
+        if let Some(algorithm) = self.algorithm {
+            push("algorithm", algorithm.as_str(), false);
+        }
+        push("nonce", &self.nonce, true);
+        if let Some(nc) = self.nc {
+            push("nc", &format!("{:08x}", nc), false);
+        }
+        if let Some(ref cnonce) = self.cnonce {
+            push("cnonce", cnonce, true);
+        }
+        if let Some(qop) = self.qop {
+            push("qop", qop.as_str(), false);
+        }
+        push("response", &self.response, true);
+        if let Some(ref opaque) = self.opaque {
+            push("opaque", opaque, true);
+        }
+        if self.userhash {
+            push("userhash", "true", false);
+        }
+        params
+    }
+}
+
+impl Credentials for Digest {
+    const SCHEME: &'static str = "Digest";
+
+    fn decode(value: &HeaderValue) -> Option<Self> {
+        debug_assert!(
+            value.as_bytes()[..Self::SCHEME.len()].eq_ignore_ascii_case(Self::SCHEME.as_bytes()),
+            "HeaderValue to decode should start with \"Digest ..\", received = {:?}",
+            value,
+        );
+
+        let s = std::str::from_utf8(value.as_bytes()).ok()?;
+        let mut params = Vec::new();
+        let rest = parse_auth_params(s[Self::SCHEME.len()..].trim_start(), &mut params)?;
+        if !rest.trim().is_empty() {
+            return None;
+        }
+
+        let qop = match find_auth_param(&params, "qop") {
+            Some(qop) => Some(qop.parse().ok()?),
+            None => None,
+        };
+        let nc = match find_auth_param(&params, "nc") {
+            Some(nc) if nc.len() == 8 => Some(u32::from_str_radix(nc, 16).ok()?),
+            Some(_) => return None,
+            None => None,
+        };
+        let cnonce = find_auth_param(&params, "cnonce").map(str::to_owned);
+        if qop.is_some() && (nc.is_none() || cnonce.is_none()) {
+            return None;
+        }
+
+        Some(Digest {
+            username: find_auth_param(&params, "username")?.to_owned(),
+            realm: find_auth_param(&params, "realm")?.to_owned(),
+            nonce: find_auth_param(&params, "nonce")?.to_owned(),
+            uri: find_auth_param(&params, "uri")?.to_owned(),
+            response: find_auth_param(&params, "response")?.to_owned(),
+            algorithm: parse_algorithm(&params)?,
+            qop,
+            nc,
+            cnonce,
+            opaque: find_auth_param(&params, "opaque").map(str::to_owned),
+            userhash: parse_bool(&params, "userhash")?,
+        })
+    }
+
+    fn encode(&self) -> HeaderValue {
+        util::fmt(FmtDigest(self))
+    }
+}
+
+struct FmtDigest<'a>(&'a Digest);
+
+impl fmt::Display for FmtDigest<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            Digest::SCHEME,
+            FmtAuthParams(&self.0.to_params())
+        )
+    }
+}
+
+impl DigestChallenge {
+    /// Create a challenge for the given protection space, with a nonce
+    /// chosen by the server.
+    ///
+    /// The quality of protection is `auth`, and the algorithm is the
+    /// default `MD5`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the realm or nonce contains control characters.
+    pub fn new(realm: &str, nonce: &str) -> Self {
+        assert!(is_param_value(realm), "invalid realm");
+        assert!(is_param_value(nonce), "invalid nonce");
+        DigestChallenge {
+            realm: realm.to_owned(),
+            nonce: nonce.to_owned(),
+            opaque: None,
+            stale: false,
+            algorithm: None,
+            qop: vec![Qop::Auth],
+            userhash: false,
+        }
+    }
+
+    /// Get a Digest challenge from a generic challenge.
+    ///
+    /// Returns `None` if the challenge isn't for the `Digest` scheme, or
+    /// is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use headers::authorization::DigestChallenge;
+    /// use headers::{HeaderMap, HeaderMapExt, WwwAuthenticate};
+    ///
+    /// let mut res = HeaderMap::new();
+    /// res.insert(
+    ///     "www-authenticate",
+    ///     r#"Digest realm="api", qop="auth, auth-int", nonce="abc""#.parse().unwrap(),
+    /// );
+    ///
+    /// let challenge = res
+    ///     .typed_get::<WwwAuthenticate>()
+    ///     .and_then(|www_auth| www_auth.find("Digest").and_then(DigestChallenge::from_challenge))
+    ///     .unwrap();
+    /// assert_eq!(challenge.nonce(), "abc");
+    /// ```
+    pub fn from_challenge(challenge: &Challenge) -> Option<Self> {
+        if !challenge.scheme().eq_ignore_ascii_case(Digest::SCHEME) {
+            return None;
+        }
+        let params = &challenge.params;
+
+        let qop = find_auth_param(params, "qop")
+            .map(|qop| {
+                qop.split(',')
+                    .filter_map(|qop| qop.trim().parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(DigestChallenge {
+            realm: find_auth_param(params, "realm")?.to_owned(),
+            nonce: find_auth_param(params, "nonce")?.to_owned(),
+            opaque: find_auth_param(params, "opaque").map(str::to_owned),
+            stale: parse_bool(params, "stale")?,
+            algorithm: parse_algorithm(params)?,
+            qop,
+            userhash: parse_bool(params, "userhash")?,
+        })
+    }
+
+    /// Set an opaque value, that the client sends back unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value contains control characters.
+    pub fn with_opaque(mut self, opaque: &str) -> Self {
+        assert!(is_param_value(opaque), "invalid opaque");
+        self.opaque = Some(opaque.to_owned());
+        self
+    }
+
+    /// Set whether the previous request was rejected only because its
+    /// nonce was stale, so the client can retry without asking the user.
+    pub fn with_stale(mut self, stale: bool) -> Self {
+        self.stale = stale;
+        self
+    }
+
+    /// Set the hash algorithm.
+    pub fn with_algorithm(mut self, algorithm: DigestAlgorithm) -> Self {
+        self.algorithm = Some(algorithm);
+        self
+    }
+
+    /// Set the supported qualities of protection.
+    pub fn with_qop<I>(mut self, qop: I) -> Self
+    where
+        I: IntoIterator<Item = Qop>,
+    {
+        self.qop = qop.into_iter().collect();
+        self
+    }
+
+    /// Set whether the client should hash the username.
+    pub fn with_userhash(mut self, userhash: bool) -> Self {
+        self.userhash = userhash;
+        self
+    }
+
+    /// View the protection space.
+    pub fn realm(&self) -> &str {
+        &self.realm
+    }
+
+    /// View the nonce chosen by the server.
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    /// View the opaque value.
+    pub fn opaque(&self) -> Option<&str> {
+        self.opaque.as_deref()
+    }
+
+    /// Check if the nonce of the previous request was stale.
+    pub fn stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Get the hash algorithm.
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        self.algorithm.unwrap_or(DigestAlgorithm::Md5)
+    }
+
+    /// View the supported qualities of protection.
+    pub fn qop(&self) -> &[Qop] {
+        &self.qop
+    }
+
+    /// Check if the client should hash the username.
+    pub fn userhash(&self) -> bool {
+        self.userhash
+    }
+}
+
+impl From<DigestChallenge> for Challenge {
+    fn from(digest: DigestChallenge) -> Challenge {
+        let mut challenge = Challenge::new(Digest::SCHEME).with_realm(&digest.realm);
+        if !digest.qop.is_empty() {
+            let qop = digest
+                .qop
+                .iter()
+                .map(|qop| qop.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            challenge = challenge.with_param("qop", &qop);
+        }
+        if let Some(algorithm) = digest.algorithm {
+            challenge = challenge.set_param("algorithm", algorithm.as_str(), false);
+        }
+        challenge = challenge.with_param("nonce", &digest.nonce);
+        if let Some(ref opaque) = digest.opaque {
+            challenge = challenge.with_param("opaque", opaque);
+        }
+        if digest.stale {
+            challenge = challenge.set_param("stale", "true", false);
+        }
+        if digest.userhash {
+            challenge = challenge.set_param("userhash", "true", false);
+        }
+        challenge
+    }
+}
+
+impl DigestAlgorithm {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            DigestAlgorithm::Md5 => "MD5",
+            DigestAlgorithm::Md5Sess => "MD5-sess",
+            DigestAlgorithm::Sha256 => "SHA-256",
+            DigestAlgorithm::Sha256Sess => "SHA-256-sess",
+        }
+    }
+
+    fn is_session(&self) -> bool {
+        matches!(
+            *self,
+            DigestAlgorithm::Md5Sess | DigestAlgorithm::Sha256Sess
+        )
+    }
+
+    /// Hash some data, in lowercase hexadecimal.
+    fn hash<T: AsRef<[u8]>>(&self, data: T) -> String {
+        match *self {
+            DigestAlgorithm::Md5 | DigestAlgorithm::Md5Sess => hex(&Md5::digest(data)),
+            DigestAlgorithm::Sha256 | DigestAlgorithm::Sha256Sess => hex(&Sha256::digest(data)),
+        }
+    }
+}
+
+impl fmt::Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DigestAlgorithm {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            DigestAlgorithm::Md5,
+            DigestAlgorithm::Md5Sess,
+            DigestAlgorithm::Sha256,
+            DigestAlgorithm::Sha256Sess,
+        ]
+        .iter()
+        .find(|algorithm| algorithm.as_str().eq_ignore_ascii_case(s))
+        .copied()
+        .ok_or_else(crate::Error::invalid)
+    }
+}
+
+impl Qop {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            Qop::Auth => "auth",
+            Qop::AuthInt => "auth-int",
+        }
+    }
+}
+
+impl fmt::Display for Qop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Qop {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("auth") {
+            Ok(Qop::Auth)
+        } else if s.eq_ignore_ascii_case("auth-int") {
+            Ok(Qop::AuthInt)
+        } else {
+            Err(crate::Error::invalid())
+        }
+    }
+}
+
+/// Parse the `algorithm` parameter. The outer `None` means it is invalid.
+fn parse_algorithm(params: &[AuthParam]) -> Option<Option<DigestAlgorithm>> {
+    match find_auth_param(params, "algorithm") {
+        Some(algorithm) => algorithm.parse().ok().map(Some),
+        None => Some(None),
+    }
+}
+
+/// Parse a boolean parameter, which defaults to false.
+fn parse_bool(params: &[AuthParam], name: &str) -> Option<bool> {
+    match find_auth_param(params, name) {
+        Some(value) if value.eq_ignore_ascii_case("true") => Some(true),
+        Some(value) if value.eq_ignore_ascii_case("false") => Some(false),
+        Some(_) => None,
+        None => Some(false),
+    }
+}
+
+fn is_param_value(s: &str) -> bool {
+    !s.chars().any(|c| c.is_control() && c != '\t')
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::test_decode;
+    use super::super::Authorization;
+    use super::*;
+
+    const NONCE: &str = "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v";
+    const CNONCE: &str = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+    const OPAQUE: &str = "FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS";
+
+    fn rfc7616_challenge(algorithm: DigestAlgorithm) -> DigestChallenge {
+        DigestChallenge::new("http-auth@example.org", NONCE)
+            .with_qop(vec![Qop::Auth, Qop::AuthInt])
+            .with_algorithm(algorithm)
+            .with_opaque(OPAQUE)
+    }
+
+    #[test]
+    fn rfc7616_examples() {
+        let md5 = Digest::new(
+            &rfc7616_challenge(DigestAlgorithm::Md5),
+            "Mufasa",
+            "/dir/index.html",
+            CNONCE,
+        )
+        .sign(&Method::GET, "Circle of Life", b"");
+        assert_eq!(md5.response(), "8ca523f5e9506fed4657c9700eebdbec");
+        assert_eq!(md5.qop(), Some(Qop::Auth));
+
+        let sha256 = Digest::new(
+            &rfc7616_challenge(DigestAlgorithm::Sha256),
+            "Mufasa",
+            "/dir/index.html",
+            CNONCE,
+        )
+        .sign(&Method::GET, "Circle of Life", b"");
+        assert_eq!(
+            sha256.response(),
+            "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1"
+        );
+    }
+
+    #[test]
+    fn decode_rfc2617_example() {
+        let auth = test_decode::<Authorization<Digest>>(&[
+            "Digest username=\"Mufasa\", realm=\"testrealm@host.com\", \
+             nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", uri=\"/dir/index.html\", \
+             qop=auth, nc=00000001, cnonce=\"0a4f113b\", \
+             response=\"6629fae49393a05397450978507c4ef1\", \
+             opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"",
+        ])
+        .unwrap();
+        let digest = auth.0;
+        assert_eq!(digest.username(), "Mufasa");
+        assert_eq!(digest.nc(), Some(1));
+        assert_eq!(digest.algorithm(), DigestAlgorithm::Md5);
+        assert!(digest.is_username("Mufasa"));
+        assert!(digest.verify(&Method::GET, "Mufasa", "Circle Of Life", b""));
+        assert!(!digest.verify(&Method::GET, "Mufasa", "Circle of Life", b""));
+        assert!(!digest.verify(&Method::POST, "Mufasa", "Circle Of Life", b""));
+    }
+
+    #[test]
+    fn decode_invalid() {
+        let valid = "username=\"a\", realm=\"r\", nonce=\"n\", uri=\"/\", response=\"x\"";
+        assert!(test_decode::<Authorization<Digest>>(&[&format!("Digest {}", valid)]).is_some());
+        // Empty list elements at the end are allowed.
+        assert!(test_decode::<Authorization<Digest>>(&[&format!("Digest {}, ,", valid)]).is_some());
+
+        for extra in &[
+            ", qop=auth",
+            ", qop=other, nc=00000001, cnonce=\"c\"",
+            ", qop=auth, nc=1, cnonce=\"c\"",
+            ", algorithm=SHA-1",
+            ", userhash=maybe",
+            ", realm=\"again\"",
+            " trailing",
+        ] {
+            let value = format!("Digest {}{}", valid, extra);
+            assert_eq!(
+                test_decode::<Authorization<Digest>>(&[&value]),
+                None,
+                "{:?} should be invalid",
+                value
+            );
+        }
+        assert_eq!(
+            test_decode::<Authorization<Digest>>(&["Digest realm=\"r\""]),
+            None
+        );
+    }
+
+    #[test]
+    fn roundtrip_session_and_auth_int() {
+        let challenge = DigestChallenge::new("api", "abc")
+            .with_qop(vec![Qop::AuthInt])
+            .with_algorithm(DigestAlgorithm::Sha256Sess)
+            .with_userhash(true);
+        let digest = Digest::new(&challenge, "Mufasa", "/upload", "xyz")
+            .with_nc(2)
+            .sign(&Method::POST, "secret", b"body");
+
+        assert_ne!(digest.username(), "Mufasa");
+        assert!(digest.is_username("Mufasa"));
+        assert!(!digest.is_username("Scar"));
+
+        let auth = Authorization(digest);
+        let headers = super::super::super::test_encode(auth.clone());
+        let value = headers["authorization"].to_str().unwrap();
+        assert!(value.starts_with("Digest username=\""));
+        assert!(value.contains(", algorithm=SHA-256-sess, "));
+        assert!(value.contains(", nc=00000002, "));
+        assert!(value.contains(", qop=auth-int, "));
+        assert!(value.ends_with(", userhash=true"));
+
+        let decoded = test_decode::<Authorization<Digest>>(&[value]).unwrap();
+        assert_eq!(decoded, auth);
+        assert!(decoded.0.verify(&Method::POST, "Mufasa", "secret", b"body"));
+        assert!(!decoded
+            .0
+            .verify(&Method::POST, "Mufasa", "secret", b"other"));
+    }
+
+    #[test]
+    fn challenge_roundtrip() {
+        let challenge = rfc7616_challenge(DigestAlgorithm::Sha256).with_stale(true);
+        let generic = Challenge::from(challenge.clone());
+        assert_eq!(
+            generic.to_string(),
+            format!(
+                "Digest realm=\"http-auth@example.org\", qop=\"auth, auth-int\", \
+                 algorithm=SHA-256, nonce=\"{}\", opaque=\"{}\", stale=true",
+                NONCE, OPAQUE
+            )
+        );
+        assert_eq!(DigestChallenge::from_challenge(&generic), Some(challenge));
+
+        assert_eq!(
+            DigestChallenge::from_challenge(&Challenge::basic("api")),
+            None
+        );
+    }
+
+    #[test]
+    fn rfc2069_compatibility() {
+        let challenge =
+            DigestChallenge::new("testrealm@host.com", "dcd98b7102dd2f0e8b11d0f600bfb0c093")
+                .with_qop(vec![]);
+        let digest = Digest::new(&challenge, "Mufasa", "/dir/index.html", "ignored").sign(
+            &Method::GET,
+            "CircleOfLife",
+            b"",
+        );
+        assert_eq!(digest.qop(), None);
+        assert_eq!(digest.cnonce(), None);
+        assert_eq!(digest.response(), "1949323746fe6a43ef61f9606e7febea");
+    }
+}
@@ -1,3 +1,4 @@
+use std::cmp;
 use std::fmt;
 use std::iter::FromIterator;
 use std::str::FromStr;
@@ -5,17 +6,22 @@ use std::time::Duration;
 
 use http::{HeaderName, HeaderValue};
 
-use crate::util::{self, csv, Seconds};
+use crate::structured::{self, BareItem, FmtBareItem, FmtDictionary, Member};
+use crate::util::{self, csv, fmt_token_or_quoted, parse_quoted, Seconds};
 use crate::{Error, Header};
 
 /// `Cache-Control` header, defined in [RFC7234](https://tools.ietf.org/html/rfc7234#section-5.2)
 /// with extensions in [RFC8246](https://www.rfc-editor.org/rfc/rfc8246)
+/// and [RFC5861](https://www.rfc-editor.org/rfc/rfc5861)
 ///
 /// The `Cache-Control` header field is used to specify directives for
 /// caches along the request/response chain.  Such cache directives are
 /// unidirectional in that the presence of a directive in a request does
 /// not imply that the same directive is to be given in the response.
 ///
+/// Extension directives that aren't known are kept as they are, so that
+/// they survive a round-trip.
+///
 /// ## ABNF
 ///
 /// ```text
@@ -43,6 +49,11 @@ pub struct CacheControl {
     max_stale: Option<Seconds>,
     min_fresh: Option<Seconds>,
     s_max_age: Option<Seconds>,
+    stale_while_revalidate: Option<Seconds>,
+    stale_if_error: Option<Seconds>,
+    no_cache_fields: Vec<HeaderName>,
+    private_fields: Vec<HeaderName>,
+    extensions: Vec<(String, Option<String>)>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -61,6 +72,9 @@ impl Flags {
     const PROXY_REVALIDATE: Self = Self { bits: 0b010000000 };
     const IMMUTABLE: Self = Self { bits: 0b100000000 };
     const MUST_UNDERSTAND: Self = Self { bits: 0b1000000000 };
+    const ANY_STALE: Self = Self {
+        bits: 0b10000000000,
+    };
 
     fn empty() -> Self {
         Self { bits: 0 }
@@ -77,6 +91,7 @@ impl Flags {
 
 impl CacheControl {
     /// Construct a new empty `CacheControl` header.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         CacheControl {
             flags: Flags::empty(),
@@ -84,12 +99,21 @@ impl CacheControl {
             max_stale: None,
             min_fresh: None,
             s_max_age: None,
+            stale_while_revalidate: None,
+            stale_if_error: None,
+            no_cache_fields: Vec::new(),
+            private_fields: Vec::new(),
+            extensions: Vec::new(),
         }
     }
 
     // getters
 
     /// Check if the `no-cache` directive is set.
+    ///
+    /// This is only the unqualified form, see
+    /// [`no_cache_fields`](CacheControl::no_cache_fields) for the form
+    /// listing header fields.
     pub fn no_cache(&self) -> bool {
         self.flags.contains(Flags::NO_CACHE)
     }
@@ -115,6 +139,10 @@ impl CacheControl {
     }
 
     /// Check if the `private` directive is set.
+    ///
+    /// This is only the unqualified form, see
+    /// [`private_fields`](CacheControl::private_fields) for the form
+    /// listing header fields.
     pub fn private(&self) -> bool {
         self.flags.contains(Flags::PRIVATE)
     }
@@ -134,16 +162,40 @@ impl CacheControl {
         self.flags.contains(Flags::MUST_UNDERSTAND)
     }
 
+    /// Check if the `proxy-revalidate` directive is set.
+    pub fn proxy_revalidate(&self) -> bool {
+        self.flags.contains(Flags::PROXY_REVALIDATE)
+    }
+
+    /// Get the header fields listed by a `no-cache="..."` directive.
+    pub fn no_cache_fields(&self) -> &[HeaderName] {
+        &self.no_cache_fields
+    }
+
+    /// Get the header fields listed by a `private="..."` directive.
+    pub fn private_fields(&self) -> &[HeaderName] {
+        &self.private_fields
+    }
+
     /// Get the value of the `max-age` directive if set.
     pub fn max_age(&self) -> Option<Duration> {
         self.max_age.map(Into::into)
     }
 
     /// Get the value of the `max-stale` directive if set.
+    ///
+    /// This is only the form with a value, see
+    /// [`any_stale`](CacheControl::any_stale) for the unqualified form.
     pub fn max_stale(&self) -> Option<Duration> {
         self.max_stale.map(Into::into)
     }
 
+    /// Check if the `max-stale` directive is set without a value, meaning
+    /// that a stale response of any age is accepted.
+    pub fn any_stale(&self) -> bool {
+        self.flags.contains(Flags::ANY_STALE)
+    }
+
     /// Get the value of the `min-fresh` directive if set.
     pub fn min_fresh(&self) -> Option<Duration> {
         self.min_fresh.map(Into::into)
@@ -154,6 +206,34 @@ impl CacheControl {
         self.s_max_age.map(Into::into)
     }
 
+    /// Get the value of the `stale-while-revalidate` directive if set.
+    pub fn stale_while_revalidate(&self) -> Option<Duration> {
+        self.stale_while_revalidate.map(Into::into)
+    }
+
+    /// Get the value of the `stale-if-error` directive if set.
+    pub fn stale_if_error(&self) -> Option<Duration> {
+        self.stale_if_error.map(Into::into)
+    }
+
+    /// Get the value of an extension directive, if set.
+    ///
+    /// The outer `Option` is whether the directive is set, and the inner
+    /// one its value, if it has one. Names are compared case-insensitively.
+    pub fn extension(&self, name: &str) -> Option<Option<&str>> {
+        self.extensions
+            .iter()
+            .find(|(ext, _)| ext.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_deref())
+    }
+
+    /// Iterate the extension directives, with their values if any.
+    pub fn extensions(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.extensions
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_deref()))
+    }
+
     // setters
 
     /// Set the `no-cache` directive.
@@ -210,6 +290,30 @@ impl CacheControl {
         self
     }
 
+    /// Set the `proxy-revalidate` directive.
+    pub fn with_proxy_revalidate(mut self) -> Self {
+        self.flags.insert(Flags::PROXY_REVALIDATE);
+        self
+    }
+
+    /// Set the `no-cache` directive, listing header fields.
+    pub fn with_no_cache_fields<I>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = HeaderName>,
+    {
+        self.no_cache_fields.extend(fields);
+        self
+    }
+
+    /// Set the `private` directive, listing header fields.
+    pub fn with_private_fields<I>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = HeaderName>,
+    {
+        self.private_fields.extend(fields);
+        self
+    }
+
     /// Set the `max-age` directive.
     pub fn with_max_age(mut self, duration: Duration) -> Self {
         self.max_age = Some(duration.into());
@@ -222,6 +326,13 @@ impl CacheControl {
         self
     }
 
+    /// Set the `max-stale` directive without a value, accepting a stale
+    /// response of any age.
+    pub fn with_any_stale(mut self) -> Self {
+        self.flags.insert(Flags::ANY_STALE);
+        self
+    }
+
     /// Set the `min-fresh` directive.
     pub fn with_min_fresh(mut self, duration: Duration) -> Self {
         self.min_fresh = Some(duration.into());
@@ -233,6 +344,35 @@ impl CacheControl {
         self.s_max_age = Some(duration.into());
         self
     }
+
+    /// Set the `stale-while-revalidate` directive.
+    pub fn with_stale_while_revalidate(mut self, duration: Duration) -> Self {
+        self.stale_while_revalidate = Some(duration.into());
+        self
+    }
+
+    /// Set the `stale-if-error` directive.
+    pub fn with_stale_if_error(mut self, duration: Duration) -> Self {
+        self.stale_if_error = Some(duration.into());
+        self
+    }
+
+    /// Set an extension directive, with an optional value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not a valid token, or if the value contains
+    /// control characters other than horizontal tab, such as CR or LF.
+    pub fn with_extension(mut self, name: &str, value: Option<&str>) -> Self {
+        assert!(util::is_token(name), "invalid directive name");
+        assert!(
+            value.map_or(true, util::is_quotable),
+            "invalid directive value"
+        );
+        self.extensions
+            .push((name.to_owned(), value.map(ToOwned::to_owned)));
+        self
+    }
 }
 
 impl Header for CacheControl {
@@ -249,6 +389,94 @@ impl Header for CacheControl {
     }
 }
 
+impl CacheControl {
+    /// Decode the directives of a targeted cache-control field, such as
+    /// `CDN-Cache-Control`, which uses the syntax of a structured field
+    /// dictionary.
+    ///
+    /// Directives with an invalid value are ignored.
+    pub(crate) fn decode_targeted<'i, I>(values: I) -> Result<Self, Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let dict = structured::parse_dictionary(values)?;
+        let FromIter(cc) = dict
+            .into_iter()
+            .filter_map(|(key, member)| {
+                // Parameters are ignored, as are inner lists.
+                let directive = match member {
+                    Member::Item(item) => match item.bare_item {
+                        BareItem::Boolean(true) => key,
+                        BareItem::Boolean(false)
+                        | BareItem::ByteSequence(_)
+                        | BareItem::Date(_)
+                        | BareItem::DisplayString(_) => return None,
+                        BareItem::Integer(value) if value < 0 => return None,
+                        ref value => format!("{}={}", key, FmtBareItem(value)),
+                    },
+                    Member::InnerList(_) => return None,
+                };
+                directive.parse().ok()
+            })
+            .collect();
+        Ok(cc)
+    }
+
+    /// Encode the directives as a targeted cache-control field.
+    ///
+    /// Extension directives that can't be represented as a structured field
+    /// are skipped.
+    pub(crate) fn encode_targeted(&self) -> HeaderValue {
+        let dict = self
+            .items()
+            .filter_map(|item| {
+                let (key, value) = match item {
+                    Item::Directive(dir) => {
+                        let value = match dir.secs() {
+                            Some(secs) => BareItem::Integer(cmp::min(secs, MAX_SECS) as i64),
+                            None => BareItem::Boolean(true),
+                        };
+                        (dir.name().to_owned(), value)
+                    }
+                    Item::Fields(name, fields) => {
+                        let fields = fields.iter().map(HeaderName::as_str).collect::<Vec<_>>();
+                        (name.to_owned(), BareItem::String(fields.join(", ")))
+                    }
+                    Item::Extension(name, value) => {
+                        let value = match value {
+                            None => BareItem::Boolean(true),
+                            Some(value) if is_integer(value) => {
+                                BareItem::Integer(value.parse().ok()?)
+                            }
+                            Some(value) if structured::is_token(value) => {
+                                BareItem::Token(value.to_owned())
+                            }
+                            Some(value) if structured::is_string(value) => {
+                                BareItem::String(value.to_owned())
+                            }
+                            Some(_) => return None,
+                        };
+                        (name.to_ascii_lowercase(), value)
+                    }
+                };
+                if structured::is_key(&key) {
+                    Some((key, Member::Item(structured::Item::new(value))))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        util::fmt(FmtDictionary(&dict))
+    }
+}
+
+/// The largest number of seconds that can be sent in a structured field.
+const MAX_SECS: u64 = 999_999_999_999_999;
+
+fn is_integer(s: &str) -> bool {
+    !s.is_empty() && s.len() <= 15 && s.bytes().all(|b| b.is_ascii_digit())
+}
+
 // Adapter to be used in Header::decode
 struct FromIter(CacheControl);
 
@@ -259,13 +487,17 @@ impl FromIterator<KnownDirective> for FromIter {
     {
         let mut cc = CacheControl::new();
 
-        // ignore all unknown directives
-        let iter = iter.into_iter().filter_map(|dir| match dir {
-            KnownDirective::Known(dir) => Some(dir),
-            KnownDirective::Unknown => None,
-        });
+        let mut directives = Vec::new();
+        for dir in iter {
+            match dir {
+                KnownDirective::Known(dir) => directives.push(dir),
+                KnownDirective::NoCacheFields(fields) => cc.no_cache_fields.extend(fields),
+                KnownDirective::PrivateFields(fields) => cc.private_fields.extend(fields),
+                KnownDirective::Extension(name, value) => cc.extensions.push((name, value)),
+            }
+        }
 
-        for directive in iter {
+        for directive in directives {
             match directive {
                 Directive::NoCache => {
                     cc.flags.insert(Flags::NO_CACHE);
@@ -297,6 +529,9 @@ impl FromIterator<KnownDirective> for FromIter {
                 Directive::ProxyRevalidate => {
                     cc.flags.insert(Flags::PROXY_REVALIDATE);
                 }
+                Directive::AnyStale => {
+                    cc.flags.insert(Flags::ANY_STALE);
+                }
                 Directive::MaxAge(secs) => {
                     cc.max_age = Some(Duration::from_secs(secs).into());
                 }
@@ -309,6 +544,12 @@ impl FromIterator<KnownDirective> for FromIter {
                 Directive::SMaxAge(secs) => {
                     cc.s_max_age = Some(Duration::from_secs(secs).into());
                 }
+                Directive::StaleWhileRevalidate(secs) => {
+                    cc.stale_while_revalidate = Some(Duration::from_secs(secs).into());
+                }
+                Directive::StaleIfError(secs) => {
+                    cc.stale_if_error = Some(Duration::from_secs(secs).into());
+                }
             }
         }
 
@@ -320,15 +561,21 @@ struct Fmt<'a>(&'a CacheControl);
 
 impl fmt::Display for Fmt<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        csv::fmt_comma_delimited(f, self.0.items())
+    }
+}
+
+impl CacheControl {
+    fn items<'a>(&'a self) -> impl Iterator<Item = Item<'a>> {
         let if_flag = |f: Flags, dir: Directive| {
-            if self.0.flags.contains(f) {
+            if self.flags.contains(f) {
                 Some(dir)
             } else {
                 None
             }
         };
 
-        let slice = &[
+        let directives = vec![
             if_flag(Flags::NO_CACHE, Directive::NoCache),
             if_flag(Flags::NO_STORE, Directive::NoStore),
             if_flag(Flags::NO_TRANSFORM, Directive::NoTransform),
@@ -339,34 +586,82 @@ impl fmt::Display for Fmt<'_> {
             if_flag(Flags::IMMUTABLE, Directive::Immutable),
             if_flag(Flags::MUST_UNDERSTAND, Directive::MustUnderstand),
             if_flag(Flags::PROXY_REVALIDATE, Directive::ProxyRevalidate),
-            self.0
-                .max_age
-                .as_ref()
-                .map(|s| Directive::MaxAge(s.as_u64())),
-            self.0
-                .max_stale
+            self.max_age.as_ref().map(|s| Directive::MaxAge(s.as_u64())),
+            if_flag(Flags::ANY_STALE, Directive::AnyStale),
+            // The unqualified `max-stale` supersedes the one with a value.
+            self.max_stale
                 .as_ref()
+                .filter(|_| !self.flags.contains(Flags::ANY_STALE))
                 .map(|s| Directive::MaxStale(s.as_u64())),
-            self.0
-                .min_fresh
+            self.min_fresh
                 .as_ref()
                 .map(|s| Directive::MinFresh(s.as_u64())),
-            self.0
-                .s_max_age
+            self.s_max_age
                 .as_ref()
                 .map(|s| Directive::SMaxAge(s.as_u64())),
+            self.stale_while_revalidate
+                .as_ref()
+                .map(|s| Directive::StaleWhileRevalidate(s.as_u64())),
+            self.stale_if_error
+                .as_ref()
+                .map(|s| Directive::StaleIfError(s.as_u64())),
         ];
 
-        let iter = slice.iter().filter_map(|o| *o);
+        // The unqualified directives supersede the ones listing fields.
+        let fields = |flag: Flags, name: &'static str, fields: &'a [HeaderName]| {
+            if self.flags.contains(flag) || fields.is_empty() {
+                None
+            } else {
+                Some(Item::Fields(name, fields))
+            }
+        };
 
-        csv::fmt_comma_delimited(f, iter)
+        directives
+            .into_iter()
+            .flatten()
+            .map(Item::Directive)
+            .chain(fields(Flags::NO_CACHE, "no-cache", &self.no_cache_fields))
+            .chain(fields(Flags::PRIVATE, "private", &self.private_fields))
+            .chain(
+                self.extensions
+                    .iter()
+                    .map(|(name, value)| Item::Extension(name, value.as_deref())),
+            )
+    }
+}
+
+enum Item<'a> {
+    Directive(Directive),
+    Fields(&'static str, &'a [HeaderName]),
+    Extension(&'a str, Option<&'a str>),
+}
+
+impl fmt::Display for Item<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Item::Directive(ref dir) => fmt::Display::fmt(dir, f),
+            Item::Fields(name, fields) => {
+                write!(f, "{}=\"", name)?;
+                csv::fmt_comma_delimited(f, fields.iter())?;
+                f.write_str("\"")
+            }
+            Item::Extension(name, value) => {
+                f.write_str(name)?;
+                if let Some(value) = value {
+                    f.write_str("=")?;
+                    fmt_token_or_quoted(f, value)?;
+                }
+                Ok(())
+            }
+        }
     }
 }
 
-#[derive(Clone, Copy)]
 enum KnownDirective {
     Known(Directive),
-    Unknown,
+    NoCacheFields(Vec<HeaderName>),
+    PrivateFields(Vec<HeaderName>),
+    Extension(String, Option<String>),
 }
 
 #[derive(Clone, Copy)]
@@ -379,6 +674,7 @@ enum Directive {
     // request directives
     MaxAge(u64),
     MaxStale(u64),
+    AnyStale,
     MinFresh(u64),
 
     // response directives
@@ -389,71 +685,124 @@ enum Directive {
     Immutable,
     ProxyRevalidate,
     SMaxAge(u64),
+    StaleWhileRevalidate(u64),
+    StaleIfError(u64),
+}
+
+impl Directive {
+    fn name(&self) -> &'static str {
+        match *self {
+            Directive::NoCache => "no-cache",
+            Directive::NoStore => "no-store",
+            Directive::NoTransform => "no-transform",
+            Directive::OnlyIfCached => "only-if-cached",
+
+            Directive::MaxAge(_) => "max-age",
+            Directive::MaxStale(_) | Directive::AnyStale => "max-stale",
+            Directive::MinFresh(_) => "min-fresh",
+
+            Directive::MustRevalidate => "must-revalidate",
+            Directive::MustUnderstand => "must-understand",
+            Directive::Public => "public",
+            Directive::Private => "private",
+            Directive::Immutable => "immutable",
+            Directive::ProxyRevalidate => "proxy-revalidate",
+            Directive::SMaxAge(_) => "s-maxage",
+            Directive::StaleWhileRevalidate(_) => "stale-while-revalidate",
+            Directive::StaleIfError(_) => "stale-if-error",
+        }
+    }
+
+    fn secs(&self) -> Option<u64> {
+        match *self {
+            Directive::MaxAge(secs)
+            | Directive::MaxStale(secs)
+            | Directive::MinFresh(secs)
+            | Directive::SMaxAge(secs)
+            | Directive::StaleWhileRevalidate(secs)
+            | Directive::StaleIfError(secs) => Some(secs),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Directive {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        fmt::Display::fmt(
-            match *self {
-                Directive::NoCache => "no-cache",
-                Directive::NoStore => "no-store",
-                Directive::NoTransform => "no-transform",
-                Directive::OnlyIfCached => "only-if-cached",
-
-                Directive::MaxAge(secs) => return write!(f, "max-age={}", secs),
-                Directive::MaxStale(secs) => return write!(f, "max-stale={}", secs),
-                Directive::MinFresh(secs) => return write!(f, "min-fresh={}", secs),
-
-                Directive::MustRevalidate => "must-revalidate",
-                Directive::MustUnderstand => "must-understand",
-                Directive::Public => "public",
-                Directive::Private => "private",
-                Directive::Immutable => "immutable",
-                Directive::ProxyRevalidate => "proxy-revalidate",
-                Directive::SMaxAge(secs) => return write!(f, "s-maxage={}", secs),
-            },
-            f,
-        )
+        match self.secs() {
+            Some(secs) => write!(f, "{}={}", self.name(), secs),
+            None => f.write_str(self.name()),
+        }
     }
 }
 
 impl FromStr for KnownDirective {
     type Err = ();
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(KnownDirective::Known(match s {
-            "no-cache" => Directive::NoCache,
-            "no-store" => Directive::NoStore,
-            "no-transform" => Directive::NoTransform,
-            "only-if-cached" => Directive::OnlyIfCached,
-            "must-revalidate" => Directive::MustRevalidate,
-            "public" => Directive::Public,
-            "private" => Directive::Private,
-            "immutable" => Directive::Immutable,
-            "must-understand" => Directive::MustUnderstand,
-            "proxy-revalidate" => Directive::ProxyRevalidate,
-            "" => return Err(()),
-            _ => match s.find('=') {
-                Some(idx) if idx + 1 < s.len() => {
-                    match (&s[..idx], (s[idx + 1..]).trim_matches('"')) {
-                        ("max-age", secs) => secs.parse().map(Directive::MaxAge).map_err(|_| ())?,
-                        ("max-stale", secs) => {
-                            secs.parse().map(Directive::MaxStale).map_err(|_| ())?
-                        }
-                        ("min-fresh", secs) => {
-                            secs.parse().map(Directive::MinFresh).map_err(|_| ())?
-                        }
-                        ("s-maxage", secs) => {
-                            secs.parse().map(Directive::SMaxAge).map_err(|_| ())?
-                        }
-                        _unknown => return Ok(KnownDirective::Unknown),
-                    }
-                }
-                Some(_) | None => return Ok(KnownDirective::Unknown),
-            },
+        let (name, value) = match s.find('=') {
+            Some(idx) => (&s[..idx], Some(parse_value(&s[idx + 1..])?)),
+            None => (s, None),
+        };
+        if !util::is_token(name) {
+            return Err(());
+        }
+
+        // Directive names are case-insensitive.
+        let lower = name.to_ascii_lowercase();
+        let value = match value {
+            Some(value) => value,
+            None => {
+                return Ok(KnownDirective::Known(match lower.as_str() {
+                    "no-cache" => Directive::NoCache,
+                    "no-store" => Directive::NoStore,
+                    "no-transform" => Directive::NoTransform,
+                    "only-if-cached" => Directive::OnlyIfCached,
+                    "must-revalidate" => Directive::MustRevalidate,
+                    "public" => Directive::Public,
+                    "private" => Directive::Private,
+                    "immutable" => Directive::Immutable,
+                    "must-understand" => Directive::MustUnderstand,
+                    "proxy-revalidate" => Directive::ProxyRevalidate,
+                    "max-stale" => Directive::AnyStale,
+                    _ => return Ok(KnownDirective::Extension(name.to_owned(), None)),
+                }))
+            }
+        };
+        let secs = || value.parse::<u64>().map_err(|_| ());
+
+        Ok(KnownDirective::Known(match lower.as_str() {
+            "max-age" => Directive::MaxAge(secs()?),
+            "max-stale" => Directive::MaxStale(secs()?),
+            "min-fresh" => Directive::MinFresh(secs()?),
+            "s-maxage" => Directive::SMaxAge(secs()?),
+            "stale-while-revalidate" => Directive::StaleWhileRevalidate(secs()?),
+            "stale-if-error" => Directive::StaleIfError(secs()?),
+            "no-cache" => return parse_fields(&value).map(KnownDirective::NoCacheFields),
+            "private" => return parse_fields(&value).map(KnownDirective::PrivateFields),
+            _ => return Ok(KnownDirective::Extension(name.to_owned(), Some(value))),
         }))
     }
 }
 
+/// Parse the value of a directive, either a `token` or a `quoted-string`.
+fn parse_value(s: &str) -> Result<String, ()> {
+    if s.starts_with('"') {
+        match parse_quoted(s) {
+            Some((value, "")) => Ok(value),
+            _ => Err(()),
+        }
+    } else {
+        Ok(s.to_owned())
+    }
+}
+
+fn parse_fields(s: &str) -> Result<Vec<HeaderName>, ()> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(|field| field.parse().map_err(|_| ()))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::{test_decode, test_encode};
@@ -489,8 +838,10 @@ mod tests {
     fn test_parse_quoted_comma() {
         assert_eq!(
             test_decode::<CacheControl>(&["foo=\"a, private, immutable, b\", no-cache"]).unwrap(),
-            CacheControl::new().with_no_cache(),
-            "unknown extensions are ignored but shouldn't fail parsing",
+            CacheControl::new()
+                .with_no_cache()
+                .with_extension("foo", Some("a, private, immutable, b")),
+            "unknown extensions are kept and shouldn't fail parsing",
         )
     }
 
@@ -498,8 +849,11 @@ mod tests {
     fn test_parse_extension() {
         assert_eq!(
             test_decode::<CacheControl>(&["foo, no-cache, bar=baz"]).unwrap(),
-            CacheControl::new().with_no_cache(),
-            "unknown extensions are ignored but shouldn't fail parsing",
+            CacheControl::new()
+                .with_no_cache()
+                .with_extension("foo", None)
+                .with_extension("bar", Some("baz")),
+            "unknown extensions are kept and shouldn't fail parsing",
         );
     }
 
@@ -536,6 +890,17 @@ mod tests {
         assert!(cc.must_understand());
     }
 
+    #[test]
+    fn test_parse_case_insensitive() {
+        assert_eq!(
+            test_decode::<CacheControl>(&["MAX-AGE=5, No-Cache, Private=\"Set-Cookie\""]).unwrap(),
+            CacheControl::new()
+                .with_max_age(Duration::from_secs(5))
+                .with_no_cache()
+                .with_private_fields(vec![::http::header::SET_COOKIE]),
+        );
+    }
+
     #[test]
     fn test_parse_bad_syntax() {
         assert_eq!(test_decode::<CacheControl>(&["max-age=lolz"]), None);
@@ -569,4 +934,100 @@ mod tests {
         );
         assert_eq!(headers["cache-control"], "no-cache, max-age=100");
     }
+
+    #[test]
+    fn test_proxy_revalidate() {
+        let cc = CacheControl::new().with_proxy_revalidate();
+        let headers = test_encode(cc.clone());
+        assert_eq!(headers["cache-control"], "proxy-revalidate");
+        assert_eq!(
+            test_decode::<CacheControl>(&["proxy-revalidate"]).unwrap(),
+            cc
+        );
+        assert!(cc.proxy_revalidate());
+    }
+
+    #[test]
+    fn test_stale_directives() {
+        let cc = test_decode::<CacheControl>(&[
+            "max-age=600, stale-while-revalidate=30, stale-if-error=\"86400\"",
+        ])
+        .unwrap();
+        assert_eq!(cc.stale_while_revalidate(), Some(Duration::from_secs(30)));
+        assert_eq!(cc.stale_if_error(), Some(Duration::from_secs(86400)));
+
+        let headers = test_encode(cc);
+        assert_eq!(
+            headers["cache-control"],
+            "max-age=600, stale-while-revalidate=30, stale-if-error=86400"
+        );
+
+        assert_eq!(test_decode::<CacheControl>(&["stale-if-error=soon"]), None);
+    }
+
+    #[test]
+    fn test_field_lists() {
+        let cc =
+            test_decode::<CacheControl>(&["no-cache=\"Set-Cookie, x-foo\", private=authorization"])
+                .unwrap();
+        assert!(!cc.no_cache());
+        assert!(!cc.private());
+        assert_eq!(
+            cc.no_cache_fields(),
+            &[::http::header::SET_COOKIE, HeaderName::from_static("x-foo")]
+        );
+        assert_eq!(cc.private_fields(), &[::http::header::AUTHORIZATION]);
+
+        let headers = test_encode(cc);
+        assert_eq!(
+            headers["cache-control"],
+            "no-cache=\"set-cookie, x-foo\", private=\"authorization\""
+        );
+
+        // The unqualified form supersedes the field list.
+        let cc = CacheControl::new()
+            .with_no_cache()
+            .with_no_cache_fields(vec![::http::header::SET_COOKIE]);
+        let headers = test_encode(cc);
+        assert_eq!(headers["cache-control"], "no-cache");
+
+        assert_eq!(test_decode::<CacheControl>(&["private=\"a b\""]), None);
+    }
+
+    #[test]
+    fn test_any_stale() {
+        let cc = test_decode::<CacheControl>(&["max-stale"]).unwrap();
+        assert!(cc.any_stale());
+        assert_eq!(cc.max_stale(), None);
+        assert_eq!(cc.extension("max-stale"), None);
+        assert_eq!(cc, CacheControl::new().with_any_stale());
+
+        let headers = test_encode(cc.with_max_stale(Duration::from_secs(10)));
+        assert_eq!(headers["cache-control"], "max-stale");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid directive value")]
+    fn test_extension_invalid_value() {
+        let _ = CacheControl::new().with_extension("foo", Some("a\r\nb"));
+    }
+
+    #[test]
+    fn test_extensions_round_trip() {
+        let cc =
+            test_decode::<CacheControl>(&["public, community=\"UCI\", foo, bar=\"a \\\"b\\\"\""])
+                .unwrap();
+        assert!(cc.public());
+        assert_eq!(cc.extension("Community"), Some(Some("UCI")));
+        assert_eq!(cc.extension("foo"), Some(None));
+        assert_eq!(cc.extension("bar"), Some(Some("a \"b\"")));
+        assert_eq!(cc.extension("baz"), None);
+        assert_eq!(cc.extensions().count(), 3);
+
+        let headers = test_encode(cc);
+        assert_eq!(
+            headers["cache-control"],
+            "public, community=UCI, foo, bar=\"a \\\"b\\\"\""
+        );
+    }
 }
@@ -0,0 +1,574 @@
+use std::fmt;
+use std::iter::FromIterator;
+
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+
+use crate::structured::{self, BareItem, FmtItem, FmtList, Item, Member, Parameters};
+use crate::util;
+use crate::{Error, Header};
+
+static CACHE_STATUS: HeaderName = HeaderName::from_static("cache-status");
+
+/// `Cache-Status` header, defined in
+/// [RFC9211](https://www.rfc-editor.org/rfc/rfc9211)
+///
+/// The `Cache-Status` header field indicates how caches have handled a
+/// request. It is a list of entries, one for each cache, ordered from the
+/// origin server towards the client: a cache appends its own entry after
+/// those of the caches before it.
+///
+/// Parameters that are not understood are kept, such that entries of other
+/// caches are forwarded unchanged.
+///
+/// # Example values
+///
+/// * `ExampleCache; hit`
+/// * `OriginCache; hit; ttl=1100, "CDN Company Here"; hit; ttl=545`
+/// * `ExampleCache; fwd=uri-miss; stored`
+///
+/// # Example
+///
+/// ```
+/// use headers::{CacheStatus, CacheStatusEntry, ForwardReason, HeaderMap, HeaderMapExt};
+///
+/// let mut res = HeaderMap::new();
+/// res.insert("cache-status", "OriginCache; hit; ttl=1100".parse().unwrap());
+///
+/// CacheStatus::append(
+///     &mut res,
+///     CacheStatusEntry::new("ExampleCache")
+///         .with_fwd(ForwardReason::UriMiss)
+///         .with_stored(),
+/// );
+///
+/// let status = res.typed_get::<CacheStatus>().unwrap();
+/// assert_eq!(status.entries().len(), 2);
+/// assert_eq!(status.entries()[0].ttl(), Some(1100));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CacheStatus(Vec<CacheStatusEntry>);
+
+/// The entry of a single cache in a `Cache-Status` header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CacheStatusEntry {
+    cache: String,
+    hit: bool,
+    fwd: Option<ForwardReason>,
+    fwd_status: Option<StatusCode>,
+    ttl: Option<i64>,
+    stored: bool,
+    collapsed: bool,
+    key: Option<String>,
+    detail: Option<String>,
+    extensions: Parameters,
+}
+
+/// Why a cache forwarded a request, in the `fwd` parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ForwardReason {
+    /// "bypass", the cache was configured to not handle this request.
+    Bypass,
+    /// "method", the request method's semantics require forwarding.
+    Method,
+    /// "uri-miss", the cache did not contain any responses for the target
+    /// URI.
+    UriMiss,
+    /// "vary-miss", the cache had responses for the target URI, but their
+    /// `Vary` did not match the request.
+    VaryMiss,
+    /// "miss", the cache did not contain any responses that could be used.
+    Miss,
+    /// "request", the cache could have used a stored response, but the
+    /// request directives prevented it.
+    Request,
+    /// "stale", the cache had a stale response that needed revalidation.
+    Stale,
+    /// "partial", the cache only had part of the response.
+    Partial,
+}
+
+impl Header for CacheStatus {
+    fn name() -> &'static HeaderName {
+        &CACHE_STATUS
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let entries = structured::parse_list(values)?
+            .into_iter()
+            .map(CacheStatusEntry::from_member)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if entries.is_empty() {
+            Err(Error::invalid())
+        } else {
+            Ok(CacheStatus(entries))
+        }
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let list = self
+            .0
+            .iter()
+            .map(|entry| Member::Item(entry.to_item()))
+            .collect::<Vec<_>>();
+        values.extend(::std::iter::once(util::fmt(FmtList(&list))));
+    }
+}
+
+impl CacheStatus {
+    /// Create a `Cache-Status` header with a single entry.
+    pub fn new(entry: CacheStatusEntry) -> Self {
+        CacheStatus(vec![entry])
+    }
+
+    /// Get the entries of this header, ordered from the origin server
+    /// towards the client.
+    pub fn entries(&self) -> &[CacheStatusEntry] {
+        &self.0
+    }
+
+    /// Add the entry of a cache after the existing ones.
+    pub fn push(&mut self, entry: CacheStatusEntry) {
+        self.0.push(entry);
+    }
+
+    /// Append the entry of a cache to the `Cache-Status` of a response.
+    ///
+    /// The entry is added as a new field line, so the entries of upstream
+    /// caches are left exactly as they were received.
+    pub fn append(headers: &mut HeaderMap, entry: CacheStatusEntry) {
+        headers.append(CACHE_STATUS.clone(), util::fmt(FmtItem(&entry.to_item())));
+    }
+}
+
+impl From<CacheStatusEntry> for CacheStatus {
+    fn from(entry: CacheStatusEntry) -> Self {
+        CacheStatus::new(entry)
+    }
+}
+
+impl FromIterator<CacheStatusEntry> for CacheStatus {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = CacheStatusEntry>,
+    {
+        CacheStatus(iter.into_iter().collect())
+    }
+}
+
+impl CacheStatusEntry {
+    /// Create an entry for the cache with the given name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name contains characters other than visible ASCII and
+    /// spaces.
+    pub fn new(cache: &str) -> Self {
+        assert!(structured::is_string(cache), "invalid cache name");
+        CacheStatusEntry {
+            cache: cache.to_owned(),
+            hit: false,
+            fwd: None,
+            fwd_status: None,
+            ttl: None,
+            stored: false,
+            collapsed: false,
+            key: None,
+            detail: None,
+            extensions: Vec::new(),
+        }
+    }
+
+    // getters
+
+    /// Get the name of the cache.
+    pub fn cache(&self) -> &str {
+        &self.cache
+    }
+
+    /// Check if the request was satisfied by the cache, without forwarding
+    /// it.
+    pub fn hit(&self) -> bool {
+        self.hit
+    }
+
+    /// Get the reason the request was forwarded, if any.
+    pub fn fwd(&self) -> Option<ForwardReason> {
+        self.fwd
+    }
+
+    /// Get the status code of the response to the forwarded request.
+    pub fn fwd_status(&self) -> Option<StatusCode> {
+        self.fwd_status
+    }
+
+    /// Get the remaining freshness lifetime of the response, in seconds.
+    ///
+    /// It is negative if the response is stale.
+    pub fn ttl(&self) -> Option<i64> {
+        self.ttl
+    }
+
+    /// Check if the cache stored the response.
+    pub fn stored(&self) -> bool {
+        self.stored
+    }
+
+    /// Check if the request was collapsed with other requests.
+    pub fn collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    /// Get the cache key used for the response, in an implementation
+    /// specific format.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// Get the implementation specific detail of how the cache handled the
+    /// request.
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    // setters
+
+    /// Set the `hit` parameter.
+    pub fn with_hit(mut self) -> Self {
+        self.remove_extension("hit");
+        self.hit = true;
+        self
+    }
+
+    /// Set the `fwd` parameter.
+    pub fn with_fwd(mut self, reason: ForwardReason) -> Self {
+        self.remove_extension("fwd");
+        self.fwd = Some(reason);
+        self
+    }
+
+    /// Set the `fwd-status` parameter.
+    pub fn with_fwd_status(mut self, status: StatusCode) -> Self {
+        self.remove_extension("fwd-status");
+        self.fwd_status = Some(status);
+        self
+    }
+
+    /// Set the `ttl` parameter, in seconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ttl has more than 15 digits, which is the most a
+    /// structured field integer can have.
+    pub fn with_ttl(mut self, ttl: i64) -> Self {
+        assert!(
+            (-structured::MAX_INTEGER..=structured::MAX_INTEGER).contains(&ttl),
+            "invalid ttl"
+        );
+        self.remove_extension("ttl");
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Set the `stored` parameter.
+    pub fn with_stored(mut self) -> Self {
+        self.remove_extension("stored");
+        self.stored = true;
+        self
+    }
+
+    /// Set the `collapsed` parameter.
+    pub fn with_collapsed(mut self) -> Self {
+        self.remove_extension("collapsed");
+        self.collapsed = true;
+        self
+    }
+
+    /// Set the `key` parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key contains characters other than visible ASCII and
+    /// spaces.
+    pub fn with_key(mut self, key: &str) -> Self {
+        assert!(structured::is_string(key), "invalid cache key");
+        self.remove_extension("key");
+        self.key = Some(key.to_owned());
+        self
+    }
+
+    /// Set the `detail` parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the detail contains characters other than visible ASCII
+    /// and spaces.
+    pub fn with_detail(mut self, detail: &str) -> Self {
+        assert!(structured::is_string(detail), "invalid detail");
+        self.remove_extension("detail");
+        self.detail = Some(detail.to_owned());
+        self
+    }
+
+    /// Remove a parameter that was kept as an extension because its value
+    /// was invalid, so that it isn't sent twice once it is set.
+    fn remove_extension(&mut self, name: &str) {
+        self.extensions.retain(|(key, _)| key != name);
+    }
+
+    fn from_member(member: Member) -> Result<Self, Error> {
+        let item = match member {
+            Member::Item(item) => item,
+            Member::InnerList(_) => return Err(Error::invalid()),
+        };
+
+        let mut entry = match item.bare_item {
+            BareItem::Token(cache) | BareItem::String(cache) => CacheStatusEntry::new(&cache),
+            _ => return Err(Error::invalid()),
+        };
+
+        for (key, value) in item.params {
+            match (key.as_str(), &value) {
+                ("hit", BareItem::Boolean(hit)) => entry.hit = *hit,
+                ("fwd", BareItem::Token(reason)) if reason.parse::<ForwardReason>().is_ok() => {
+                    entry.fwd = reason.parse().ok();
+                }
+                ("fwd-status", BareItem::Integer(status)) if (100..1000).contains(status) => {
+                    entry.fwd_status = StatusCode::from_u16(*status as u16).ok();
+                }
+                ("ttl", BareItem::Integer(ttl)) => entry.ttl = Some(*ttl),
+                ("stored", BareItem::Boolean(stored)) => entry.stored = *stored,
+                ("collapsed", BareItem::Boolean(collapsed)) => entry.collapsed = *collapsed,
+                ("key", BareItem::String(key)) => entry.key = Some(key.clone()),
+                ("detail", BareItem::String(detail)) | ("detail", BareItem::Token(detail)) => {
+                    entry.detail = Some(detail.clone())
+                }
+                _ => entry.extensions.push((key, value)),
+            }
+        }
+
+        Ok(entry)
+    }
+
+    fn to_item(&self) -> Item {
+        let mut params = Vec::new();
+        if self.hit {
+            params.push(("hit".to_owned(), BareItem::Boolean(true)));
+        }
+        if let Some(fwd) = self.fwd {
+            params.push(("fwd".to_owned(), BareItem::Token(fwd.as_str().to_owned())));
+        }
+        if let Some(status) = self.fwd_status {
+            params.push((
+                "fwd-status".to_owned(),
+                BareItem::Integer(status.as_u16().into()),
+            ));
+        }
+        if let Some(ttl) = self.ttl {
+            params.push(("ttl".to_owned(), BareItem::Integer(ttl)));
+        }
+        if self.stored {
+            params.push(("stored".to_owned(), BareItem::Boolean(true)));
+        }
+        if self.collapsed {
+            params.push(("collapsed".to_owned(), BareItem::Boolean(true)));
+        }
+        if let Some(ref key) = self.key {
+            params.push(("key".to_owned(), BareItem::String(key.clone())));
+        }
+        if let Some(ref detail) = self.detail {
+            params.push(("detail".to_owned(), token_or_string(detail)));
+        }
+        params.extend(self.extensions.iter().cloned());
+
+        Item {
+            bare_item: token_or_string(&self.cache),
+            params,
+        }
+    }
+}
+
+impl fmt::Display for CacheStatusEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&FmtItem(&self.to_item()), f)
+    }
+}
+
+fn token_or_string(s: &str) -> BareItem {
+    if structured::is_token(s) {
+        BareItem::Token(s.to_owned())
+    } else {
+        BareItem::String(s.to_owned())
+    }
+}
+
+impl ForwardReason {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            ForwardReason::Bypass => "bypass",
+            ForwardReason::Method => "method",
+            ForwardReason::UriMiss => "uri-miss",
+            ForwardReason::VaryMiss => "vary-miss",
+            ForwardReason::Miss => "miss",
+            ForwardReason::Request => "request",
+            ForwardReason::Stale => "stale",
+            ForwardReason::Partial => "partial",
+        }
+    }
+}
+
+impl fmt::Display for ForwardReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ForwardReason {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "bypass" => Ok(ForwardReason::Bypass),
+            "method" => Ok(ForwardReason::Method),
+            "uri-miss" => Ok(ForwardReason::UriMiss),
+            "vary-miss" => Ok(ForwardReason::VaryMiss),
+            "miss" => Ok(ForwardReason::Miss),
+            "request" => Ok(ForwardReason::Request),
+            "stale" => Ok(ForwardReason::Stale),
+            "partial" => Ok(ForwardReason::Partial),
+            _ => Err(Error::invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+    use crate::HeaderMapExt;
+
+    #[test]
+    fn parse_entries() {
+        let status = test_decode::<CacheStatus>(&[
+            "OriginCache; hit; ttl=1100",
+            "\"CDN Company Here\"; fwd=uri-miss; fwd-status=200; stored; collapsed; key=\"/a\"; detail=mem",
+        ])
+        .unwrap();
+
+        let entries = status.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].cache(), "OriginCache");
+        assert!(entries[0].hit());
+        assert_eq!(entries[0].ttl(), Some(1100));
+        assert_eq!(entries[0].fwd(), None);
+
+        assert_eq!(entries[1].cache(), "CDN Company Here");
+        assert!(!entries[1].hit());
+        assert_eq!(entries[1].fwd(), Some(ForwardReason::UriMiss));
+        assert_eq!(entries[1].fwd_status(), Some(StatusCode::OK));
+        assert!(entries[1].stored());
+        assert!(entries[1].collapsed());
+        assert_eq!(entries[1].key(), Some("/a"));
+        assert_eq!(entries[1].detail(), Some("mem"));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(test_decode::<CacheStatus>(&[""]), None);
+        assert_eq!(test_decode::<CacheStatus>(&["(a b)"]), None);
+        assert_eq!(test_decode::<CacheStatus>(&["1; hit"]), None);
+        assert_eq!(test_decode::<CacheStatus>(&["Cache; hit="]), None);
+    }
+
+    #[test]
+    fn unknown_params_are_kept() {
+        let status = test_decode::<CacheStatus>(&[
+            "Cache; fwd=later; ttl=\"soon\"; x-region=eu; fwd-status=1000",
+        ])
+        .unwrap();
+        let entry = &status.entries()[0];
+        assert_eq!(entry.fwd(), None);
+        assert_eq!(entry.ttl(), None);
+        assert_eq!(entry.fwd_status(), None);
+
+        let headers = test_encode(status);
+        assert_eq!(
+            headers["cache-status"],
+            "Cache;fwd=later;ttl=\"soon\";x-region=eu;fwd-status=1000"
+        );
+    }
+
+    #[test]
+    fn setters_replace_unknown_params() {
+        let status = test_decode::<CacheStatus>(&[
+            "Cache; hit=1; fwd=later; fwd-status=1000; ttl=\"soon\"; stored=?0; key=k; detail=?1",
+        ])
+        .unwrap();
+        let entry = status.entries()[0]
+            .clone()
+            .with_hit()
+            .with_fwd(ForwardReason::Miss)
+            .with_fwd_status(StatusCode::OK)
+            .with_ttl(10)
+            .with_key("k")
+            .with_detail("d");
+
+        let headers = test_encode(vec![entry].into_iter().collect::<CacheStatus>());
+        assert_eq!(
+            headers["cache-status"],
+            "Cache;hit;fwd=miss;fwd-status=200;ttl=10;key=\"k\";detail=d"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid ttl")]
+    fn ttl_out_of_range() {
+        let _ = CacheStatusEntry::new("Cache").with_ttl(1_000_000_000_000_000);
+    }
+
+    #[test]
+    fn encode_entries() {
+        let status = vec![
+            CacheStatusEntry::new("OriginCache")
+                .with_hit()
+                .with_ttl(-30),
+            CacheStatusEntry::new("Edge Cache")
+                .with_fwd(ForwardReason::Stale)
+                .with_fwd_status(StatusCode::NOT_MODIFIED)
+                .with_stored()
+                .with_key("GET /a")
+                .with_detail("revalidated"),
+        ]
+        .into_iter()
+        .collect::<CacheStatus>();
+
+        let headers = test_encode(status);
+        assert_eq!(
+            headers["cache-status"],
+            "OriginCache;hit;ttl=-30, \"Edge Cache\";fwd=stale;fwd-status=304;stored;key=\"GET /a\";detail=revalidated"
+        );
+    }
+
+    #[test]
+    fn append_preserves_upstream() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "cache-status",
+            "Origin; hit; x-unknown=:AQI=:".parse().unwrap(),
+        );
+        CacheStatus::append(&mut headers, CacheStatusEntry::new("Edge").with_hit());
+
+        let values = headers.get_all("cache-status").iter().collect::<Vec<_>>();
+        assert_eq!(values, ["Origin; hit; x-unknown=:AQI=:", "Edge;hit"]);
+
+        let status = headers.typed_get::<CacheStatus>().unwrap();
+        assert_eq!(status.entries().len(), 2);
+        assert_eq!(status.entries()[1].cache(), "Edge");
+    }
+
+    bench_header!(
+        bench,
+        CacheStatus,
+        "OriginCache; hit; ttl=1100, \"CDN Company Here\"; fwd=uri-miss; stored"
+    );
+}
@@ -0,0 +1,230 @@
+use http::{HeaderMap, HeaderName, HeaderValue};
+
+use super::CacheControl;
+use crate::{Error, Header};
+
+static CDN_CACHE_CONTROL: HeaderName = HeaderName::from_static("cdn-cache-control");
+
+/// `CDN-Cache-Control` header, defined in
+/// [RFC9213](https://www.rfc-editor.org/rfc/rfc9213)
+///
+/// The `CDN-Cache-Control` header field is a targeted cache-control field,
+/// that only applies to content delivery networks. It has the same
+/// directives as `Cache-Control`, using the syntax of a structured field
+/// dictionary.
+///
+/// Other targeted fields, such as `Vercel-CDN-Cache-Control`, can be read
+/// and written with [`decode_targeted`](CdnCacheControl::decode_targeted)
+/// and [`encode_targeted`](CdnCacheControl::encode_targeted).
+///
+/// # Example values
+///
+/// * `max-age=600, stale-while-revalidate=30`
+/// * `no-store`
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use headers::{CacheControl, CdnCacheControl};
+///
+/// let cdn = CdnCacheControl::new(
+///     CacheControl::new().with_max_age(Duration::from_secs(600)),
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CdnCacheControl(CacheControl);
+
+impl Header for CdnCacheControl {
+    fn name() -> &'static HeaderName {
+        &CDN_CACHE_CONTROL
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        CacheControl::decode_targeted(values).map(CdnCacheControl)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(::std::iter::once(self.0.encode_targeted()));
+    }
+}
+
+impl CdnCacheControl {
+    /// Create a `CDN-Cache-Control` header with the given directives.
+    pub fn new(directives: CacheControl) -> Self {
+        CdnCacheControl(directives)
+    }
+
+    /// Get the directives of this header.
+    pub fn directives(&self) -> &CacheControl {
+        &self.0
+    }
+
+    /// Decode a targeted cache-control field with the given name.
+    ///
+    /// Returns `None` if the field is absent or invalid.
+    pub fn decode_targeted(headers: &HeaderMap, name: &HeaderName) -> Option<Self> {
+        let mut values = headers.get_all(name).iter().peekable();
+        values.peek()?;
+        CacheControl::decode_targeted(values)
+            .ok()
+            .map(CdnCacheControl)
+    }
+
+    /// Encode the directives as a targeted cache-control field with the
+    /// given name, replacing any existing value.
+    pub fn encode_targeted(&self, headers: &mut HeaderMap, name: HeaderName) {
+        headers.insert(name, self.0.encode_targeted());
+    }
+
+    /// Select the directives that apply to a cache, given the targeted
+    /// fields it obeys, in order of precedence.
+    ///
+    /// The first of these fields that is present and valid wins, otherwise
+    /// the `Cache-Control` header is used. Returns `None` if there are no
+    /// directives at all.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use headers::{CdnCacheControl, HeaderMap, HeaderName};
+    ///
+    /// let mut res = HeaderMap::new();
+    /// res.insert("cache-control", "no-store".parse().unwrap());
+    /// res.insert("cdn-cache-control", "max-age=600".parse().unwrap());
+    ///
+    /// let targets = [
+    ///     HeaderName::from_static("vercel-cdn-cache-control"),
+    ///     HeaderName::from_static("cdn-cache-control"),
+    /// ];
+    /// let cc = CdnCacheControl::effective(&res, &targets).unwrap();
+    /// assert!(!cc.no_store());
+    /// assert_eq!(cc.max_age().unwrap().as_secs(), 600);
+    ///
+    /// // The browser only obeys `Cache-Control`.
+    /// let cc = CdnCacheControl::effective(&res, &[]).unwrap();
+    /// assert!(cc.no_store());
+    /// ```
+    pub fn effective(headers: &HeaderMap, targets: &[HeaderName]) -> Option<CacheControl> {
+        targets
+            .iter()
+            .find_map(|name| Self::decode_targeted(headers, name))
+            .map(|targeted| targeted.0)
+            .or_else(|| crate::HeaderMapExt::typed_get(headers))
+    }
+}
+
+impl From<CacheControl> for CdnCacheControl {
+    fn from(directives: CacheControl) -> Self {
+        CdnCacheControl(directives)
+    }
+}
+
+impl From<CdnCacheControl> for CacheControl {
+    fn from(cdn: CdnCacheControl) -> Self {
+        cdn.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn decode() {
+        let cdn = test_decode::<CdnCacheControl>(&[
+            "max-age=600, stale-while-revalidate=30, no-cache=\"set-cookie\", public",
+        ])
+        .unwrap();
+        let cc = cdn.directives();
+        assert_eq!(cc.max_age(), Some(Duration::from_secs(600)));
+        assert_eq!(cc.stale_while_revalidate(), Some(Duration::from_secs(30)));
+        assert_eq!(cc.no_cache_fields(), &[::http::header::SET_COOKIE]);
+        assert!(cc.public());
+    }
+
+    #[test]
+    fn decode_ignores_invalid_directives() {
+        let cdn = test_decode::<CdnCacheControl>(&[
+            "max-age=-1, s-maxage=1.5, private=?0, no-store;foo=1, bar=(a b)",
+        ])
+        .unwrap();
+        assert_eq!(
+            cdn,
+            CdnCacheControl::new(CacheControl::new().with_no_store())
+        );
+    }
+
+    #[test]
+    fn decode_invalid() {
+        // Not a structured field dictionary.
+        assert_eq!(test_decode::<CdnCacheControl>(&["max-age=\"60"]), None);
+        assert_eq!(test_decode::<CdnCacheControl>(&["Max-Age=60"]), None);
+    }
+
+    #[test]
+    fn encode() {
+        let cdn = CdnCacheControl::new(
+            CacheControl::new()
+                .with_public()
+                .with_max_age(Duration::from_secs(600))
+                .with_private_fields(vec![::http::header::SET_COOKIE])
+                .with_extension("Foo", Some("bar baz"))
+                .with_extension("level", Some("3"))
+                .with_extension("tag", Some("a/b")),
+        );
+        let headers = test_encode(cdn.clone());
+        assert_eq!(
+            headers["cdn-cache-control"],
+            "public, max-age=600, private=\"set-cookie\", foo=\"bar baz\", level=3, tag=a/b"
+        );
+
+        let decoded =
+            test_decode::<CdnCacheControl>(&[headers["cdn-cache-control"].to_str().unwrap()])
+                .unwrap();
+        assert!(decoded.directives().public());
+        assert_eq!(decoded.directives().extension("foo"), Some(Some("bar baz")));
+        assert_eq!(decoded.directives().extension("level"), Some(Some("3")));
+    }
+
+    #[test]
+    fn targeted() {
+        let vercel = HeaderName::from_static("vercel-cdn-cache-control");
+        let mut headers = HeaderMap::new();
+        CdnCacheControl::new(CacheControl::new().with_max_age(Duration::from_secs(5)))
+            .encode_targeted(&mut headers, vercel.clone());
+        assert_eq!(headers["vercel-cdn-cache-control"], "max-age=5");
+
+        let cdn = CdnCacheControl::decode_targeted(&headers, &vercel).unwrap();
+        assert_eq!(cdn.directives().max_age(), Some(Duration::from_secs(5)));
+        assert_eq!(
+            CdnCacheControl::decode_targeted(&headers, &CDN_CACHE_CONTROL),
+            None
+        );
+    }
+
+    #[test]
+    fn effective_skips_invalid_targeted_fields() {
+        let mut headers = HeaderMap::new();
+        headers.insert("cache-control", "max-age=10".parse().unwrap());
+        headers.insert("cdn-cache-control", "max-age=\"60".parse().unwrap());
+
+        let cc =
+            CdnCacheControl::effective(&headers, std::slice::from_ref(&CDN_CACHE_CONTROL)).unwrap();
+        assert_eq!(cc.max_age(), Some(Duration::from_secs(10)));
+
+        assert_eq!(
+            CdnCacheControl::effective(&HeaderMap::new(), std::slice::from_ref(&CDN_CACHE_CONTROL)),
+            None
+        );
+    }
+
+    bench_header!(
+        bench,
+        CdnCacheControl,
+        "max-age=600, stale-while-revalidate=30"
+    );
+}
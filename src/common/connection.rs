@@ -88,6 +88,10 @@ impl Connection {
         let s = name.as_connection_option();
         self.0.iter().any(|opt| opt.eq_ignore_ascii_case(s))
     }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter()
+    }
 }
 
 impl FromIterator<HeaderName> for Connection {
@@ -1,7 +1,10 @@
+use std::convert::TryFrom;
+
 use http::HeaderValue;
 
 use self::sealed::AsCoding;
-use crate::util::FlatCsv;
+use crate::util::{self, Encoding, FlatCsv};
+use crate::Error;
 
 /// `Content-Encoding` header, defined in
 /// [RFC7231](https://datatracker.ietf.org/doc/html/rfc7231#section-3.1.2.2)
@@ -65,6 +68,7 @@ impl ContentEncoding {
     /// This can be used with these argument types:
     ///
     /// - `&str`
+    /// - `Encoding`
     ///
     /// # Example
     ///
@@ -82,7 +86,29 @@ impl ContentEncoding {
     }
 }
 
+impl TryFrom<Encoding> for ContentEncoding {
+    type Error = Error;
+
+    /// Create a `Content-Encoding` header with a single coding.
+    ///
+    /// Fails if the coding is not a content coding, such as `identity`,
+    /// the transfer codings `chunked` and `trailers`, or the `*` wildcard
+    /// of `Accept-Encoding`. An `Encoding::EncodingExt` must be a token.
+    fn try_from(coding: Encoding) -> Result<Self, Error> {
+        let not_content_codings = [Encoding::Identity, Encoding::Chunked, Encoding::Trailers];
+        if coding.is_any()
+            || !util::is_token(coding.as_str())
+            || not_content_codings.contains(&coding)
+        {
+            return Err(Error::invalid());
+        }
+        Ok(ContentEncoding(util::fmt(coding).into()))
+    }
+}
+
 mod sealed {
+    use crate::util::Encoding;
+
     pub trait AsCoding: Sealed {}
 
     pub trait Sealed {
@@ -96,4 +122,40 @@ mod sealed {
             self
         }
     }
+
+    impl AsCoding for Encoding {}
+
+    impl Sealed for Encoding {
+        fn as_coding(&self) -> &str {
+            self.as_str()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_encode;
+    use super::*;
+
+    #[test]
+    fn from_encoding() {
+        let content_enc = ContentEncoding::try_from(Encoding::Zstd).unwrap();
+        assert!(content_enc.contains(Encoding::Zstd));
+        assert!(!content_enc.contains(Encoding::Gzip));
+
+        let headers = test_encode(content_enc);
+        assert_eq!(headers["content-encoding"], "zstd");
+
+        let invalid = Encoding::EncodingExt("a\r\nb".to_owned());
+        assert!(ContentEncoding::try_from(invalid).is_err());
+        let any = Encoding::EncodingExt("*".to_owned());
+        assert!(ContentEncoding::try_from(any).is_err());
+
+        assert!(ContentEncoding::try_from(Encoding::Identity).is_err());
+        assert!(ContentEncoding::try_from(Encoding::Chunked).is_err());
+        assert!(ContentEncoding::try_from(Encoding::Trailers).is_err());
+        // Extensions are compared case-insensitively, like the known codings.
+        let identity = Encoding::EncodingExt("IDENTITY".to_owned());
+        assert!(ContentEncoding::try_from(identity).is_err());
+    }
 }
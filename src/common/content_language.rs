@@ -0,0 +1,86 @@
+use std::iter::FromIterator;
+
+use crate::util::{self, FlatCsv, LanguageTag};
+
+/// `Content-Language` header, defined in
+/// [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-8.5)
+///
+/// The `Content-Language` header field describes the natural language(s)
+/// of the intended audience for the representation.  Note that this
+/// might not be equivalent to all the languages used within the
+/// representation.
+///
+/// # ABNF
+///
+/// ```text
+/// Content-Language = #language-tag
+/// ```
+///
+/// # Example values
+///
+/// * `da`
+/// * `mi, en`
+///
+/// # Examples
+///
+/// ```
+/// use headers::{ContentLanguage, LanguageTag};
+///
+/// let con_lang = vec!["mi".parse::<LanguageTag>().unwrap(), "en".parse().unwrap()]
+///     .into_iter()
+///     .collect::<ContentLanguage>();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContentLanguage(FlatCsv);
+
+derive_header! {
+    ContentLanguage(_),
+    name: CONTENT_LANGUAGE
+}
+
+impl ContentLanguage {
+    /// Returns an iterator over the `LanguageTag`s contained within.
+    pub fn iter(&self) -> impl Iterator<Item = LanguageTag> + '_ {
+        self.0.iter().filter_map(|s| s.parse().ok())
+    }
+}
+
+impl FromIterator<LanguageTag> for ContentLanguage {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = LanguageTag>,
+    {
+        let flat = iter.into_iter().map(util::fmt).collect();
+        ContentLanguage(flat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn decode() {
+        let con_lang = test_decode::<ContentLanguage>(&["mi, en", "de-CH"]).unwrap();
+        let tags = con_lang.iter().collect::<Vec<_>>();
+        assert_eq!(
+            tags,
+            vec![
+                "mi".parse::<LanguageTag>().unwrap(),
+                "en".parse().unwrap(),
+                "de-ch".parse().unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn encode() {
+        let con_lang = vec!["da".parse::<LanguageTag>().unwrap()]
+            .into_iter()
+            .collect::<ContentLanguage>();
+
+        let headers = test_encode(con_lang);
+        assert_eq!(headers["content-language"], "da");
+    }
+}
@@ -0,0 +1,690 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::iter::FromIterator;
+
+use http::{HeaderName, HeaderValue};
+
+use crate::util::{self, is_scheme};
+use crate::{Error, Header};
+
+/// `Content-Security-Policy` header, defined in
+/// [CSP3](https://www.w3.org/TR/CSP3/#csp-header)
+///
+/// The `Content-Security-Policy` header field restricts the resources a
+/// document may load and what it may do, such as where scripts are loaded
+/// from. Each policy is a list of directives, separated by semicolons, and
+/// most directives take a list of allowed sources.
+///
+/// The header can hold several policies, either in separate field lines or
+/// separated by commas. They are kept separate, since a resource must be
+/// allowed by each of them.
+///
+/// # ABNF
+///
+/// ```text
+/// Content-Security-Policy = 1#serialized-policy
+/// serialized-policy = serialized-directive *( OWS ";" [ OWS serialized-directive ] )
+/// serialized-directive = directive-name [ RWS directive-value ]
+/// ```
+///
+/// # Example values
+///
+/// * `default-src 'self'`
+/// * `script-src 'self' https://cdn.example.com; frame-ancestors 'none'`
+///
+/// # Example
+///
+/// ```
+/// use headers::{ContentSecurityPolicy, CspPolicy, CspSource};
+///
+/// let csp = ContentSecurityPolicy::new(
+///     CspPolicy::new()
+///         .with_sources("default-src", vec![CspSource::SELF])
+///         .with_sources("frame-ancestors", vec![CspSource::NONE])
+///         .with_report_to("csp-endpoint"),
+/// )
+/// .with_nonce("rAnd0m");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContentSecurityPolicy(Vec<CspPolicy>);
+
+/// A single policy of a `Content-Security-Policy` or
+/// `Content-Security-Policy-Report-Only` header.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CspPolicy {
+    directives: Vec<CspDirective>,
+}
+
+/// A directive of a policy, such as `script-src 'self'`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CspDirective {
+    name: String,
+    values: Vec<String>,
+}
+
+/// A source expression in the value of a directive, such as `'self'` or
+/// `https://example.com`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CspSource(Source);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Source {
+    /// A quoted keyword, in lowercase and without the quotes.
+    Keyword(Cow<'static, str>),
+    Nonce(String),
+    Hash(CspHashAlgorithm, String),
+    /// A scheme, without the colon.
+    Scheme(String),
+    Host(String),
+}
+
+/// The hash algorithm of a hash source, such as `'sha256-…'`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CspHashAlgorithm {
+    /// SHA-256
+    Sha256,
+    /// SHA-384
+    Sha384,
+    /// SHA-512
+    Sha512,
+}
+
+impl Header for ContentSecurityPolicy {
+    fn name() -> &'static HeaderName {
+        &::http::header::CONTENT_SECURITY_POLICY
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        decode_policies(values).map(ContentSecurityPolicy)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(self.0.iter().map(util::fmt));
+    }
+}
+
+impl ContentSecurityPolicy {
+    /// Create a `Content-Security-Policy` header with a single policy.
+    pub fn new(policy: CspPolicy) -> Self {
+        ContentSecurityPolicy(vec![policy])
+    }
+
+    /// Iterate the policies of this header.
+    pub fn policies(&self) -> impl Iterator<Item = &CspPolicy> {
+        self.0.iter()
+    }
+
+    /// Allow scripts and styles with the given nonce in every policy.
+    ///
+    /// See [`CspPolicy::with_nonce`].
+    pub fn with_nonce(self, nonce: &str) -> Self {
+        ContentSecurityPolicy(inject_nonce(self.0, nonce))
+    }
+}
+
+impl From<CspPolicy> for ContentSecurityPolicy {
+    fn from(policy: CspPolicy) -> Self {
+        ContentSecurityPolicy::new(policy)
+    }
+}
+
+impl FromIterator<CspPolicy> for ContentSecurityPolicy {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = CspPolicy>,
+    {
+        ContentSecurityPolicy(iter.into_iter().collect())
+    }
+}
+
+pub(super) fn decode_policies<'i, I>(values: I) -> Result<Vec<CspPolicy>, Error>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let mut policies = Vec::new();
+    for value in values {
+        let value = value.to_str().map_err(|_| Error::invalid())?;
+        policies.extend(
+            value
+                .split(',')
+                .map(CspPolicy::parse)
+                .filter(|policy| !policy.directives.is_empty()),
+        );
+    }
+
+    if policies.is_empty() {
+        Err(Error::invalid())
+    } else {
+        Ok(policies)
+    }
+}
+
+pub(super) fn inject_nonce(policies: Vec<CspPolicy>, nonce: &str) -> Vec<CspPolicy> {
+    policies
+        .into_iter()
+        .map(|policy| policy.with_nonce(nonce))
+        .collect()
+}
+
+impl CspPolicy {
+    /// Create an empty policy.
+    pub fn new() -> Self {
+        CspPolicy::default()
+    }
+
+    /// Get the directive with the given name, such as `"script-src"`.
+    pub fn get(&self, name: &str) -> Option<&CspDirective> {
+        self.directives
+            .iter()
+            .find(|directive| directive.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Iterate the directives of this policy.
+    pub fn directives(&self) -> impl Iterator<Item = &CspDirective> {
+        self.directives.iter()
+    }
+
+    /// Set a directive with a source list, such as `script-src`, replacing
+    /// any previous one with the same name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not a valid directive name.
+    pub fn with_sources<I>(self, name: &str, sources: I) -> Self
+    where
+        I: IntoIterator<Item = CspSource>,
+    {
+        let values = sources.into_iter().map(|source| source.to_string());
+        self.with_values(name, values.collect())
+    }
+
+    /// Set a directive, replacing any previous one with the same name.
+    ///
+    /// This is used for directives without a source list, such as
+    /// `sandbox allow-scripts` or `upgrade-insecure-requests`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not a valid directive name, or if a value is
+    /// empty or contains whitespace, `;` or `,`.
+    pub fn with_directive(self, name: &str, values: &[&str]) -> Self {
+        for value in values {
+            assert!(is_directive_value(value), "invalid directive value");
+        }
+        let values = values.iter().map(|value| (*value).to_owned());
+        self.with_values(name, values.collect())
+    }
+
+    /// Set the `report-to` directive, naming the reporting endpoint that
+    /// violations are sent to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group is empty or contains whitespace, `;` or `,`.
+    pub fn with_report_to(self, group: &str) -> Self {
+        self.with_directive("report-to", &[group])
+    }
+
+    /// Allow scripts and styles with the given nonce.
+    ///
+    /// The `'nonce-…'` source is added to the `script-src`,
+    /// `script-src-elem`, `style-src` and `style-src-elem` directives. If
+    /// `script-src` or `style-src` is missing, it is created from
+    /// `default-src`, which would otherwise apply. Directives that are
+    /// `'none'` are left alone, since they don't allow any source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the nonce is not a valid base64 value.
+    pub fn with_nonce(mut self, nonce: &str) -> Self {
+        let nonce = CspSource::nonce(nonce).to_string();
+
+        let default_src = self.get("default-src").cloned();
+        for &name in &["script-src", "style-src"] {
+            if self.get(name).is_none() {
+                if let Some(ref default_src) = default_src {
+                    self = self.with_values(name, default_src.values.clone());
+                }
+            }
+        }
+
+        for directive in &mut self.directives {
+            let applies = [
+                "script-src",
+                "script-src-elem",
+                "style-src",
+                "style-src-elem",
+            ]
+            .iter()
+            .any(|name| directive.name == *name);
+            let none = directive
+                .values
+                .iter()
+                .any(|value| value.eq_ignore_ascii_case("'none'"));
+            if applies && !none && !directive.values.contains(&nonce) {
+                directive.values.push(nonce.clone());
+            }
+        }
+        self
+    }
+
+    fn with_values(mut self, name: &str, values: Vec<String>) -> Self {
+        assert!(is_directive_name(name), "invalid directive name");
+        let name = name.to_ascii_lowercase();
+        match self.directives.iter_mut().find(|d| d.name == name) {
+            Some(directive) => directive.values = values,
+            None => self.directives.push(CspDirective { name, values }),
+        }
+        self
+    }
+
+    fn parse(s: &str) -> CspPolicy {
+        let mut policy = CspPolicy::new();
+        for directive in s.split(';') {
+            let mut tokens = directive.split_ascii_whitespace();
+            let name = match tokens.next() {
+                Some(name) => name.to_ascii_lowercase(),
+                None => continue,
+            };
+            // Only the first directive with a name is used.
+            if policy.get(&name).is_some() {
+                continue;
+            }
+            policy.directives.push(CspDirective {
+                name,
+                values: tokens.map(str::to_owned).collect(),
+            });
+        }
+        policy
+    }
+}
+
+impl fmt::Display for CspPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, directive) in self.directives.iter().enumerate() {
+            if i != 0 {
+                f.write_str("; ")?;
+            }
+            fmt::Display::fmt(directive, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl CspDirective {
+    /// Get the name of this directive, in lowercase.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Iterate the values of this directive.
+    pub fn values(&self) -> impl Iterator<Item = &str> {
+        self.values.iter().map(String::as_str)
+    }
+
+    /// Iterate the values of this directive as sources.
+    ///
+    /// This is only meaningful for directives with a source list, such as
+    /// `script-src` or `frame-ancestors`.
+    pub fn sources(&self) -> impl Iterator<Item = CspSource> + '_ {
+        self.values.iter().map(|value| CspSource::parse(value))
+    }
+}
+
+impl fmt::Display for CspDirective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.name)?;
+        for value in &self.values {
+            write!(f, " {}", value)?;
+        }
+        Ok(())
+    }
+}
+
+impl CspSource {
+    /// `'self'`
+    pub const SELF: CspSource = CspSource(Source::Keyword(Cow::Borrowed("self")));
+
+    /// `'none'`
+    pub const NONE: CspSource = CspSource(Source::Keyword(Cow::Borrowed("none")));
+
+    /// `'unsafe-inline'`
+    pub const UNSAFE_INLINE: CspSource = CspSource(Source::Keyword(Cow::Borrowed("unsafe-inline")));
+
+    /// `'unsafe-eval'`
+    pub const UNSAFE_EVAL: CspSource = CspSource(Source::Keyword(Cow::Borrowed("unsafe-eval")));
+
+    /// `'unsafe-hashes'`
+    pub const UNSAFE_HASHES: CspSource = CspSource(Source::Keyword(Cow::Borrowed("unsafe-hashes")));
+
+    /// `'strict-dynamic'`
+    pub const STRICT_DYNAMIC: CspSource =
+        CspSource(Source::Keyword(Cow::Borrowed("strict-dynamic")));
+
+    /// `'report-sample'`
+    pub const REPORT_SAMPLE: CspSource = CspSource(Source::Keyword(Cow::Borrowed("report-sample")));
+
+    /// `'wasm-unsafe-eval'`
+    pub const WASM_UNSAFE_EVAL: CspSource =
+        CspSource(Source::Keyword(Cow::Borrowed("wasm-unsafe-eval")));
+
+    /// A nonce source, `'nonce-…'`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the nonce is not a valid base64 value.
+    pub fn nonce(nonce: &str) -> CspSource {
+        assert!(is_base64_value(nonce), "invalid nonce");
+        CspSource(Source::Nonce(nonce.to_owned()))
+    }
+
+    /// A hash source, such as `'sha256-…'`, with the base64 encoded digest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the digest is not a valid base64 value.
+    pub fn hash(algorithm: CspHashAlgorithm, digest: &str) -> CspSource {
+        assert!(is_base64_value(digest), "invalid hash digest");
+        CspSource(Source::Hash(algorithm, digest.to_owned()))
+    }
+
+    /// A scheme source, such as `https:`, given without the colon.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scheme is not valid.
+    pub fn scheme(scheme: &str) -> CspSource {
+        assert!(is_scheme(scheme), "invalid scheme");
+        CspSource(Source::Scheme(scheme.to_ascii_lowercase()))
+    }
+
+    /// A host source, such as `*.example.com` or
+    /// `https://example.com:8443/path`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the host is empty or contains whitespace, quotes, `;` or
+    /// `,`.
+    pub fn host(host: &str) -> CspSource {
+        assert!(
+            is_directive_value(host) && !host.contains('\''),
+            "invalid host source"
+        );
+        CspSource(Source::Host(host.to_owned()))
+    }
+
+    /// Get the keyword of a keyword source, such as `"self"`, in
+    /// lowercase and without the quotes.
+    pub fn as_keyword(&self) -> Option<&str> {
+        match self.0 {
+            Source::Keyword(ref keyword) => Some(keyword),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a nonce source.
+    pub fn as_nonce(&self) -> Option<&str> {
+        match self.0 {
+            Source::Nonce(ref nonce) => Some(nonce),
+            _ => None,
+        }
+    }
+
+    /// Get the algorithm and base64 encoded digest of a hash source.
+    pub fn as_hash(&self) -> Option<(CspHashAlgorithm, &str)> {
+        match self.0 {
+            Source::Hash(algorithm, ref digest) => Some((algorithm, digest)),
+            _ => None,
+        }
+    }
+
+    /// Get the scheme of a scheme source, without the colon.
+    pub fn as_scheme(&self) -> Option<&str> {
+        match self.0 {
+            Source::Scheme(ref scheme) => Some(scheme),
+            _ => None,
+        }
+    }
+
+    /// Get a host source.
+    pub fn as_host(&self) -> Option<&str> {
+        match self.0 {
+            Source::Host(ref host) => Some(host),
+            _ => None,
+        }
+    }
+
+    fn parse(s: &str) -> CspSource {
+        if let Some(quoted) = s
+            .strip_prefix('\'')
+            .and_then(|s| s.strip_suffix('\''))
+            .filter(|s| !s.is_empty())
+        {
+            let lower = quoted.to_ascii_lowercase();
+            if let Some(nonce) = lower.strip_prefix("nonce-") {
+                if is_base64_value(nonce) {
+                    // The base64 value is case-sensitive.
+                    return CspSource(Source::Nonce(quoted[6..].to_owned()));
+                }
+            }
+            let algorithms = [
+                ("sha256-", CspHashAlgorithm::Sha256),
+                ("sha384-", CspHashAlgorithm::Sha384),
+                ("sha512-", CspHashAlgorithm::Sha512),
+            ];
+            for &(prefix, algorithm) in &algorithms {
+                if lower.starts_with(prefix) && is_base64_value(&quoted[7..]) {
+                    return CspSource(Source::Hash(algorithm, quoted[7..].to_owned()));
+                }
+            }
+            return CspSource(Source::Keyword(Cow::Owned(lower)));
+        }
+
+        match s.strip_suffix(':') {
+            Some(scheme) if is_scheme(scheme) => {
+                CspSource(Source::Scheme(scheme.to_ascii_lowercase()))
+            }
+            _ => CspSource(Source::Host(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for CspSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Source::Keyword(ref keyword) => write!(f, "'{}'", keyword),
+            Source::Nonce(ref nonce) => write!(f, "'nonce-{}'", nonce),
+            Source::Hash(algorithm, ref digest) => write!(f, "'{}-{}'", algorithm, digest),
+            Source::Scheme(ref scheme) => write!(f, "{}:", scheme),
+            Source::Host(ref host) => f.write_str(host),
+        }
+    }
+}
+
+impl fmt::Display for CspHashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            CspHashAlgorithm::Sha256 => "sha256",
+            CspHashAlgorithm::Sha384 => "sha384",
+            CspHashAlgorithm::Sha512 => "sha512",
+        })
+    }
+}
+
+/// directive-name = 1*( ALPHA / DIGIT / "-" )
+fn is_directive_name(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+}
+
+/// A single token of a directive value, which can't contain `;` or `,`.
+fn is_directive_value(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_graphic() && b != b';' && b != b',')
+}
+
+/// base64-value = 1*( ALPHA / DIGIT / "+" / "/" / "-" / "_" )*2( "=" )
+fn is_base64_value(s: &str) -> bool {
+    let value = s.trim_end_matches('=');
+    !value.is_empty()
+        && s.len() - value.len() <= 2
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'-' | b'_'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn parse_policies() {
+        let csp = test_decode::<ContentSecurityPolicy>(&[
+            "default-src 'self'; Script-Src 'self'  https://cdn.example.com 'nonce-abc+/=' ;",
+            "frame-ancestors 'none', img-src https: data:",
+        ])
+        .unwrap();
+
+        let policies = csp.policies().collect::<Vec<_>>();
+        assert_eq!(policies.len(), 3);
+
+        let script_src = policies[0].get("script-src").unwrap();
+        assert_eq!(script_src.name(), "script-src");
+        assert_eq!(
+            script_src.sources().collect::<Vec<_>>(),
+            vec![
+                CspSource::SELF,
+                CspSource::host("https://cdn.example.com"),
+                CspSource::nonce("abc+/="),
+            ]
+        );
+
+        assert_eq!(
+            policies[1].get("frame-ancestors").unwrap().sources().next(),
+            Some(CspSource::NONE)
+        );
+        assert_eq!(
+            policies[2]
+                .get("img-src")
+                .unwrap()
+                .sources()
+                .map(|source| source.as_scheme().map(str::to_owned))
+                .collect::<Vec<_>>(),
+            vec![Some("https".to_owned()), Some("data".to_owned())]
+        );
+    }
+
+    #[test]
+    fn parse_duplicates_and_empty() {
+        let csp = test_decode::<ContentSecurityPolicy>(&["script-src a; ;script-src b; sandbox"])
+            .unwrap();
+        let policy = csp.policies().next().unwrap();
+        assert_eq!(
+            policy
+                .get("script-src")
+                .unwrap()
+                .values()
+                .collect::<Vec<_>>(),
+            ["a"]
+        );
+        assert_eq!(policy.get("sandbox").unwrap().values().count(), 0);
+
+        assert_eq!(test_decode::<ContentSecurityPolicy>(&[" ; , "]), None);
+    }
+
+    #[test]
+    fn parse_sources() {
+        assert_eq!(CspSource::parse("'SELF'"), CspSource::SELF);
+        assert_eq!(
+            CspSource::parse("'sha384-AbC='").as_hash(),
+            Some((CspHashAlgorithm::Sha384, "AbC="))
+        );
+        assert_eq!(CspSource::parse("'Nonce-AbC'").as_nonce(), Some("AbC"));
+        assert_eq!(
+            CspSource::parse("'nonce-a b'").as_keyword(),
+            Some("nonce-a b")
+        );
+        assert_eq!(CspSource::parse("*").as_host(), Some("*"));
+        assert_eq!(
+            CspSource::parse("example.com:443").as_host(),
+            Some("example.com:443")
+        );
+    }
+
+    #[test]
+    fn builder() {
+        let policy = CspPolicy::new()
+            .with_sources("default-src", vec![CspSource::SELF])
+            .with_sources(
+                "script-src",
+                vec![
+                    CspSource::STRICT_DYNAMIC,
+                    CspSource::hash(CspHashAlgorithm::Sha256, "abc="),
+                    CspSource::scheme("HTTPS"),
+                ],
+            )
+            .with_directive("upgrade-insecure-requests", &[])
+            .with_sources("default-src", vec![CspSource::NONE])
+            .with_report_to("csp");
+
+        let headers = test_encode(ContentSecurityPolicy::new(policy));
+        assert_eq!(
+            headers["content-security-policy"],
+            "default-src 'none'; script-src 'strict-dynamic' 'sha256-abc=' https:; upgrade-insecure-requests; report-to csp"
+        );
+    }
+
+    #[test]
+    fn separate_policies_are_kept() {
+        let csp = vec![
+            CspPolicy::new().with_sources("default-src", vec![CspSource::SELF]),
+            CspPolicy::new().with_sources("img-src", vec![CspSource::host("*")]),
+        ]
+        .into_iter()
+        .collect::<ContentSecurityPolicy>();
+
+        let headers = test_encode(csp.clone());
+        let values = headers
+            .get_all("content-security-policy")
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(values, ["default-src 'self'", "img-src *"]);
+        assert_eq!(test_decode(&["default-src 'self'", "img-src *"]), Some(csp));
+    }
+
+    #[test]
+    fn nonce_injection() {
+        let csp = test_decode::<ContentSecurityPolicy>(&[
+            "default-src 'self'; style-src 'self' 'nonce-abc'",
+            "script-src-elem 'none'; style-src-elem https:",
+        ])
+        .unwrap()
+        .with_nonce("abc");
+
+        let headers = test_encode(csp);
+        let values = headers
+            .get_all("content-security-policy")
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [
+                "default-src 'self'; style-src 'self' 'nonce-abc'; script-src 'self' 'nonce-abc'",
+                "script-src-elem 'none'; style-src-elem https: 'nonce-abc'",
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid nonce")]
+    fn invalid_nonce() {
+        let _ = CspPolicy::new().with_nonce("a b");
+    }
+
+    bench_header!(
+        bench,
+        ContentSecurityPolicy,
+        "default-src 'self'; script-src 'self' https://cdn.example.com; frame-ancestors 'none'"
+    );
+}
@@ -0,0 +1,114 @@
+use std::iter::FromIterator;
+
+use http::{HeaderName, HeaderValue};
+
+use super::content_security_policy::{decode_policies, inject_nonce};
+use super::CspPolicy;
+use crate::util;
+use crate::{Error, Header};
+
+/// `Content-Security-Policy-Report-Only` header, defined in
+/// [CSP3](https://www.w3.org/TR/CSP3/#cspro-header)
+///
+/// The `Content-Security-Policy-Report-Only` header field has the same
+/// syntax as [`ContentSecurityPolicy`](super::ContentSecurityPolicy), but its
+/// policies are not enforced: violations are only reported, which allows
+/// trying out a policy before enforcing it.
+///
+/// # Example values
+///
+/// * `default-src 'self'; report-to csp-endpoint`
+///
+/// # Example
+///
+/// ```
+/// use headers::{ContentSecurityPolicyReportOnly, CspPolicy, CspSource};
+///
+/// let csp = ContentSecurityPolicyReportOnly::new(
+///     CspPolicy::new()
+///         .with_sources("script-src", vec![CspSource::SELF])
+///         .with_report_to("csp-endpoint"),
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContentSecurityPolicyReportOnly(Vec<CspPolicy>);
+
+impl Header for ContentSecurityPolicyReportOnly {
+    fn name() -> &'static HeaderName {
+        &::http::header::CONTENT_SECURITY_POLICY_REPORT_ONLY
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        decode_policies(values).map(ContentSecurityPolicyReportOnly)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(self.0.iter().map(util::fmt));
+    }
+}
+
+impl ContentSecurityPolicyReportOnly {
+    /// Create a `Content-Security-Policy-Report-Only` header with a single
+    /// policy.
+    pub fn new(policy: CspPolicy) -> Self {
+        ContentSecurityPolicyReportOnly(vec![policy])
+    }
+
+    /// Iterate the policies of this header.
+    pub fn policies(&self) -> impl Iterator<Item = &CspPolicy> {
+        self.0.iter()
+    }
+
+    /// Allow scripts and styles with the given nonce in every policy.
+    ///
+    /// See [`CspPolicy::with_nonce`].
+    pub fn with_nonce(self, nonce: &str) -> Self {
+        ContentSecurityPolicyReportOnly(inject_nonce(self.0, nonce))
+    }
+}
+
+impl From<CspPolicy> for ContentSecurityPolicyReportOnly {
+    fn from(policy: CspPolicy) -> Self {
+        ContentSecurityPolicyReportOnly::new(policy)
+    }
+}
+
+impl FromIterator<CspPolicy> for ContentSecurityPolicyReportOnly {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = CspPolicy>,
+    {
+        ContentSecurityPolicyReportOnly(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode, CspSource};
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let csp = test_decode::<ContentSecurityPolicyReportOnly>(&[
+            "script-src 'self'; report-to csp-endpoint",
+        ])
+        .unwrap();
+        let policy = csp.policies().next().unwrap();
+        assert_eq!(
+            policy.get("script-src").unwrap().sources().next(),
+            Some(CspSource::SELF)
+        );
+
+        let headers = test_encode(csp.with_nonce("xyz"));
+        assert_eq!(
+            headers["content-security-policy-report-only"],
+            "script-src 'self' 'nonce-xyz'; report-to csp-endpoint"
+        );
+    }
+
+    bench_header!(
+        bench,
+        ContentSecurityPolicyReportOnly,
+        "default-src 'self'; report-to csp-endpoint"
+    );
+}
@@ -0,0 +1,613 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::FromIterator;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use http::uri::Authority;
+use http::{HeaderName, HeaderValue};
+
+use crate::util::{self, fmt_token_or_quoted, is_ows, is_scheme, is_tchar, parse_quoted};
+use crate::{Error, Header};
+
+/// `Forwarded` header, defined in
+/// [RFC7239](https://www.rfc-editor.org/rfc/rfc7239)
+///
+/// The `Forwarded` header field carries information about the proxies a
+/// request went through, which is otherwise lost. Each proxy appends an
+/// element, so they are ordered from the client to the proxy closest to
+/// the server.
+///
+/// The values in this header are only as trustworthy as the proxies that
+/// added them. To find the address of the client, see
+/// [`TrustedProxies`](crate::proxy::TrustedProxies).
+///
+/// # ABNF
+///
+/// ```text
+/// Forwarded         = 1#forwarded-element
+/// forwarded-element = [ forwarded-pair ] *( ";" [ forwarded-pair ] )
+/// forwarded-pair    = token "=" value
+/// value             = token / quoted-string
+/// ```
+///
+/// # Example values
+///
+/// * `for=192.0.2.60;proto=http;by=203.0.113.43`
+/// * `for="[2001:db8:cafe::17]:4711"`
+/// * `for=192.0.2.43, for=unknown`
+///
+/// # Example
+///
+/// ```
+/// use std::net::{IpAddr, Ipv4Addr};
+/// use headers::{Forwarded, ForwardedElement, ForwardedNode};
+///
+/// let forwarded = Forwarded::new(
+///     ForwardedElement::new()
+///         .with_for(ForwardedNode::from(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 60))))
+///         .with_proto("https"),
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Forwarded(Vec<ForwardedElement>);
+
+/// An element of a `Forwarded` header, added by a single proxy.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ForwardedElement {
+    forwarded_for: Option<ForwardedNode>,
+    by: Option<ForwardedNode>,
+    proto: Option<String>,
+    host: Option<Authority>,
+    extensions: Vec<(String, String)>,
+}
+
+/// A node in a `Forwarded` header, the client or a proxy.
+///
+/// A node is an IP address, an obfuscated identifier such as `_hidden`, or
+/// `unknown`, with an optional port.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ForwardedNode {
+    name: NodeName,
+    port: Option<NodePort>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum NodeName {
+    Ip(IpAddr),
+    Obfuscated(String),
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum NodePort {
+    Port(u16),
+    Obfuscated(String),
+}
+
+impl Header for Forwarded {
+    fn name() -> &'static HeaderName {
+        &::http::header::FORWARDED
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let mut elements = Vec::new();
+        for value in values {
+            let value = value.to_str().map_err(|_| Error::invalid())?;
+            parse_elements(value, &mut elements)?;
+        }
+
+        if elements.is_empty() {
+            Err(Error::invalid())
+        } else {
+            Ok(Forwarded(elements))
+        }
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let value = self
+            .0
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        values.extend(::std::iter::once(util::fmt(value)));
+    }
+}
+
+impl Forwarded {
+    /// Create a `Forwarded` header with a single element.
+    pub fn new(element: ForwardedElement) -> Self {
+        Forwarded(vec![element])
+    }
+
+    /// Iterate the elements of this header, from the client to the proxy
+    /// closest to the server.
+    pub fn elements(&self) -> impl Iterator<Item = &ForwardedElement> {
+        self.0.iter()
+    }
+
+    /// Append the element of another proxy.
+    pub fn push(&mut self, element: ForwardedElement) {
+        self.0.push(element);
+    }
+}
+
+impl From<ForwardedElement> for Forwarded {
+    fn from(element: ForwardedElement) -> Self {
+        Forwarded::new(element)
+    }
+}
+
+impl FromIterator<ForwardedElement> for Forwarded {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = ForwardedElement>,
+    {
+        Forwarded(iter.into_iter().collect())
+    }
+}
+
+fn parse_elements(s: &str, elements: &mut Vec<ForwardedElement>) -> Result<(), Error> {
+    let mut element = ForwardedElement::new();
+    let mut rest = s;
+    loop {
+        rest = rest.trim_start_matches(is_ows);
+        let sep = rest.chars().next();
+        if sep == Some(',') || sep.is_none() {
+            if element != ForwardedElement::new() {
+                elements.push(element);
+            }
+            element = ForwardedElement::new();
+            match sep {
+                Some(_) => {
+                    rest = &rest[1..];
+                    continue;
+                }
+                None => return Ok(()),
+            }
+        }
+        if sep == Some(';') {
+            rest = &rest[1..];
+            continue;
+        }
+
+        let name_len = rest
+            .bytes()
+            .position(|b| !is_tchar(b))
+            .unwrap_or(rest.len());
+        if name_len == 0 || !rest[name_len..].starts_with('=') {
+            return Err(Error::invalid());
+        }
+        let name = rest[..name_len].to_ascii_lowercase();
+        rest = &rest[name_len + 1..];
+
+        let value = if rest.starts_with('"') {
+            let (value, after) = parse_quoted(rest).ok_or_else(Error::invalid)?;
+            rest = after;
+            value
+        } else {
+            let len = rest
+                .bytes()
+                .position(|b| !is_tchar(b))
+                .unwrap_or(rest.len());
+            if len == 0 {
+                return Err(Error::invalid());
+            }
+            let value = rest[..len].to_owned();
+            rest = &rest[len..];
+            value
+        };
+
+        element.set(name, value)?;
+
+        let after = rest.trim_start_matches(is_ows);
+        if !(after.is_empty() || after.starts_with(',') || after.starts_with(';')) {
+            return Err(Error::invalid());
+        }
+    }
+}
+
+impl ForwardedElement {
+    /// Create an empty element.
+    pub fn new() -> Self {
+        ForwardedElement::default()
+    }
+
+    /// Get the `for` parameter, the node that made the request to the
+    /// proxy.
+    pub fn forwarded_for(&self) -> Option<&ForwardedNode> {
+        self.forwarded_for.as_ref()
+    }
+
+    /// Get the `by` parameter, the interface of the proxy that received the
+    /// request.
+    pub fn by(&self) -> Option<&ForwardedNode> {
+        self.by.as_ref()
+    }
+
+    /// Get the `proto` parameter, the scheme used by the incoming request,
+    /// such as `https`.
+    pub fn proto(&self) -> Option<&str> {
+        self.proto.as_deref()
+    }
+
+    /// Get the `host` parameter, the `Host` header of the incoming request.
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_ref().map(Authority::as_str)
+    }
+
+    /// Get the value of an extension parameter.
+    pub fn extension(&self, name: &str) -> Option<&str> {
+        self.extensions
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Set the `for` parameter.
+    pub fn with_for(mut self, node: ForwardedNode) -> Self {
+        self.forwarded_for = Some(node);
+        self
+    }
+
+    /// Set the `by` parameter.
+    pub fn with_by(mut self, node: ForwardedNode) -> Self {
+        self.by = Some(node);
+        self
+    }
+
+    /// Set the `proto` parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scheme is not valid.
+    pub fn with_proto(mut self, proto: &str) -> Self {
+        assert!(is_scheme(proto), "invalid proto");
+        self.proto = Some(proto.to_owned());
+        self
+    }
+
+    /// Set the `host` parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the host is not a valid `Host` header value.
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.host = Some(parse_host(host).expect("invalid host"));
+        self
+    }
+
+    fn set(&mut self, name: String, value: String) -> Result<(), Error> {
+        // Each parameter can only occur once in an element.
+        let duplicate = match name.as_str() {
+            "for" => self.forwarded_for.replace(value.parse()?).is_some(),
+            "by" => self.by.replace(value.parse()?).is_some(),
+            "proto" if is_scheme(&value) => self.proto.replace(value).is_some(),
+            "proto" => return Err(Error::invalid()),
+            "host" => self.host.replace(parse_host(&value)?).is_some(),
+            _ => {
+                let duplicate = self.extension(&name).is_some();
+                self.extensions.push((name, value));
+                duplicate
+            }
+        };
+        if duplicate {
+            Err(Error::invalid())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for ForwardedElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut pairs = Vec::new();
+        if let Some(ref node) = self.forwarded_for {
+            pairs.push(("for", node.to_string()));
+        }
+        if let Some(ref node) = self.by {
+            pairs.push(("by", node.to_string()));
+        }
+        if let Some(ref proto) = self.proto {
+            pairs.push(("proto", proto.clone()));
+        }
+        if let Some(ref host) = self.host {
+            pairs.push(("host", host.to_string()));
+        }
+        for (name, value) in &self.extensions {
+            pairs.push((name, value.clone()));
+        }
+
+        for (i, (name, value)) in pairs.iter().enumerate() {
+            if i != 0 {
+                f.write_str(";")?;
+            }
+            write!(f, "{}=", name)?;
+            fmt_token_or_quoted(f, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl ForwardedNode {
+    /// The `unknown` node, used when the identity of a node is unknown but
+    /// the proxy still wants to signal that it forwarded the request.
+    pub const UNKNOWN: ForwardedNode = ForwardedNode {
+        name: NodeName::Unknown,
+        port: None,
+    };
+
+    /// Create an obfuscated node, such as `_hidden`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the identifier doesn't start with `_`, or contains
+    /// characters other than letters, digits, `.`, `_` and `-`.
+    pub fn obfuscated(identifier: &str) -> ForwardedNode {
+        assert!(is_obfuscated(identifier), "invalid obfuscated identifier");
+        ForwardedNode {
+            name: NodeName::Obfuscated(identifier.to_owned()),
+            port: None,
+        }
+    }
+
+    /// Set the port of this node.
+    pub fn with_port(mut self, port: u16) -> ForwardedNode {
+        self.port = Some(NodePort::Port(port));
+        self
+    }
+
+    /// Get the IP address of this node, if it isn't obfuscated or unknown.
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self.name {
+            NodeName::Ip(ip) => Some(ip),
+            _ => None,
+        }
+    }
+
+    /// Get the port of this node, if it has one that isn't obfuscated.
+    pub fn port(&self) -> Option<u16> {
+        match self.port {
+            Some(NodePort::Port(port)) => Some(port),
+            _ => None,
+        }
+    }
+
+    /// Get the obfuscated identifier of this node, such as `_hidden`.
+    pub fn as_obfuscated(&self) -> Option<&str> {
+        match self.name {
+            NodeName::Obfuscated(ref identifier) => Some(identifier),
+            _ => None,
+        }
+    }
+
+    /// Get the obfuscated port of this node, such as `_8080`.
+    pub fn obfuscated_port(&self) -> Option<&str> {
+        match self.port {
+            Some(NodePort::Obfuscated(ref port)) => Some(port),
+            _ => None,
+        }
+    }
+
+    /// Check if this node is `unknown`.
+    pub fn is_unknown(&self) -> bool {
+        self.name == NodeName::Unknown
+    }
+}
+
+impl From<IpAddr> for ForwardedNode {
+    fn from(ip: IpAddr) -> ForwardedNode {
+        ForwardedNode {
+            name: NodeName::Ip(ip),
+            port: None,
+        }
+    }
+}
+
+impl From<SocketAddr> for ForwardedNode {
+    fn from(addr: SocketAddr) -> ForwardedNode {
+        ForwardedNode::from(addr.ip()).with_port(addr.port())
+    }
+}
+
+impl std::str::FromStr for ForwardedNode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let (name, port) = if let Some(rest) = s.strip_prefix('[') {
+            let end = rest.find(']').ok_or_else(Error::invalid)?;
+            let ip = rest[..end]
+                .parse::<Ipv6Addr>()
+                .map_err(|_| Error::invalid())?;
+            let port = match &rest[end + 1..] {
+                "" => None,
+                port => Some(port.strip_prefix(':').ok_or_else(Error::invalid)?),
+            };
+            (NodeName::Ip(IpAddr::V6(ip)), port)
+        } else {
+            let (name, port) = match s.find(':') {
+                Some(idx) => (&s[..idx], Some(&s[idx + 1..])),
+                None => (s, None),
+            };
+            let name = if name.eq_ignore_ascii_case("unknown") {
+                NodeName::Unknown
+            } else if is_obfuscated(name) {
+                NodeName::Obfuscated(name.to_owned())
+            } else {
+                let ip = name.parse::<Ipv4Addr>().map_err(|_| Error::invalid())?;
+                NodeName::Ip(IpAddr::V4(ip))
+            };
+            (name, port)
+        };
+
+        let port = match port {
+            None => None,
+            Some(port) if is_obfuscated(port) => Some(NodePort::Obfuscated(port.to_owned())),
+            Some(port)
+                if !port.is_empty()
+                    && port.len() <= 5
+                    && port.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                Some(NodePort::Port(port.parse().map_err(|_| Error::invalid())?))
+            }
+            Some(_) => return Err(Error::invalid()),
+        };
+
+        Ok(ForwardedNode { name, port })
+    }
+}
+
+impl fmt::Display for ForwardedNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name {
+            NodeName::Ip(IpAddr::V4(ip)) => write!(f, "{}", ip)?,
+            NodeName::Ip(IpAddr::V6(ip)) => write!(f, "[{}]", ip)?,
+            NodeName::Obfuscated(ref identifier) => f.write_str(identifier)?,
+            NodeName::Unknown => f.write_str("unknown")?,
+        }
+        match self.port {
+            Some(NodePort::Port(port)) => write!(f, ":{}", port),
+            Some(NodePort::Obfuscated(ref port)) => write!(f, ":{}", port),
+            None => Ok(()),
+        }
+    }
+}
+
+/// obfnode = "_" 1*( ALPHA / DIGIT / "." / "_" / "-")
+fn is_obfuscated(s: &str) -> bool {
+    s.len() > 1
+        && s.starts_with('_')
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-'))
+}
+
+/// Parse a `Host` header value, which can't contain user information.
+pub(super) fn parse_host(s: &str) -> Result<Authority, Error> {
+    match Authority::try_from(s) {
+        Ok(authority) if !authority.as_str().contains('@') && !authority.host().is_empty() => {
+            Ok(authority)
+        }
+        _ => Err(Error::invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn decode_elements() {
+        let forwarded = test_decode::<Forwarded>(&[
+            "for=192.0.2.60;proto=http;by=203.0.113.43",
+            "For=\"[2001:db8:cafe::17]:4711\", for=unknown;host=\"example.com:8080\"",
+        ])
+        .unwrap();
+        let elements = forwarded.elements().collect::<Vec<_>>();
+        assert_eq!(elements.len(), 3);
+
+        assert_eq!(
+            elements[0].forwarded_for().and_then(ForwardedNode::ip),
+            Some(ip("192.0.2.60"))
+        );
+        assert_eq!(elements[0].proto(), Some("http"));
+        assert_eq!(
+            elements[0].by().and_then(ForwardedNode::ip),
+            Some(ip("203.0.113.43"))
+        );
+
+        let node = elements[1].forwarded_for().unwrap();
+        assert_eq!(node.ip(), Some(ip("2001:db8:cafe::17")));
+        assert_eq!(node.port(), Some(4711));
+
+        assert!(elements[2].forwarded_for().unwrap().is_unknown());
+        assert_eq!(elements[2].host(), Some("example.com:8080"));
+    }
+
+    #[test]
+    fn parse_nodes() {
+        let node = "_hidden:_port".parse::<ForwardedNode>().unwrap();
+        assert_eq!(node.as_obfuscated(), Some("_hidden"));
+        assert_eq!(node.obfuscated_port(), Some("_port"));
+        assert_eq!(node.ip(), None);
+
+        for s in &[
+            "2001:db8::1",
+            "[2001:db8::1",
+            "[2001:db8::1]80",
+            "1.2.3.4:",
+            "1.2.3.4:65536",
+            "_",
+            "example.com",
+            "1.2.3.4:_",
+        ] {
+            assert!(
+                s.parse::<ForwardedNode>().is_err(),
+                "{:?} should be invalid",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for s in &[
+            "",
+            " , ;",
+            "for",
+            "for=",
+            "for=1.2.3.4;for=1.2.3.5",
+            "for=\"1.2.3.4",
+            "for=1.2.3.4 by=1.2.3.5",
+            "for=[::1]",
+            "proto=1http",
+            "host=user@example.com",
+        ] {
+            assert_eq!(
+                test_decode::<Forwarded>(&[s]),
+                None,
+                "{:?} should be invalid",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let s = "for=192.0.2.43;by=\"[2001:db8::1]:443\";proto=https;host=example.com;ext=\"a b\", for=_hidden";
+        let forwarded = test_decode::<Forwarded>(&[s]).unwrap();
+        assert_eq!(
+            forwarded.elements().next().unwrap().extension("EXT"),
+            Some("a b")
+        );
+        assert_eq!(test_encode(forwarded)["forwarded"], s);
+    }
+
+    #[test]
+    fn build() {
+        let mut forwarded = Forwarded::new(
+            ForwardedElement::new()
+                .with_for(ForwardedNode::from(
+                    "192.0.2.60:1234".parse::<SocketAddr>().unwrap(),
+                ))
+                .with_proto("https")
+                .with_host("example.com"),
+        );
+        forwarded.push(ForwardedElement::new().with_for(ForwardedNode::UNKNOWN));
+
+        assert_eq!(
+            test_encode(forwarded)["forwarded"],
+            "for=\"192.0.2.60:1234\";proto=https;host=example.com, for=unknown"
+        );
+    }
+
+    bench_header!(
+        bench,
+        Forwarded,
+        "for=192.0.2.60;proto=http;by=203.0.113.43, for=\"[2001:db8:cafe::17]:4711\""
+    );
+}
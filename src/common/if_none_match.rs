@@ -52,6 +52,14 @@ impl IfNoneMatch {
         IfNoneMatch(EntityTagRange::Any)
     }
 
+    /// Returns whether this is `If-None-Match: *`, matching any entity tag.
+    pub fn is_any(&self) -> bool {
+        match self.0 {
+            EntityTagRange::Any => true,
+            EntityTagRange::Tags(..) => false,
+        }
+    }
+
     /// Checks whether the ETag passes this precondition.
     pub fn precondition_passes(&self, etag: &ETag) -> bool {
         !self.0.matches_weak(&etag.0)
@@ -0,0 +1,1174 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+use http::{HeaderName, HeaderValue};
+use mime::Mime;
+
+use crate::util::{self, is_ows, ExtendedValue, LanguageTag};
+use crate::{Error, Header};
+
+/// The `Link` header, defined in
+/// [RFC8288](https://www.rfc-editor.org/rfc/rfc8288#section-3)
+///
+/// # ABNF
+///
+/// ```text
+/// Link       = #link-value
+/// link-value = "<" URI-Reference ">" *( OWS ";" OWS link-param )
+/// link-param = token BWS [ "=" BWS ( token / quoted-string ) ]
+/// ```
+///
+/// The target attributes `rel`, `anchor`, `rev`, `hreflang`, `media`,
+/// `title`, `title*` and `type` are parsed into typed values. Any other
+/// parameter, such as `as` for preload hints, is kept as an extension
+/// parameter.
+///
+/// # Example values
+///
+/// `Link: <http://example.com/TheBook/chapter2>; rel="previous";
+///        title="previous chapter"`
+///
+/// `Link: </TheBook/chapter2>; rel="previous"; title*=UTF-8'de'letztes%20Kapitel,
+///        </TheBook/chapter4>; rel="next"; title*=UTF-8'de'n%c3%a4chstes%20Kapitel`
+///
+/// # Examples
+///
+/// ```
+/// use headers::{Link, LinkValue, RelationType};
+///
+/// let link_value = LinkValue::new("http://example.com/TheBook/chapter2")
+///     .push_rel(RelationType::Previous)
+///     .set_title("previous chapter");
+///
+/// let link = Link::new(vec![link_value]);
+/// ```
+///
+/// Paginating a collection:
+///
+/// ```
+/// use headers::{HeaderMap, HeaderMapExt, Link, LinkValue};
+///
+/// let link = vec![
+///     LinkValue::next_page("/items?page=3"),
+///     LinkValue::prev_page("/items?page=1"),
+/// ]
+/// .into_iter()
+/// .collect::<Link>();
+///
+/// let mut headers = HeaderMap::new();
+/// headers.typed_insert(link);
+///
+/// let link = headers.typed_get::<Link>().unwrap();
+/// assert_eq!(link.next_page(), Some("/items?page=3"));
+/// assert_eq!(link.last_page(), None);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Link {
+    /// A list of the `link-value`s of the Link entity-header.
+    values: Vec<LinkValue>,
+}
+
+/// A single `link-value` of a `Link` header, based on:
+/// [RFC8288](https://www.rfc-editor.org/rfc/rfc8288#section-3)
+#[derive(Clone, PartialEq, Debug)]
+pub struct LinkValue {
+    /// Target IRI: `link-value`.
+    link: Cow<'static, str>,
+
+    /// Forward Relation Types: `rel`.
+    rel: Option<Vec<RelationType>>,
+
+    /// Context IRI: `anchor`.
+    anchor: Option<String>,
+
+    /// Reverse Relation Types: `rev`.
+    rev: Option<Vec<RelationType>>,
+
+    /// Hint on the language of the result of dereferencing
+    /// the link: `hreflang`.
+    href_lang: Option<Vec<LanguageTag>>,
+
+    /// Destination medium or media: `media`.
+    media_desc: Option<Vec<MediaDesc>>,
+
+    /// Label of the destination of a Link: `title`.
+    title: Option<String>,
+
+    /// The `title` encoded in a different charset: `title*`.
+    title_star: Option<ExtendedValue>,
+
+    /// Hint on the media type of the result of dereferencing
+    /// the link: `type`.
+    media_type: Option<Mime>,
+
+    /// Extension parameters, such as `as`.
+    params: Vec<(String, Option<String>)>,
+}
+
+/// A Media Descriptors Enum based on:
+/// [https://www.w3.org/TR/html401/types.html#h-6.13][url]
+///
+/// [url]: https://www.w3.org/TR/html401/types.html#h-6.13
+#[derive(Clone, PartialEq, Debug)]
+pub enum MediaDesc {
+    /// screen.
+    Screen,
+    /// tty.
+    Tty,
+    /// tv.
+    Tv,
+    /// projection.
+    Projection,
+    /// handheld.
+    Handheld,
+    /// print.
+    Print,
+    /// braille.
+    Braille,
+    /// aural.
+    Aural,
+    /// all.
+    All,
+    /// Unrecognized media descriptor extension.
+    Extension(String),
+}
+
+/// A Link Relation Type Enum based on the
+/// [IANA registry](https://www.iana.org/assignments/link-relations/link-relations.xhtml).
+#[derive(Clone, PartialEq, Debug)]
+pub enum RelationType {
+    /// alternate.
+    Alternate,
+    /// appendix.
+    Appendix,
+    /// bookmark.
+    Bookmark,
+    /// chapter.
+    Chapter,
+    /// contents.
+    Contents,
+    /// copyright.
+    Copyright,
+    /// current.
+    Current,
+    /// describedby.
+    DescribedBy,
+    /// dns-prefetch.
+    DnsPrefetch,
+    /// edit.
+    Edit,
+    /// edit-media.
+    EditMedia,
+    /// enclosure.
+    Enclosure,
+    /// first.
+    First,
+    /// glossary.
+    Glossary,
+    /// help.
+    Help,
+    /// hub.
+    Hub,
+    /// index.
+    Index,
+    /// last.
+    Last,
+    /// latest-version.
+    LatestVersion,
+    /// license.
+    License,
+    /// next.
+    Next,
+    /// next-archive.
+    NextArchive,
+    /// payment.
+    Payment,
+    /// preconnect.
+    Preconnect,
+    /// prefetch.
+    Prefetch,
+    /// preload.
+    Preload,
+    /// prev.
+    Prev,
+    /// predecessor-version.
+    PredecessorVersion,
+    /// previous.
+    Previous,
+    /// prev-archive.
+    PrevArchive,
+    /// related.
+    Related,
+    /// replies.
+    Replies,
+    /// section.
+    Section,
+    /// self.
+    RelationTypeSelf,
+    /// service.
+    Service,
+    /// start.
+    Start,
+    /// stylesheet.
+    Stylesheet,
+    /// subsection.
+    Subsection,
+    /// successor-version.
+    SuccessorVersion,
+    /// up.
+    Up,
+    /// version-history.
+    VersionHistory,
+    /// via.
+    Via,
+    /// working-copy.
+    WorkingCopy,
+    /// working-copy-of.
+    WorkingCopyOf,
+    /// ext-rel-type.
+    ExtRelType(String),
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Struct methods
+////////////////////////////////////////////////////////////////////////////////
+
+impl Link {
+    /// Create `Link` from a `Vec<LinkValue>`.
+    pub fn new(link_values: Vec<LinkValue>) -> Link {
+        Link {
+            values: link_values,
+        }
+    }
+
+    /// Get the `Link` header's `LinkValue`s.
+    pub fn values(&self) -> &[LinkValue] {
+        self.values.as_ref()
+    }
+
+    /// Add a `LinkValue` instance to the `Link` header's values.
+    pub fn push_value(&mut self, link_value: LinkValue) {
+        self.values.push(link_value);
+    }
+
+    /// Find the first `LinkValue` with the given relation type.
+    ///
+    /// `prev` and `previous` are treated as the same relation type.
+    pub fn find_rel(&self, rel: &RelationType) -> Option<&LinkValue> {
+        self.values.iter().find(|value| value.has_rel(rel))
+    }
+
+    /// Get the target of the `rel="next"` link, if any.
+    pub fn next_page(&self) -> Option<&str> {
+        self.find_rel(&RelationType::Next).map(LinkValue::link)
+    }
+
+    /// Get the target of the `rel="prev"` (or `"previous"`) link, if any.
+    pub fn prev_page(&self) -> Option<&str> {
+        self.find_rel(&RelationType::Prev).map(LinkValue::link)
+    }
+
+    /// Get the target of the `rel="first"` link, if any.
+    pub fn first_page(&self) -> Option<&str> {
+        self.find_rel(&RelationType::First).map(LinkValue::link)
+    }
+
+    /// Get the target of the `rel="last"` link, if any.
+    pub fn last_page(&self) -> Option<&str> {
+        self.find_rel(&RelationType::Last).map(LinkValue::link)
+    }
+}
+
+impl LinkValue {
+    /// Create `LinkValue` from URI-Reference.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the URI contains characters other than visible ASCII, or
+    /// a `>`.
+    pub fn new<T>(uri: T) -> LinkValue
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        let link = uri.into();
+        assert!(is_uri_reference(&link), "invalid link URI");
+        LinkValue {
+            link,
+            rel: None,
+            anchor: None,
+            rev: None,
+            href_lang: None,
+            media_desc: None,
+            title: None,
+            title_star: None,
+            media_type: None,
+            params: Vec::new(),
+        }
+    }
+
+    /// Create a `rel="next"` link to the next page of a collection.
+    pub fn next_page<T>(uri: T) -> LinkValue
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        LinkValue::new(uri).push_rel(RelationType::Next)
+    }
+
+    /// Create a `rel="prev"` link to the previous page of a collection.
+    pub fn prev_page<T>(uri: T) -> LinkValue
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        LinkValue::new(uri).push_rel(RelationType::Prev)
+    }
+
+    /// Create a `rel="first"` link to the first page of a collection.
+    pub fn first_page<T>(uri: T) -> LinkValue
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        LinkValue::new(uri).push_rel(RelationType::First)
+    }
+
+    /// Create a `rel="last"` link to the last page of a collection.
+    pub fn last_page<T>(uri: T) -> LinkValue
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        LinkValue::new(uri).push_rel(RelationType::Last)
+    }
+
+    /// Create a [`rel="preload"`](https://www.w3.org/TR/preload/) hint.
+    ///
+    /// The `destination` is the `as` parameter, such as `"style"`,
+    /// `"script"` or `"font"`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the URI is not valid, as in [`LinkValue::new`], or the
+    /// destination is not a token.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use headers::{HeaderMap, HeaderMapExt, Link, LinkValue};
+    ///
+    /// let link = Link::new(vec![LinkValue::preload("/app.css", "style")]);
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.typed_insert(link);
+    /// assert_eq!(headers["link"], "</app.css>; rel=\"preload\"; as=style");
+    /// ```
+    pub fn preload<T>(uri: T, destination: &str) -> LinkValue
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        LinkValue::new(uri)
+            .push_rel(RelationType::Preload)
+            .push_param("as", destination)
+    }
+
+    /// Get the `LinkValue`'s value.
+    pub fn link(&self) -> &str {
+        self.link.as_ref()
+    }
+
+    /// Get the `LinkValue`'s `rel` parameter(s).
+    pub fn rel(&self) -> Option<&[RelationType]> {
+        self.rel.as_ref().map(AsRef::as_ref)
+    }
+
+    /// Check if the `LinkValue`'s `rel` parameter contains a relation type.
+    ///
+    /// `prev` and `previous` are treated as the same relation type.
+    pub fn has_rel(&self, rel: &RelationType) -> bool {
+        self.rel().unwrap_or(&[]).iter().any(|r| r.is_same(rel))
+    }
+
+    /// Get the `LinkValue`'s `anchor` parameter.
+    pub fn anchor(&self) -> Option<&str> {
+        self.anchor.as_ref().map(AsRef::as_ref)
+    }
+
+    /// Get the `LinkValue`'s `rev` parameter(s).
+    pub fn rev(&self) -> Option<&[RelationType]> {
+        self.rev.as_ref().map(AsRef::as_ref)
+    }
+
+    /// Get the `LinkValue`'s `hreflang` parameter(s).
+    pub fn href_lang(&self) -> Option<&[LanguageTag]> {
+        self.href_lang.as_ref().map(AsRef::as_ref)
+    }
+
+    /// Get the `LinkValue`'s `media` parameter(s).
+    pub fn media_desc(&self) -> Option<&[MediaDesc]> {
+        self.media_desc.as_ref().map(AsRef::as_ref)
+    }
+
+    /// Get the `LinkValue`'s `title` parameter.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_ref().map(AsRef::as_ref)
+    }
+
+    /// Get the `LinkValue`'s `title*` parameter.
+    pub fn title_star(&self) -> Option<&ExtendedValue> {
+        self.title_star.as_ref()
+    }
+
+    /// Get the `LinkValue`'s `type` parameter.
+    pub fn media_type(&self) -> Option<&Mime> {
+        self.media_type.as_ref()
+    }
+
+    /// Get the value of an extension parameter, such as `as`.
+    ///
+    /// Returns `None` if the parameter is missing or has no value.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .and_then(|(_, value)| value.as_ref().map(AsRef::as_ref))
+    }
+
+    /// Add a `RelationType` to the `LinkValue`'s `rel` parameter.
+    pub fn push_rel(mut self, rel: RelationType) -> LinkValue {
+        self.rel.get_or_insert_with(Vec::new).push(rel);
+        self
+    }
+
+    /// Set `LinkValue`'s `anchor` parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the anchor contains characters other than visible ASCII,
+    /// or a `>`.
+    pub fn set_anchor<T: Into<String>>(mut self, anchor: T) -> LinkValue {
+        let anchor = anchor.into();
+        assert!(is_uri_reference(&anchor), "invalid link anchor");
+        self.anchor = Some(anchor);
+        self
+    }
+
+    /// Add a `RelationType` to the `LinkValue`'s `rev` parameter.
+    pub fn push_rev(mut self, rev: RelationType) -> LinkValue {
+        self.rev.get_or_insert_with(Vec::new).push(rev);
+        self
+    }
+
+    /// Add a `LanguageTag` to the `LinkValue`'s `hreflang` parameter.
+    pub fn push_href_lang(mut self, language_tag: LanguageTag) -> LinkValue {
+        self.href_lang
+            .get_or_insert_with(Vec::new)
+            .push(language_tag);
+        self
+    }
+
+    /// Add a `MediaDesc` to the `LinkValue`'s `media_desc` parameter.
+    pub fn push_media_desc(mut self, media_desc: MediaDesc) -> LinkValue {
+        self.media_desc
+            .get_or_insert_with(Vec::new)
+            .push(media_desc);
+        self
+    }
+
+    /// Set `LinkValue`'s `title` parameter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the title contains control characters other than
+    /// horizontal tab.
+    pub fn set_title<T: Into<String>>(mut self, title: T) -> LinkValue {
+        let title = title.into();
+        assert!(util::is_quotable(&title), "invalid link title");
+        self.title = Some(title);
+        self
+    }
+
+    /// Set `LinkValue`'s `title*` parameter.
+    ///
+    /// Use this for titles that aren't ASCII, such as with
+    /// `ExtendedValue::utf8("nächstes Kapitel")`.
+    pub fn set_title_star(mut self, title_star: ExtendedValue) -> LinkValue {
+        self.title_star = Some(title_star);
+        self
+    }
+
+    /// Set `LinkValue`'s `type` parameter.
+    pub fn set_media_type(mut self, media_type: Mime) -> LinkValue {
+        self.media_type = Some(media_type);
+        self
+    }
+
+    /// Add an extension parameter, such as `crossorigin=anonymous`.
+    ///
+    /// The value is quoted if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not a token, or the value contains control
+    /// characters other than horizontal tab.
+    pub fn push_param<N, V>(mut self, name: N, value: V) -> LinkValue
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        let name = name.into();
+        let value = value.into();
+        assert!(util::is_token(&name), "invalid link param name");
+        assert!(util::is_quotable(&value), "invalid link param value");
+        self.params.push((name, Some(value)));
+        self
+    }
+
+    fn set_param(&mut self, name: &str, value: Option<String>) -> Result<(), Error> {
+        if "rel".eq_ignore_ascii_case(name) {
+            // Only the first `rel` parameter counts.
+            // https://www.rfc-editor.org/rfc/rfc8288#section-3.3
+            let rel = parse_relation_types(value)?;
+            self.rel.get_or_insert(rel);
+        } else if "anchor".eq_ignore_ascii_case(name) {
+            self.anchor = Some(required(value)?);
+        } else if "rev".eq_ignore_ascii_case(name) {
+            let rev = parse_relation_types(value)?;
+            self.rev.get_or_insert(rev);
+        } else if "hreflang".eq_ignore_ascii_case(name) {
+            let tag = required(value)?.parse()?;
+            self.href_lang.get_or_insert_with(Vec::new).push(tag);
+        } else if "media".eq_ignore_ascii_case(name) {
+            let media = required(value)?
+                .split(',')
+                .map(|desc| desc.trim().parse())
+                .collect::<Result<Vec<MediaDesc>, _>>()?;
+            self.media_desc.get_or_insert(media);
+        } else if "title".eq_ignore_ascii_case(name) {
+            let title = required(value)?;
+            self.title.get_or_insert(title);
+        } else if "title*".eq_ignore_ascii_case(name) {
+            let title_star = required(value)?.parse()?;
+            self.title_star.get_or_insert(title_star);
+        } else if "type".eq_ignore_ascii_case(name) {
+            let media_type = required(value)?.parse().map_err(|_| Error::invalid())?;
+            self.media_type.get_or_insert(media_type);
+        } else {
+            self.params.push((name.to_owned(), value));
+        }
+        Ok(())
+    }
+}
+
+impl RelationType {
+    fn as_str(&self) -> &str {
+        match *self {
+            RelationType::Alternate => "alternate",
+            RelationType::Appendix => "appendix",
+            RelationType::Bookmark => "bookmark",
+            RelationType::Chapter => "chapter",
+            RelationType::Contents => "contents",
+            RelationType::Copyright => "copyright",
+            RelationType::Current => "current",
+            RelationType::DescribedBy => "describedby",
+            RelationType::DnsPrefetch => "dns-prefetch",
+            RelationType::Edit => "edit",
+            RelationType::EditMedia => "edit-media",
+            RelationType::Enclosure => "enclosure",
+            RelationType::First => "first",
+            RelationType::Glossary => "glossary",
+            RelationType::Help => "help",
+            RelationType::Hub => "hub",
+            RelationType::Index => "index",
+            RelationType::Last => "last",
+            RelationType::LatestVersion => "latest-version",
+            RelationType::License => "license",
+            RelationType::Next => "next",
+            RelationType::NextArchive => "next-archive",
+            RelationType::Payment => "payment",
+            RelationType::Preconnect => "preconnect",
+            RelationType::Prefetch => "prefetch",
+            RelationType::Preload => "preload",
+            RelationType::Prev => "prev",
+            RelationType::PredecessorVersion => "predecessor-version",
+            RelationType::Previous => "previous",
+            RelationType::PrevArchive => "prev-archive",
+            RelationType::Related => "related",
+            RelationType::Replies => "replies",
+            RelationType::Section => "section",
+            RelationType::RelationTypeSelf => "self",
+            RelationType::Service => "service",
+            RelationType::Start => "start",
+            RelationType::Stylesheet => "stylesheet",
+            RelationType::Subsection => "subsection",
+            RelationType::SuccessorVersion => "successor-version",
+            RelationType::Up => "up",
+            RelationType::VersionHistory => "version-history",
+            RelationType::Via => "via",
+            RelationType::WorkingCopy => "working-copy",
+            RelationType::WorkingCopyOf => "working-copy-of",
+            RelationType::ExtRelType(ref uri) => uri,
+        }
+    }
+
+    fn is_same(&self, other: &RelationType) -> bool {
+        let prev = |rel: &RelationType| matches!(*rel, RelationType::Prev | RelationType::Previous);
+        (prev(self) && prev(other)) || self.as_str().eq_ignore_ascii_case(other.as_str())
+    }
+}
+
+const KNOWN_RELATION_TYPES: &[RelationType] = &[
+    RelationType::Alternate,
+    RelationType::Appendix,
+    RelationType::Bookmark,
+    RelationType::Chapter,
+    RelationType::Contents,
+    RelationType::Copyright,
+    RelationType::Current,
+    RelationType::DescribedBy,
+    RelationType::DnsPrefetch,
+    RelationType::Edit,
+    RelationType::EditMedia,
+    RelationType::Enclosure,
+    RelationType::First,
+    RelationType::Glossary,
+    RelationType::Help,
+    RelationType::Hub,
+    RelationType::Index,
+    RelationType::Last,
+    RelationType::LatestVersion,
+    RelationType::License,
+    RelationType::Next,
+    RelationType::NextArchive,
+    RelationType::Payment,
+    RelationType::Preconnect,
+    RelationType::Prefetch,
+    RelationType::Preload,
+    RelationType::Prev,
+    RelationType::PredecessorVersion,
+    RelationType::Previous,
+    RelationType::PrevArchive,
+    RelationType::Related,
+    RelationType::Replies,
+    RelationType::Section,
+    RelationType::RelationTypeSelf,
+    RelationType::Service,
+    RelationType::Start,
+    RelationType::Stylesheet,
+    RelationType::Subsection,
+    RelationType::SuccessorVersion,
+    RelationType::Up,
+    RelationType::VersionHistory,
+    RelationType::Via,
+    RelationType::WorkingCopy,
+    RelationType::WorkingCopyOf,
+];
+
+////////////////////////////////////////////////////////////////////////////////
+// Trait implementations
+////////////////////////////////////////////////////////////////////////////////
+
+impl Header for Link {
+    fn name() -> &'static HeaderName {
+        &::http::header::LINK
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        // If more that one `Link` headers are present in a request's
+        // headers they are combined in a single `Link` header containing
+        // all the `link-value`s present in each of those `Link` headers.
+        let mut link_values = Vec::new();
+        let mut found = false;
+        for value in values {
+            found = true;
+            // Titles may contain UTF-8, which `HeaderValue::to_str` rejects.
+            let s = ::std::str::from_utf8(value.as_bytes()).map_err(|_| Error::invalid())?;
+            parse_link_values(s, &mut link_values)?;
+        }
+
+        if found {
+            Ok(Link::new(link_values))
+        } else {
+            Err(Error::invalid())
+        }
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(::std::iter::once(util::fmt(self)));
+    }
+}
+
+impl FromIterator<LinkValue> for Link {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = LinkValue>,
+    {
+        Link::new(iter.into_iter().collect())
+    }
+}
+
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_delimited(f, self.values.as_slice(), ", ", ("", ""))
+    }
+}
+
+impl fmt::Display for LinkValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<{}>", self.link)?;
+
+        if let Some(ref rel) = self.rel {
+            fmt_delimited(f, rel.as_slice(), " ", ("; rel=\"", "\""))?;
+        }
+        if let Some(ref anchor) = self.anchor {
+            f.write_str("; anchor=")?;
+            util::fmt_quoted(f, anchor)?;
+        }
+        if let Some(ref rev) = self.rev {
+            fmt_delimited(f, rev.as_slice(), " ", ("; rev=\"", "\""))?;
+        }
+        if let Some(ref href_lang) = self.href_lang {
+            for tag in href_lang {
+                write!(f, "; hreflang={}", tag)?;
+            }
+        }
+        if let Some(ref media_desc) = self.media_desc {
+            fmt_delimited(f, media_desc.as_slice(), ", ", ("; media=\"", "\""))?;
+        }
+        if let Some(ref title) = self.title {
+            f.write_str("; title=")?;
+            util::fmt_quoted(f, title)?;
+        }
+        if let Some(ref title_star) = self.title_star {
+            write!(f, "; title*={}", title_star)?;
+        }
+        if let Some(ref media_type) = self.media_type {
+            write!(f, "; type=\"{}\"", media_type)?;
+        }
+        for (name, value) in &self.params {
+            write!(f, "; {}", name)?;
+            if let Some(ref value) = value {
+                f.write_str("=")?;
+                util::fmt_token_or_quoted(f, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for MediaDesc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MediaDesc::Screen => write!(f, "screen"),
+            MediaDesc::Tty => write!(f, "tty"),
+            MediaDesc::Tv => write!(f, "tv"),
+            MediaDesc::Projection => write!(f, "projection"),
+            MediaDesc::Handheld => write!(f, "handheld"),
+            MediaDesc::Print => write!(f, "print"),
+            MediaDesc::Braille => write!(f, "braille"),
+            MediaDesc::Aural => write!(f, "aural"),
+            MediaDesc::All => write!(f, "all"),
+            MediaDesc::Extension(ref other) => write!(f, "{}", other),
+        }
+    }
+}
+
+impl FromStr for MediaDesc {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<MediaDesc, Error> {
+        match s.to_ascii_lowercase().as_str() {
+            "screen" => Ok(MediaDesc::Screen),
+            "tty" => Ok(MediaDesc::Tty),
+            "tv" => Ok(MediaDesc::Tv),
+            "projection" => Ok(MediaDesc::Projection),
+            "handheld" => Ok(MediaDesc::Handheld),
+            "print" => Ok(MediaDesc::Print),
+            "braille" => Ok(MediaDesc::Braille),
+            "aural" => Ok(MediaDesc::Aural),
+            "all" => Ok(MediaDesc::All),
+            "" => Err(Error::invalid()),
+            _ => Ok(MediaDesc::Extension(String::from(s))),
+        }
+    }
+}
+
+impl fmt::Display for RelationType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RelationType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<RelationType, Error> {
+        if s.is_empty() {
+            return Err(Error::invalid());
+        }
+
+        let rel = KNOWN_RELATION_TYPES
+            .iter()
+            .find(|rel| rel.as_str().eq_ignore_ascii_case(s))
+            .cloned()
+            .unwrap_or_else(|| RelationType::ExtRelType(String::from(s)));
+        Ok(rel)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Utilities
+////////////////////////////////////////////////////////////////////////////////
+
+fn parse_link_values(s: &str, values: &mut Vec<LinkValue>) -> Result<(), Error> {
+    let mut rest = s;
+    loop {
+        // Empty list elements are allowed, e.g. `<a>, , <b>`.
+        rest = rest.trim_start_matches(|c| is_ows(c) || c == ',');
+        if rest.is_empty() {
+            return Ok(());
+        }
+
+        // Parse the `Target IRI`
+        // https://www.rfc-editor.org/rfc/rfc8288#section-3.1
+        let target = rest.strip_prefix('<').ok_or_else(Error::invalid)?;
+        let end = target.find('>').ok_or_else(Error::invalid)?;
+        if !is_uri_reference(&target[..end]) {
+            return Err(Error::invalid());
+        }
+        let mut link_value = LinkValue::new(target[..end].to_owned());
+        rest = target[end + 1..].trim_start_matches(is_ows);
+
+        // Parse the current link-value's parameters
+        while let Some(params) = rest.strip_prefix(';') {
+            let (name, value, after) = util::parse_param(params)?;
+            link_value.set_param(name, value)?;
+            rest = after.trim_start_matches(is_ows);
+        }
+
+        values.push(link_value);
+        if !rest.is_empty() && !rest.starts_with(',') {
+            return Err(Error::invalid());
+        }
+    }
+}
+
+/// Check if a string can be sent as a `URI-Reference` between `<` and `>`.
+fn is_uri_reference(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_graphic() && b != b'>')
+}
+
+fn required(value: Option<String>) -> Result<String, Error> {
+    value
+        .filter(|value| !value.is_empty())
+        .ok_or_else(Error::invalid)
+}
+
+fn parse_relation_types(value: Option<String>) -> Result<Vec<RelationType>, Error> {
+    required(value)?
+        .split_ascii_whitespace()
+        .map(str::parse)
+        .collect()
+}
+
+fn fmt_delimited<T: fmt::Display>(
+    f: &mut fmt::Formatter,
+    p: &[T],
+    d: &str,
+    b: (&str, &str),
+) -> fmt::Result {
+    if let Some((first, rest)) = p.split_first() {
+        // Write a starting string `b.0` before the first element
+        write!(f, "{}{}", b.0, first)?;
+
+        for i in rest {
+            // Write the next element preceded by the delimiter `d`
+            write!(f, "{}{}", d, i)?;
+        }
+
+        // Write a ending string `b.1` after the last element
+        write!(f, "{}", b.1)?;
+    }
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn test_link() {
+        let link_value = LinkValue::new("http://example.com/TheBook/chapter2")
+            .push_rel(RelationType::Previous)
+            .push_rev(RelationType::Next)
+            .set_title("previous chapter");
+
+        let link_header = "<http://example.com/TheBook/chapter2>; \
+            rel=\"previous\"; rev=next; title=\"previous chapter\"";
+
+        let expected_link = Link::new(vec![link_value]);
+
+        assert_eq!(test_decode(&[link_header]), Some(expected_link));
+    }
+
+    #[test]
+    fn test_link_multiple_values() {
+        let first_link = LinkValue::new("/TheBook/chapter2")
+            .push_rel(RelationType::Previous)
+            .set_title_star("UTF-8'de'letztes%20Kapitel".parse().unwrap());
+
+        let second_link = LinkValue::new("/TheBook/chapter4")
+            .push_rel(RelationType::Next)
+            .set_title_star("UTF-8'de'n%c3%a4chstes%20Kapitel".parse().unwrap());
+
+        let link_header = "</TheBook/chapter2>; \
+            rel=\"previous\"; title*=UTF-8'de'letztes%20Kapitel, \
+            </TheBook/chapter4>; \
+            rel=\"next\"; title*=UTF-8'de'n%c3%a4chstes%20Kapitel";
+
+        let expected_link = Link::new(vec![first_link, second_link]);
+
+        let link = test_decode::<Link>(&[link_header]).unwrap();
+        assert_eq!(link, expected_link);
+        assert_eq!(
+            link.values()[1].title_star().unwrap().text().unwrap(),
+            "nächstes Kapitel"
+        );
+    }
+
+    #[test]
+    fn test_link_all_attributes() {
+        let link_value = LinkValue::new("http://example.com/TheBook/chapter2")
+            .push_rel(RelationType::Previous)
+            .set_anchor("../anchor/example/")
+            .push_rev(RelationType::Next)
+            .push_href_lang("de".parse().unwrap())
+            .push_media_desc(MediaDesc::Screen)
+            .set_title("previous chapter")
+            .set_title_star(ExtendedValue::utf8("vorheriges Kapitel"))
+            .set_media_type(mime::TEXT_PLAIN);
+
+        let link_header = "<http://example.com/TheBook/chapter2>; \
+            rel=\"previous\"; anchor=\"../anchor/example/\"; \
+            rev=\"next\"; hreflang=de; media=\"screen\"; \
+            title=\"previous chapter\"; title*=UTF-8''vorheriges%20Kapitel; \
+            type=\"text/plain\"";
+
+        let expected_link = Link::new(vec![link_value]);
+
+        assert_eq!(test_decode(&[link_header]), Some(expected_link));
+    }
+
+    #[test]
+    fn test_link_multiple_link_headers() {
+        let first_link = LinkValue::new("/TheBook/chapter2").push_rel(RelationType::Previous);
+        let second_link = LinkValue::new("/TheBook/chapter4").push_rel(RelationType::Next);
+        let third_link = LinkValue::new("http://example.com/TheBook/chapter2")
+            .push_rel(RelationType::Previous)
+            .push_rev(RelationType::Next)
+            .set_title("previous chapter");
+
+        let expected_link = Link::new(vec![first_link, second_link, third_link]);
+
+        let link = test_decode(&[
+            "</TheBook/chapter2>; rel=\"previous\", </TheBook/chapter4>; rel=\"next\"",
+            "<http://example.com/TheBook/chapter2>; rel=\"previous\"; rev=next; \
+             title=\"previous chapter\"",
+        ]);
+        assert_eq!(link, Some(expected_link));
+    }
+
+    #[test]
+    fn test_link_display() {
+        let link_value = LinkValue::new("http://example.com/TheBook/chapter2")
+            .push_rel(RelationType::Previous)
+            .set_anchor("/anchor/example/")
+            .push_rev(RelationType::Next)
+            .push_href_lang("de".parse().unwrap())
+            .push_media_desc(MediaDesc::Screen)
+            .set_title("previous \"chapter\"")
+            .set_title_star(ExtendedValue::utf8("vorheriges Kapitel"))
+            .set_media_type(mime::TEXT_PLAIN)
+            .push_param("crossorigin", "use-credentials");
+
+        let link = Link::new(vec![link_value]);
+
+        let expected_link_header = "<http://example.com/TheBook/chapter2>; \
+            rel=\"previous\"; anchor=\"/anchor/example/\"; \
+            rev=\"next\"; hreflang=de; media=\"screen\"; \
+            title=\"previous \\\"chapter\\\"\"; title*=UTF-8''vorheriges%20Kapitel; \
+            type=\"text/plain\"; crossorigin=use-credentials";
+
+        assert_eq!(link.to_string(), expected_link_header);
+
+        let headers = test_encode(link.clone());
+        assert_eq!(headers["link"], expected_link_header);
+        assert_eq!(
+            test_decode::<Link>(&[expected_link_header]),
+            Some(link),
+            "round trip"
+        );
+    }
+
+    #[test]
+    fn test_link_parsing_errors() {
+        let link_a = "http://example.com/TheBook/chapter2; \
+            rel=\"previous\"; rev=next; title=\"previous chapter\"";
+
+        assert_eq!(test_decode::<Link>(&[link_a]), None);
+
+        let link_b = "<http://example.com/TheBook/chapter2>; \
+            =\"previous\"; rev=next; title=\"previous chapter\"";
+
+        assert_eq!(test_decode::<Link>(&[link_b]), None);
+
+        let link_c = "<http://example.com/TheBook/chapter2>; \
+            rel=; rev=next; title=\"previous chapter\"";
+
+        assert_eq!(test_decode::<Link>(&[link_c]), None);
+
+        let link_d = "<http://example.com/TheBook/chapter2>; \
+            rel=\"previous\"; rev=next; title=";
+
+        assert_eq!(test_decode::<Link>(&[link_d]), None);
+
+        let link_e = "<http://example.com/TheBook/chapter2>; \
+            rel=\"previous\"; title*=not-an-ext-value";
+
+        assert_eq!(test_decode::<Link>(&[link_e]), None);
+
+        let link_f = "<http://example.com/TheBook/chapter2> rel=\"previous\"";
+
+        assert_eq!(test_decode::<Link>(&[link_f]), None);
+
+        let link_g = "<http://example.com/The Book>; rel=\"previous\"";
+
+        assert_eq!(test_decode::<Link>(&[link_g]), None);
+    }
+
+    #[test]
+    fn test_link_extension_params() {
+        let link =
+            test_decode::<Link>(&["</font.woff2>; rel=preload; as=font; crossorigin"]).unwrap();
+        let value = &link.values()[0];
+        assert!(value.has_rel(&RelationType::Preload));
+        assert_eq!(value.param("as"), Some("font"));
+        assert_eq!(value.param("crossorigin"), None);
+
+        let headers = test_encode(link);
+        assert_eq!(
+            headers["link"],
+            "</font.woff2>; rel=\"preload\"; as=font; crossorigin"
+        );
+    }
+
+    #[test]
+    fn test_link_target_with_delimiters() {
+        let link =
+            test_decode::<Link>(&["</items?a=1,2;b=3>; rel=next, </items>; rel=first"]).unwrap();
+        assert_eq!(link.next_page(), Some("/items?a=1,2;b=3"));
+        assert_eq!(link.first_page(), Some("/items"));
+    }
+
+    #[test]
+    fn test_link_first_rel_wins() {
+        let link = test_decode::<Link>(&["</a>; rel=next; rel=prev"]).unwrap();
+        assert_eq!(link.values()[0].rel(), Some(&[RelationType::Next][..]));
+    }
+
+    #[test]
+    fn test_link_pagination() {
+        let link = vec![
+            LinkValue::first_page("/items?page=1"),
+            LinkValue::prev_page("/items?page=4"),
+            LinkValue::next_page("/items?page=6"),
+            LinkValue::last_page("/items?page=9"),
+        ]
+        .into_iter()
+        .collect::<Link>();
+
+        let headers = test_encode(link);
+        assert_eq!(
+            headers["link"],
+            "</items?page=1>; rel=\"first\", </items?page=4>; rel=\"prev\", \
+             </items?page=6>; rel=\"next\", </items?page=9>; rel=\"last\""
+        );
+
+        let link = test_decode::<Link>(&[headers["link"].to_str().unwrap()]).unwrap();
+        assert_eq!(link.first_page(), Some("/items?page=1"));
+        assert_eq!(link.prev_page(), Some("/items?page=4"));
+        assert_eq!(link.next_page(), Some("/items?page=6"));
+        assert_eq!(link.last_page(), Some("/items?page=9"));
+    }
+
+    #[test]
+    fn test_link_rel_case_and_synonyms() {
+        let link = test_decode::<Link>(&["</p>; rel=\"Previous\", </n>; rel=\"NEXT up\""]).unwrap();
+        assert_eq!(link.prev_page(), Some("/p"));
+        assert_eq!(link.next_page(), Some("/n"));
+        assert_eq!(
+            link.find_rel(&RelationType::Up).map(LinkValue::link),
+            Some("/n")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid link URI")]
+    fn test_link_invalid_uri() {
+        let _ = LinkValue::new("/a>; rel=next");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid link anchor")]
+    fn test_link_invalid_anchor() {
+        let _ = LinkValue::new("/").set_anchor("#a\r\nb");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid link title")]
+    fn test_link_invalid_title() {
+        let _ = LinkValue::new("/").set_title("a\r\nb");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid link param name")]
+    fn test_link_invalid_param_name() {
+        let _ = LinkValue::new("/").push_param("cross origin", "anonymous");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid link param value")]
+    fn test_link_invalid_param_value() {
+        let _ = LinkValue::new("/").push_param("crossorigin", "a\r\nb");
+    }
+
+    #[test]
+    fn test_link_fmt_delimited() {
+        struct TestFormatterStruct<'a> {
+            v: Vec<&'a str>,
+        }
+
+        impl<'a> fmt::Display for TestFormatterStruct<'a> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt_delimited(f, self.v.as_slice(), ", ", (">>", "<<"))
+            }
+        }
+
+        let test_formatter = TestFormatterStruct {
+            v: vec!["first", "second"],
+        };
+
+        assert_eq!(test_formatter.to_string(), ">>first, second<<");
+    }
+
+    bench_header!(
+        bench,
+        Link,
+        "<http://example.com/TheBook/chapter2>; rel=\"previous\"; rev=next; title=\"previous chapter\"; type=\"text/html\"; media=\"screen, tty\""
+    );
+}
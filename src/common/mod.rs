@@ -6,11 +6,11 @@
 //! strongly-typed theme, the [mime](https://docs.rs/mime) crate
 //! is used, such as `ContentType(pub Mime)`.
 
-//pub use self::accept_charset::AcceptCharset;
-//pub use self::accept_encoding::AcceptEncoding;
-//pub use self::accept_language::AcceptLanguage;
+pub use self::accept::Accept;
+pub use self::accept_charset::AcceptCharset;
+pub use self::accept_encoding::AcceptEncoding;
+pub use self::accept_language::AcceptLanguage;
 pub use self::accept_ranges::AcceptRanges;
-//pub use self::accept::Accept;
 pub use self::access_control_allow_credentials::AccessControlAllowCredentials;
 pub use self::access_control_allow_headers::AccessControlAllowHeaders;
 pub use self::access_control_allow_methods::AccessControlAllowMethods;
@@ -21,15 +21,22 @@ pub use self::access_control_request_headers::AccessControlRequestHeaders;
 pub use self::access_control_request_method::AccessControlRequestMethod;
 pub use self::age::Age;
 pub use self::allow::Allow;
-pub use self::authorization::Authorization;
+pub use self::authentication_info::AuthenticationInfo;
+pub use self::authorization::{AnyAuthorization, Authorization};
 pub use self::cache_control::CacheControl;
+pub use self::cache_status::{CacheStatus, CacheStatusEntry, ForwardReason};
+pub use self::cdn_cache_control::CdnCacheControl;
 pub use self::connection::Connection;
 pub use self::content_disposition::ContentDisposition;
 pub use self::content_encoding::ContentEncoding;
-//pub use self::content_language::ContentLanguage;
+pub use self::content_language::ContentLanguage;
 pub use self::content_length::ContentLength;
 pub use self::content_location::ContentLocation;
 pub use self::content_range::ContentRange;
+pub use self::content_security_policy::{
+    ContentSecurityPolicy, CspDirective, CspHashAlgorithm, CspPolicy, CspSource,
+};
+pub use self::content_security_policy_report_only::ContentSecurityPolicyReportOnly;
 pub use self::content_type::ContentType;
 pub use self::cookie::Cookie;
 pub use self::date::Date;
@@ -37,6 +44,7 @@ pub use self::etag::ETag;
 pub use self::expect::Expect;
 pub use self::expires::Expires;
 //pub use self::from::From;
+pub use self::forwarded::{Forwarded, ForwardedElement, ForwardedNode};
 pub use self::host::Host;
 pub use self::if_match::IfMatch;
 pub use self::if_modified_since::IfModifiedSince;
@@ -45,13 +53,16 @@ pub use self::if_range::IfRange;
 pub use self::if_unmodified_since::IfUnmodifiedSince;
 //pub use self::last_event_id::LastEventId;
 pub use self::last_modified::LastModified;
-//pub use self::link::{Link, LinkValue, RelationType, MediaDesc};
+pub use self::link::{Link, LinkValue, MediaDesc, RelationType};
 pub use self::location::Location;
 pub use self::origin::Origin;
 pub use self::pragma::Pragma;
-//pub use self::prefer::{Prefer, Preference};
-//pub use self::preference_applied::PreferenceApplied;
-pub use self::proxy_authorization::ProxyAuthorization;
+pub use self::prefer::{Prefer, Preference};
+pub use self::preference_applied::PreferenceApplied;
+pub use self::priority::Priority;
+pub use self::proxy_authenticate::ProxyAuthenticate;
+pub use self::proxy_authentication_info::ProxyAuthenticationInfo;
+pub use self::proxy_authorization::{AnyProxyAuthorization, ProxyAuthorization};
 pub use self::range::Range;
 pub use self::referer::Referer;
 pub use self::referrer_policy::ReferrerPolicy;
@@ -62,15 +73,21 @@ pub use self::sec_websocket_version::SecWebsocketVersion;
 pub use self::server::Server;
 pub use self::set_cookie::SetCookie;
 pub use self::strict_transport_security::StrictTransportSecurity;
+pub use self::surrogate_control::SurrogateControl;
 pub use self::te::Te;
 pub use self::transfer_encoding::TransferEncoding;
 pub use self::upgrade::Upgrade;
 pub use self::user_agent::UserAgent;
 pub use self::vary::Vary;
+pub use self::via::{Via, ViaEntry};
 //pub use self::warning::Warning;
+pub use self::www_authenticate::WwwAuthenticate;
+pub use self::x_forwarded_for::XForwardedFor;
+pub use self::x_forwarded_host::XForwardedHost;
+pub use self::x_forwarded_proto::XForwardedProto;
 
 #[cfg(test)]
-fn test_decode<T: crate::Header>(values: &[&str]) -> Option<T> {
+pub(crate) fn test_decode<T: crate::Header>(values: &[&str]) -> Option<T> {
     use crate::HeaderMapExt;
     let mut map = ::http::HeaderMap::new();
     for val in values {
@@ -80,7 +97,7 @@ fn test_decode<T: crate::Header>(values: &[&str]) -> Option<T> {
 }
 
 #[cfg(test)]
-fn test_encode<T: crate::Header>(header: T) -> ::http::HeaderMap {
+pub(crate) fn test_encode<T: crate::Header>(header: T) -> ::http::HeaderMap {
     use crate::HeaderMapExt;
     let mut map = ::http::HeaderMap::new();
     map.typed_insert(header);
@@ -126,10 +143,10 @@ macro_rules! bench_header {
     };
 }
 
-//mod accept;
-//mod accept_charset;
-//mod accept_encoding;
-//mod accept_language;
+mod accept;
+mod accept_charset;
+mod accept_encoding;
+mod accept_language;
 mod accept_ranges;
 mod access_control_allow_credentials;
 mod access_control_allow_headers;
@@ -141,15 +158,20 @@ mod access_control_request_headers;
 mod access_control_request_method;
 mod age;
 mod allow;
+mod authentication_info;
 pub mod authorization;
 mod cache_control;
+mod cache_status;
+mod cdn_cache_control;
 mod connection;
 mod content_disposition;
 mod content_encoding;
-//mod content_language;
+mod content_language;
 mod content_length;
 mod content_location;
 mod content_range;
+mod content_security_policy;
+mod content_security_policy_report_only;
 mod content_type;
 mod cookie;
 mod date;
@@ -157,6 +179,7 @@ mod etag;
 mod expect;
 mod expires;
 //mod from;
+mod forwarded;
 mod host;
 mod if_match;
 mod if_modified_since;
@@ -165,12 +188,15 @@ mod if_range;
 mod if_unmodified_since;
 //mod last_event_id;
 mod last_modified;
-//mod link;
+mod link;
 mod location;
 mod origin;
 mod pragma;
-//mod prefer;
-//mod preference_applied;
+mod prefer;
+mod preference_applied;
+mod priority;
+mod proxy_authenticate;
+mod proxy_authentication_info;
 mod proxy_authorization;
 mod range;
 mod referer;
@@ -182,9 +208,15 @@ mod sec_websocket_version;
 mod server;
 mod set_cookie;
 mod strict_transport_security;
+mod surrogate_control;
 mod te;
 mod transfer_encoding;
 mod upgrade;
 mod user_agent;
 mod vary;
+mod via;
 //mod warning;
+mod www_authenticate;
+mod x_forwarded_for;
+mod x_forwarded_host;
+mod x_forwarded_proto;
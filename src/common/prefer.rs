@@ -0,0 +1,478 @@
+use std::fmt;
+use std::iter::FromIterator;
+use std::str::FromStr;
+use std::time::Duration;
+
+use http::{HeaderName, HeaderValue};
+
+use super::PreferenceApplied;
+use crate::util::{self, is_ows, FlatCsv, TryFromValues};
+use crate::{Error, Header};
+
+static PREFER: HeaderName = HeaderName::from_static("prefer");
+
+/// `Prefer` header, defined in [RFC7240](https://datatracker.ietf.org/doc/html/rfc7240)
+///
+/// The `Prefer` header field can be used by a client to request that certain
+/// behaviors be employed by a server while processing a request.
+///
+/// If a preference is listed more than once, only the first one is
+/// considered.
+///
+/// # ABNF
+///
+/// ```text
+/// Prefer     = "Prefer" ":" 1#preference
+/// preference = token [ BWS "=" BWS word ]
+///              *( OWS ";" [ OWS parameter ] )
+/// parameter  = token [ BWS "=" BWS word ]
+/// ```
+///
+/// # Example values
+/// * `respond-async`
+/// * `return=minimal`
+/// * `wait=30`
+///
+/// # Examples
+///
+/// ```
+/// use headers::{Prefer, Preference};
+///
+/// let prefer = vec![
+///     Preference::RespondAsync,
+///     Preference::ReturnRepresentation,
+///     Preference::Wait(10),
+///     Preference::Extension("foo".to_owned(), "bar".to_owned(), vec![]),
+/// ]
+/// .into_iter()
+/// .collect::<Prefer>();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prefer(FlatCsv);
+
+/// Prefer contains a list of these preferences.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Preference {
+    /// "respond-async"
+    RespondAsync,
+    /// "return=representation"
+    ReturnRepresentation,
+    /// "return=minimal"
+    ReturnMinimal,
+    /// "handling=strict"
+    HandlingStrict,
+    /// "handling=lenient"
+    HandlingLenient,
+    /// "wait=delta"
+    Wait(u32),
+
+    /// Extension preferences. Always has a value, if none is specified it is
+    /// just "". A preference can also have a list of parameters.
+    Extension(String, String, Vec<(String, String)>),
+}
+
+impl Header for Prefer {
+    fn name() -> &'static HeaderName {
+        &PREFER
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        validate(values).map(Prefer)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(::std::iter::once((&self.0).into()));
+    }
+}
+
+impl Prefer {
+    /// Iterate the preferences of this header.
+    pub fn iter(&self) -> impl Iterator<Item = Preference> + '_ {
+        self.0
+            .iter()
+            .filter(|item| !item.is_empty())
+            .filter_map(|item| item.parse().ok())
+    }
+
+    /// Get the preference with the given name, such as `"return"`.
+    ///
+    /// If the preference is listed more than once, the first one is
+    /// returned.
+    pub fn get(&self, name: &str) -> Option<Preference> {
+        self.iter()
+            .find(|pref| pref.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Check if the client expressed a preference.
+    ///
+    /// A preference that is overridden by an earlier one with the same name
+    /// is ignored, such that `return=minimal, return=representation` only
+    /// contains `Preference::ReturnMinimal`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use headers::{HeaderMap, HeaderMapExt, Prefer, Preference};
+    ///
+    /// let mut req = HeaderMap::new();
+    /// req.insert("prefer", "return=minimal, respond-async".parse().unwrap());
+    /// let prefer = req.typed_get::<Prefer>().unwrap();
+    ///
+    /// assert!(prefer.contains(&Preference::ReturnMinimal));
+    /// assert!(prefer.contains(&Preference::RespondAsync));
+    /// assert!(!prefer.contains(&Preference::ReturnRepresentation));
+    /// ```
+    pub fn contains(&self, pref: &Preference) -> bool {
+        self.get(pref.name())
+            .map(|first| first.eq_ignoring_params(pref))
+            .unwrap_or(false)
+    }
+
+    /// Get the `wait` preference, if any.
+    pub fn wait(&self) -> Option<Duration> {
+        match self.get("wait") {
+            Some(Preference::Wait(secs)) => Some(Duration::from_secs(secs.into())),
+            _ => None,
+        }
+    }
+
+    /// Build the `Preference-Applied` header for the preferences the server
+    /// honored.
+    ///
+    /// The `honored` function is called with each preference that is in
+    /// effect, meaning repeated preferences after the first are skipped.
+    /// Parameters of extension preferences are removed, since they are not
+    /// sent back in `Preference-Applied`.
+    ///
+    /// Returns `None` if no preference was honored, in which case the
+    /// `Preference-Applied` header should be omitted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use headers::{HeaderMap, HeaderMapExt, Prefer, Preference};
+    ///
+    /// let mut req = HeaderMap::new();
+    /// req.insert("prefer", "return=minimal, respond-async".parse().unwrap());
+    /// let prefer = req.typed_get::<Prefer>().unwrap();
+    ///
+    /// let mut res = HeaderMap::new();
+    /// if let Some(applied) = prefer.applied(|pref| *pref == Preference::ReturnMinimal) {
+    ///     res.typed_insert(applied);
+    /// }
+    /// assert_eq!(res["preference-applied"], "return=minimal");
+    /// ```
+    pub fn applied<F>(&self, mut honored: F) -> Option<PreferenceApplied>
+    where
+        F: FnMut(&Preference) -> bool,
+    {
+        let mut seen: Vec<Preference> = Vec::new();
+        let mut applied = Vec::new();
+        for pref in self.iter() {
+            if seen
+                .iter()
+                .any(|s| s.name().eq_ignore_ascii_case(pref.name()))
+            {
+                continue;
+            }
+            if honored(&pref) {
+                applied.push(pref.clone());
+            }
+            seen.push(pref);
+        }
+
+        if applied.is_empty() {
+            None
+        } else {
+            Some(applied.into_iter().collect())
+        }
+    }
+}
+
+impl FromIterator<Preference> for Prefer {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Preference>,
+    {
+        let flat = iter.into_iter().map(util::fmt).collect();
+        Prefer(flat)
+    }
+}
+
+impl Preference {
+    /// Get the name of this preference, such as `"return"`.
+    pub fn name(&self) -> &str {
+        match *self {
+            Preference::RespondAsync => "respond-async",
+            Preference::ReturnRepresentation | Preference::ReturnMinimal => "return",
+            Preference::HandlingStrict | Preference::HandlingLenient => "handling",
+            Preference::Wait(_) => "wait",
+            Preference::Extension(ref name, _, _) => name,
+        }
+    }
+
+    pub(super) fn without_params(self) -> Preference {
+        match self {
+            Preference::Extension(name, value, _) => Preference::Extension(name, value, Vec::new()),
+            pref => pref,
+        }
+    }
+
+    fn eq_ignoring_params(&self, other: &Preference) -> bool {
+        match (self, other) {
+            (Preference::Extension(n1, v1, _), Preference::Extension(n2, v2, _)) => {
+                n1.eq_ignore_ascii_case(n2) && v1 == v2
+            }
+            _ => self == other,
+        }
+    }
+}
+
+impl fmt::Display for Preference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::Preference::*;
+        fmt::Display::fmt(
+            match *self {
+                RespondAsync => "respond-async",
+                ReturnRepresentation => "return=representation",
+                ReturnMinimal => "return=minimal",
+                HandlingStrict => "handling=strict",
+                HandlingLenient => "handling=lenient",
+
+                Wait(secs) => return write!(f, "wait={}", secs),
+
+                Extension(ref name, ref value, ref params) => {
+                    f.write_str(name)?;
+                    if !value.is_empty() {
+                        f.write_str("=")?;
+                        util::fmt_token_or_quoted(f, value)?;
+                    }
+                    for (name, value) in params {
+                        write!(f, "; {}", name)?;
+                        if !value.is_empty() {
+                            f.write_str("=")?;
+                            util::fmt_token_or_quoted(f, value)?;
+                        }
+                    }
+                    return Ok(());
+                }
+            },
+            f,
+        )
+    }
+}
+
+impl FromStr for Preference {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Preference, Error> {
+        use self::Preference::*;
+
+        let (name, value, mut rest) = util::parse_param(s)?;
+        let value = value.unwrap_or_default();
+
+        let mut params = Vec::new();
+        loop {
+            rest = rest.trim_start_matches(is_ows);
+            if rest.is_empty() {
+                break;
+            }
+            rest = rest
+                .strip_prefix(';')
+                .ok_or_else(Error::invalid)?
+                .trim_start_matches(is_ows);
+            // Empty parameters are allowed, such as in `foo;;bar`.
+            if rest.is_empty() || rest.starts_with(';') {
+                continue;
+            }
+            let (param, param_value, after) = util::parse_param(rest)?;
+            params.push((param.to_owned(), param_value.unwrap_or_default()));
+            rest = after;
+        }
+
+        let is = |a: &str, b: &str| a.eq_ignore_ascii_case(b);
+
+        // No parameters are defined for the known preferences, so any that
+        // are sent are ignored.
+        Ok(match (name, value.as_str()) {
+            (n, "") if is(n, "respond-async") => RespondAsync,
+            (n, v) if is(n, "return") && is(v, "representation") => ReturnRepresentation,
+            (n, v) if is(n, "return") && is(v, "minimal") => ReturnMinimal,
+            (n, v) if is(n, "handling") && is(v, "strict") => HandlingStrict,
+            (n, v) if is(n, "handling") && is(v, "lenient") => HandlingLenient,
+            // A malformed delay is kept as an extension, which is ignored by
+            // `Prefer::wait`, instead of making the whole header invalid.
+            (n, _) if is(n, "wait") => match value.parse() {
+                Ok(secs) => Wait(secs),
+                Err(_) => Extension(name.to_owned(), value, params),
+            },
+            (name, _) => Extension(name.to_owned(), value, params),
+        })
+    }
+}
+
+/// Decode a list of preferences, requiring at least one.
+pub(super) fn validate<'i, I>(values: &mut I) -> Result<FlatCsv, Error>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let csv = FlatCsv::try_from_values(values)?;
+    let mut count = 0;
+    for item in csv.iter().filter(|item| !item.is_empty()) {
+        item.parse::<Preference>()?;
+        count += 1;
+    }
+
+    if count > 0 {
+        Ok(csv)
+    } else {
+        Err(Error::invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    fn prefer(values: &[&str]) -> Option<Vec<Preference>> {
+        test_decode::<Prefer>(values).map(|prefer| prefer.iter().collect())
+    }
+
+    #[test]
+    fn test_parse_multiple_headers() {
+        assert_eq!(
+            prefer(&["respond-async, return=representation"]),
+            Some(vec![
+                Preference::RespondAsync,
+                Preference::ReturnRepresentation
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_argument() {
+        assert_eq!(
+            prefer(&["wait=100, handling=lenient, respond-async"]),
+            Some(vec![
+                Preference::Wait(100),
+                Preference::HandlingLenient,
+                Preference::RespondAsync
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_quote_form() {
+        assert_eq!(
+            prefer(&["wait=\"200\", handling=\"strict\""]),
+            Some(vec![Preference::Wait(200), Preference::HandlingStrict])
+        );
+    }
+
+    #[test]
+    fn test_parse_extension() {
+        assert_eq!(
+            prefer(&[
+                "foo, bar=baz, baz; foo; bar=baz, bux=\"\"; foo=\"\", buz=\"some parameter\""
+            ]),
+            Some(vec![
+                Preference::Extension("foo".to_owned(), "".to_owned(), vec![]),
+                Preference::Extension("bar".to_owned(), "baz".to_owned(), vec![]),
+                Preference::Extension(
+                    "baz".to_owned(),
+                    "".to_owned(),
+                    vec![
+                        ("foo".to_owned(), "".to_owned()),
+                        ("bar".to_owned(), "baz".to_owned())
+                    ]
+                ),
+                Preference::Extension(
+                    "bux".to_owned(),
+                    "".to_owned(),
+                    vec![("foo".to_owned(), "".to_owned())]
+                ),
+                Preference::Extension("buz".to_owned(), "some parameter".to_owned(), vec![])
+            ])
+        );
+    }
+
+    #[test]
+    fn test_ignore_args_of_known() {
+        assert_eq!(
+            prefer(&["respond-async; foo=bar, return=minimal; foo=bar"]),
+            Some(vec![Preference::RespondAsync, Preference::ReturnMinimal])
+        );
+    }
+
+    #[test]
+    fn test_malformed_wait() {
+        let prefer = test_decode::<Prefer>(&["wait=abc, return=minimal"]).unwrap();
+        assert!(prefer.contains(&Preference::ReturnMinimal));
+        assert_eq!(prefer.wait(), None);
+        assert_eq!(
+            prefer.get("wait"),
+            Some(Preference::Extension(
+                "wait".to_owned(),
+                "abc".to_owned(),
+                vec![]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_fail_invalid() {
+        assert_eq!(prefer(&[""]), None);
+        assert_eq!(prefer(&["=minimal"]), None);
+    }
+
+    #[test]
+    fn test_format() {
+        let prefer = vec![
+            Preference::ReturnMinimal,
+            Preference::Wait(5),
+            Preference::Extension(
+                "foo".to_owned(),
+                "a b".to_owned(),
+                vec![("x".to_owned(), "".to_owned())],
+            ),
+        ]
+        .into_iter()
+        .collect::<Prefer>();
+
+        let headers = test_encode(prefer);
+        assert_eq!(headers["prefer"], "return=minimal, wait=5, foo=\"a b\"; x");
+    }
+
+    #[test]
+    fn test_first_preference_wins() {
+        let prefer =
+            test_decode::<Prefer>(&["return=minimal", "return=representation, wait=1"]).unwrap();
+        assert!(prefer.contains(&Preference::ReturnMinimal));
+        assert!(!prefer.contains(&Preference::ReturnRepresentation));
+        assert_eq!(prefer.get("Return"), Some(Preference::ReturnMinimal));
+        assert_eq!(prefer.wait(), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_applied() {
+        let prefer = test_decode::<Prefer>(&[
+            "return=minimal, respond-async, foo=bar; baz=1, return=representation",
+        ])
+        .unwrap();
+
+        let applied = prefer
+            .applied(|pref| matches!(pref, Preference::Extension(..)) || pref.name() == "return")
+            .unwrap();
+        let headers = test_encode(applied);
+        assert_eq!(headers["preference-applied"], "return=minimal, foo=bar");
+
+        assert_eq!(prefer.applied(|_| false), None);
+    }
+
+    bench_header!(
+        bench,
+        Prefer,
+        "respond-async, return=representation, wait=100"
+    );
+}
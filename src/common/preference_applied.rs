@@ -0,0 +1,125 @@
+use std::iter::FromIterator;
+
+use http::{HeaderName, HeaderValue};
+
+use super::prefer::validate;
+use super::Preference;
+use crate::util::{self, FlatCsv};
+use crate::{Error, Header};
+
+static PREFERENCE_APPLIED: HeaderName = HeaderName::from_static("preference-applied");
+
+/// `Preference-Applied` header, defined in [RFC7240](https://datatracker.ietf.org/doc/html/rfc7240)
+///
+/// The `Preference-Applied` response header may be included within a
+/// response message as an indication as to which `Prefer` header tokens were
+/// honored by the server and applied to the processing of a request.
+///
+/// Use [`Prefer::applied`](crate::Prefer::applied) to build this header
+/// from the preferences of a request.
+///
+/// # ABNF
+///
+/// ```text
+/// Preference-Applied = "Preference-Applied" ":" 1#applied-pref
+/// applied-pref = token [ BWS "=" BWS word ]
+/// ```
+///
+/// # Example values
+///
+/// * `respond-async`
+/// * `return=minimal`
+/// * `wait=30`
+///
+/// # Examples
+///
+/// ```
+/// use headers::{PreferenceApplied, Preference};
+///
+/// let applied = vec![
+///     Preference::RespondAsync,
+///     Preference::ReturnRepresentation,
+///     Preference::Wait(10),
+///     Preference::Extension("foo".to_owned(), "bar".to_owned(), vec![]),
+/// ]
+/// .into_iter()
+/// .collect::<PreferenceApplied>();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreferenceApplied(FlatCsv);
+
+impl Header for PreferenceApplied {
+    fn name() -> &'static HeaderName {
+        &PREFERENCE_APPLIED
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        validate(values).map(PreferenceApplied)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(::std::iter::once((&self.0).into()));
+    }
+}
+
+impl PreferenceApplied {
+    /// Iterate the applied preferences of this header.
+    pub fn iter(&self) -> impl Iterator<Item = Preference> + '_ {
+        self.0
+            .iter()
+            .filter(|item| !item.is_empty())
+            .filter_map(|item| item.parse().ok())
+    }
+}
+
+impl FromIterator<Preference> for PreferenceApplied {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Preference>,
+    {
+        // The spec ignores parameters in `Preferences-Applied`
+        let flat = iter
+            .into_iter()
+            .map(|pref| util::fmt(pref.without_params()))
+            .collect();
+        PreferenceApplied(flat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn test_format_ignore_parameters() {
+        let applied = vec![Preference::Extension(
+            "foo".to_owned(),
+            "bar".to_owned(),
+            vec![
+                ("bar".to_owned(), "foo".to_owned()),
+                ("buz".to_owned(), "".to_owned()),
+            ],
+        )]
+        .into_iter()
+        .collect::<PreferenceApplied>();
+
+        let headers = test_encode(applied);
+        assert_eq!(headers["preference-applied"], "foo=bar");
+    }
+
+    #[test]
+    fn test_parse() {
+        let applied = test_decode::<PreferenceApplied>(&["respond-async, wait=30"]).unwrap();
+        assert_eq!(
+            applied.iter().collect::<Vec<_>>(),
+            vec![Preference::RespondAsync, Preference::Wait(30)]
+        );
+    }
+
+    bench_header!(
+        bench,
+        PreferenceApplied,
+        "respond-async, return=representation"
+    );
+}
@@ -0,0 +1,207 @@
+use http::{HeaderName, HeaderValue};
+
+use crate::structured::{self, BareItem, FmtDictionary, Item, Member};
+use crate::util;
+use crate::{Error, Header};
+
+static PRIORITY: HeaderName = HeaderName::from_static("priority");
+
+const DEFAULT_URGENCY: u8 = 3;
+const MAX_URGENCY: u8 = 7;
+
+/// `Priority` header, defined in
+/// [RFC9218](https://www.rfc-editor.org/rfc/rfc9218)
+///
+/// The `Priority` header field carries the priority of a response, as
+/// signaled by a client in a request, or by a server in a response. It is a
+/// structured field dictionary with two parameters:
+///
+/// * `u`, the urgency, from 0 (highest) to 7 (lowest), which defaults to 3.
+/// * `i`, whether the response can be processed incrementally, which
+///   defaults to false.
+///
+/// Unknown keys, and parameters with an invalid value, are ignored.
+///
+/// # Example values
+///
+/// * `u=1`
+/// * `u=5, i`
+///
+/// # Example
+///
+/// ```
+/// use headers::Priority;
+///
+/// let priority = Priority::new().with_urgency(5).with_incremental(true);
+/// assert_eq!(priority.urgency(), 5);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Priority {
+    urgency: Option<u8>,
+    incremental: Option<bool>,
+}
+
+impl Header for Priority {
+    fn name() -> &'static HeaderName {
+        &PRIORITY
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let dict = structured::parse_dictionary(values)?;
+        let mut priority = Priority::new();
+        for (key, member) in dict {
+            let bare_item = match member {
+                Member::Item(item) => item.bare_item,
+                Member::InnerList(_) => continue,
+            };
+            match (key.as_str(), bare_item) {
+                ("u", BareItem::Integer(urgency))
+                    if (0..=MAX_URGENCY.into()).contains(&urgency) =>
+                {
+                    priority.urgency = Some(urgency as u8);
+                }
+                ("i", BareItem::Boolean(incremental)) => {
+                    priority.incremental = Some(incremental);
+                }
+                _ => (),
+            }
+        }
+        Ok(priority)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let mut dict = Vec::new();
+        if let Some(urgency) = self.urgency {
+            let item = Item::new(BareItem::Integer(urgency.into()));
+            dict.push(("u".to_owned(), Member::Item(item)));
+        }
+        if let Some(incremental) = self.incremental {
+            let item = Item::new(BareItem::Boolean(incremental));
+            dict.push(("i".to_owned(), Member::Item(item)));
+        }
+        values.extend(::std::iter::once(util::fmt(FmtDictionary(&dict))));
+    }
+}
+
+impl Priority {
+    /// Create a `Priority` header with the default parameters.
+    ///
+    /// It is encoded as an empty value.
+    pub fn new() -> Self {
+        Priority::default()
+    }
+
+    /// Get the urgency, from 0 (highest) to 7 (lowest).
+    pub fn urgency(&self) -> u8 {
+        self.urgency.unwrap_or(DEFAULT_URGENCY)
+    }
+
+    /// Check if the response can be processed incrementally.
+    pub fn incremental(&self) -> bool {
+        self.incremental.unwrap_or(false)
+    }
+
+    /// Set the urgency.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the urgency is greater than 7.
+    pub fn with_urgency(mut self, urgency: u8) -> Self {
+        assert!(urgency <= MAX_URGENCY, "urgency must be between 0 and 7");
+        self.urgency = Some(urgency);
+        self
+    }
+
+    /// Set whether the response can be processed incrementally.
+    pub fn with_incremental(mut self, incremental: bool) -> Self {
+        self.incremental = Some(incremental);
+        self
+    }
+
+    /// Apply the parameters of another priority on top of this one.
+    ///
+    /// Parameters that are present in `other` override those of `self`,
+    /// and the others are kept. This is how a server combines the priority
+    /// of a request with a later `PRIORITY_UPDATE` frame, or with the
+    /// `Priority` of its own response.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use headers::{HeaderMap, HeaderMapExt, Priority};
+    ///
+    /// let mut req = HeaderMap::new();
+    /// req.insert("priority", "u=1, i".parse().unwrap());
+    /// let client = req.typed_get::<Priority>().unwrap();
+    ///
+    /// let merged = client.merge(&Priority::new().with_urgency(5));
+    /// assert_eq!(merged.urgency(), 5);
+    /// assert!(merged.incremental());
+    /// ```
+    pub fn merge(&self, other: &Priority) -> Priority {
+        Priority {
+            urgency: other.urgency.or(self.urgency),
+            incremental: other.incremental.or(self.incremental),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let priority = test_decode::<Priority>(&[""]).unwrap();
+        assert_eq!(priority.urgency(), 3);
+        assert!(!priority.incremental());
+        assert_eq!(test_encode(priority)["priority"], "");
+    }
+
+    #[test]
+    fn parameters() {
+        let priority = test_decode::<Priority>(&["u=0", "i"]).unwrap();
+        assert_eq!(priority.urgency(), 0);
+        assert!(priority.incremental());
+        assert_eq!(test_encode(priority)["priority"], "u=0, i");
+
+        let priority = test_decode::<Priority>(&["i=?0, u=7"]).unwrap();
+        assert_eq!(
+            priority,
+            Priority::new().with_urgency(7).with_incremental(false)
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_and_invalid() {
+        let priority = test_decode::<Priority>(&["u=8, i=1, x=?1, v=(a b)"]).unwrap();
+        assert_eq!(priority, Priority::new());
+
+        let priority = test_decode::<Priority>(&["u=-1;foo, u=2;bar, i;baz"]).unwrap();
+        assert_eq!(priority.urgency(), 2);
+        assert!(priority.incremental());
+
+        assert_eq!(test_decode::<Priority>(&["u=1,"]), None);
+        assert_eq!(test_decode::<Priority>(&["U=1"]), None);
+    }
+
+    #[test]
+    fn merge() {
+        let base = Priority::new().with_urgency(1).with_incremental(true);
+        assert_eq!(base.merge(&Priority::new()), base);
+        assert_eq!(
+            base.merge(&Priority::new().with_incremental(false)),
+            Priority::new().with_urgency(1).with_incremental(false)
+        );
+        assert_eq!(Priority::new().merge(&base), base);
+    }
+
+    #[test]
+    #[should_panic(expected = "urgency must be between 0 and 7")]
+    fn invalid_urgency() {
+        let _ = Priority::new().with_urgency(8);
+    }
+
+    bench_header!(bench, Priority, "u=5, i");
+}
@@ -0,0 +1,107 @@
+use std::iter::FromIterator;
+
+use http::{HeaderName, HeaderValue};
+
+use super::authorization::Challenge;
+use super::www_authenticate::{decode_challenges, find_challenge};
+use crate::util;
+use crate::{Error, Header};
+
+/// `Proxy-Authenticate` header, defined in
+/// [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-11.7.1)
+///
+/// The `Proxy-Authenticate` header field consists of at least one
+/// challenge that indicates the authentication scheme and parameters
+/// applicable to the proxy. It is sent in 407 (Proxy Authentication
+/// Required) responses, and has the same syntax as
+/// [`WwwAuthenticate`](super::WwwAuthenticate).
+///
+/// # ABNF
+///
+/// ```text
+/// Proxy-Authenticate = #challenge
+/// ```
+///
+/// # Example values
+///
+/// * `Basic realm="proxy"`
+///
+/// # Example
+///
+/// ```
+/// use headers::authorization::Challenge;
+/// use headers::ProxyAuthenticate;
+///
+/// let proxy_auth = ProxyAuthenticate::new(Challenge::basic("proxy"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProxyAuthenticate(Vec<Challenge>);
+
+impl Header for ProxyAuthenticate {
+    fn name() -> &'static HeaderName {
+        &::http::header::PROXY_AUTHENTICATE
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        decode_challenges(values).map(ProxyAuthenticate)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(self.0.iter().map(util::fmt));
+    }
+}
+
+impl ProxyAuthenticate {
+    /// Create a `Proxy-Authenticate` header with a single challenge.
+    pub fn new(challenge: Challenge) -> Self {
+        ProxyAuthenticate(vec![challenge])
+    }
+
+    /// Iterate the challenges of this header.
+    pub fn challenges(&self) -> impl Iterator<Item = &Challenge> {
+        self.0.iter()
+    }
+
+    /// Get the first challenge with the given authentication scheme.
+    ///
+    /// Schemes are compared case-insensitively.
+    pub fn find(&self, scheme: &str) -> Option<&Challenge> {
+        find_challenge(&self.0, scheme)
+    }
+
+    /// Add a challenge after the existing ones.
+    pub fn push(&mut self, challenge: Challenge) {
+        self.0.push(challenge);
+    }
+}
+
+impl From<Challenge> for ProxyAuthenticate {
+    fn from(challenge: Challenge) -> Self {
+        ProxyAuthenticate::new(challenge)
+    }
+}
+
+impl FromIterator<Challenge> for ProxyAuthenticate {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Challenge>,
+    {
+        ProxyAuthenticate(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let proxy_auth = test_decode::<ProxyAuthenticate>(&[r#"Basic realm="proxy""#]).unwrap();
+        assert_eq!(proxy_auth.find("Basic").unwrap().realm(), Some("proxy"));
+        assert_eq!(
+            test_encode(proxy_auth)["proxy-authenticate"],
+            r#"Basic realm="proxy""#
+        );
+    }
+}
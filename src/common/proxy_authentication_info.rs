@@ -0,0 +1,68 @@
+use http::{HeaderName, HeaderValue};
+
+use super::AuthenticationInfo;
+use crate::{Error, Header};
+
+static PROXY_AUTHENTICATION_INFO: HeaderName = HeaderName::from_static("proxy-authentication-info");
+
+/// `Proxy-Authentication-Info` header, defined in
+/// [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-11.7.3)
+///
+/// The `Proxy-Authentication-Info` header field is equivalent to
+/// [`AuthenticationInfo`], except that it applies to proxy authentication.
+///
+/// # ABNF
+///
+/// ```text
+/// Proxy-Authentication-Info = #auth-param
+/// ```
+///
+/// # Example values
+///
+/// * `nextnonce="47364c23432d2e131a5fb210812c"`
+///
+/// # Example
+///
+/// ```
+/// use headers::{AuthenticationInfo, ProxyAuthenticationInfo};
+///
+/// let info = ProxyAuthenticationInfo(AuthenticationInfo::new().with_nextnonce("abc"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProxyAuthenticationInfo(pub AuthenticationInfo);
+
+impl Header for ProxyAuthenticationInfo {
+    fn name() -> &'static HeaderName {
+        &PROXY_AUTHENTICATION_INFO
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        AuthenticationInfo::decode(values).map(ProxyAuthenticationInfo)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        self.0.encode(values);
+    }
+}
+
+impl From<AuthenticationInfo> for ProxyAuthenticationInfo {
+    fn from(info: AuthenticationInfo) -> Self {
+        ProxyAuthenticationInfo(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let info = test_decode::<ProxyAuthenticationInfo>(&[r#"nextnonce="abc""#]).unwrap();
+        assert_eq!(info.0.nextnonce(), Some("abc"));
+        assert_eq!(
+            test_encode(info)["proxy-authentication-info"],
+            r#"nextnonce="abc""#
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use http::{HeaderName, HeaderValue};
 
-use super::authorization::{Authorization, Credentials};
+use super::authorization::{AnyCredentials, Authorization, Credentials};
 use crate::{Error, Header};
 
 /// `Proxy-Authorization` header, defined in [RFC7235](https://tools.ietf.org/html/rfc7235#section-4.4)
@@ -48,3 +48,28 @@ impl<C: Credentials> Header for ProxyAuthorization<C> {
         values.extend(::std::iter::once(value));
     }
 }
+
+/// `Proxy-Authorization` header with credentials of any scheme.
+///
+/// This is the [`AnyAuthorization`](super::AnyAuthorization) equivalent of
+/// [`ProxyAuthorization`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct AnyProxyAuthorization(pub AnyCredentials);
+
+impl Header for AnyProxyAuthorization {
+    fn name() -> &'static HeaderName {
+        &::http::header::PROXY_AUTHORIZATION
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        values
+            .next()
+            .and_then(AnyCredentials::decode)
+            .map(AnyProxyAuthorization)
+            .ok_or_else(Error::invalid)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(::std::iter::once(self.0.encode()));
+    }
+}
@@ -73,40 +73,71 @@ impl Range {
         &self,
         len: u64,
     ) -> impl Iterator<Item = (Bound<u64>, Bound<u64>)> + '_ {
+        self.specs().flatten().filter_map(move |spec| match spec {
+            (Some(first), last) => Some((
+                Bound::Included(first),
+                last.map_or(Bound::Unbounded, Bound::Included),
+            )),
+            // Unbounded ranges in HTTP are actually a suffix
+            // For example, `-100` means the last 100 bytes.
+            (None, Some(suffix)) if suffix <= len => {
+                Some((Bound::Included(len - suffix), Bound::Unbounded))
+            }
+            // Last N bytes is larger than available!
+            _ => None,
+        })
+    }
+
+    /// Parse the byte-range-specs of this header as `(first, last)` byte
+    /// positions, where a missing first position denotes a suffix range.
+    ///
+    /// Returns `None` if any of the specs is invalid, in which case the
+    /// whole header should be ignored.
+    pub(crate) fn byte_range_specs(&self) -> Option<Vec<(Option<u64>, Option<u64>)>> {
+        let specs = self.specs().collect::<Option<Vec<_>>>()?;
+        if specs.is_empty() {
+            None
+        } else {
+            Some(specs)
+        }
+    }
+
+    /// Iterate the byte-range-specs, with `None` for the invalid ones.
+    fn specs(&self) -> impl Iterator<Item = Option<(Option<u64>, Option<u64>)>> + '_ {
         let s = self
             .0
             .to_str()
             .expect("valid string checked in Header::decode()");
 
-        s["bytes=".len()..].split(',').filter_map(move |spec| {
-            let mut iter = spec.trim().splitn(2, '-');
-            let start = parse_bound(iter.next()?)?;
-            let end = parse_bound(iter.next()?)?;
-
-            // Unbounded ranges in HTTP are actually a suffix
-            // For example, `-100` means the last 100 bytes.
-            if let Bound::Unbounded = start {
-                if let Bound::Included(end) = end {
-                    if len < end {
-                        // Last N bytes is larger than available!
-                        return None;
-                    }
-                    return Some((Bound::Included(len - end), Bound::Unbounded));
-                }
-                // else fall through
-            }
+        s["bytes=".len()..]
+            .split(',')
+            .map(str::trim)
+            .filter(|spec| !spec.is_empty())
+            .map(parse_spec)
+    }
+}
 
-            Some((start, end))
-        })
+/// Parse a `byte-range-spec` or a `suffix-byte-range-spec`.
+fn parse_spec(spec: &str) -> Option<(Option<u64>, Option<u64>)> {
+    let mut iter = spec.splitn(2, '-');
+    let first = parse_position(iter.next()?)?;
+    let last = parse_position(iter.next()?)?;
+    match (first, last) {
+        (None, None) => None,
+        (Some(first), Some(last)) if first > last => None,
+        spec => Some(spec),
     }
 }
 
-fn parse_bound(s: &str) -> Option<Bound<u64>> {
+fn parse_position(s: &str) -> Option<Option<u64>> {
     if s.is_empty() {
-        return Some(Bound::Unbounded);
+        return Some(None);
     }
 
-    s.parse().ok().map(Bound::Included)
+    if !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok().map(Some)
 }
 
 impl Header for Range {
@@ -456,3 +487,41 @@ fn test_to_unsatisfiable_range_suffix() {
     let bounds = range.satisfiable_ranges(100).next();
     assert_eq!(bounds, None);
 }
+
+#[test]
+fn test_satisfiable_ranges_skip_invalid() {
+    let range = super::test_decode::<Range>(&["bytes=5-1, 0-9, +1-2, 20-"]).unwrap();
+    let bounds = range.satisfiable_ranges(100).collect::<Vec<_>>();
+    assert_eq!(
+        bounds,
+        vec![
+            (Bound::Included(0), Bound::Included(9)),
+            (Bound::Included(20), Bound::Unbounded)
+        ]
+    );
+}
+
+#[test]
+fn test_byte_range_specs() {
+    let range = super::test_decode::<Range>(&["bytes=0-1, 30-, -100"]).unwrap();
+    assert_eq!(
+        range.byte_range_specs(),
+        Some(vec![
+            (Some(0), Some(1)),
+            (Some(30), None),
+            (None, Some(100))
+        ])
+    );
+
+    let range = super::test_decode::<Range>(&["bytes=5-1"]).unwrap();
+    assert_eq!(range.byte_range_specs(), None);
+
+    let range = super::test_decode::<Range>(&["bytes=0-1,-"]).unwrap();
+    assert_eq!(range.byte_range_specs(), None);
+
+    let range = super::test_decode::<Range>(&["bytes=+1-2"]).unwrap();
+    assert_eq!(range.byte_range_specs(), None);
+
+    let range = super::test_decode::<Range>(&["bytes="]).unwrap();
+    assert_eq!(range.byte_range_specs(), None);
+}
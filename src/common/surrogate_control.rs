@@ -0,0 +1,96 @@
+use http::{HeaderName, HeaderValue};
+
+use super::CacheControl;
+use crate::{Error, Header};
+
+static SURROGATE_CONTROL: HeaderName = HeaderName::from_static("surrogate-control");
+
+/// `Surrogate-Control` header, defined in the
+/// [Edge Architecture Specification](https://www.w3.org/TR/edge-arch/)
+///
+/// The `Surrogate-Control` header field is used by origin servers to
+/// control caching by surrogates, such as reverse proxies. It uses the
+/// same syntax as `Cache-Control`, and surrogates remove it before
+/// forwarding the response.
+///
+/// Directives that are specific to surrogates, such as `no-store-remote`
+/// or `content="ESI/1.0"`, are available as extension directives.
+///
+/// # Example values
+///
+/// * `max-age=3600`
+/// * `no-store-remote, content="ESI/1.0"`
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use headers::{CacheControl, SurrogateControl};
+///
+/// let sc = SurrogateControl::new(
+///     CacheControl::new().with_max_age(Duration::from_secs(3600)),
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SurrogateControl(CacheControl);
+
+impl Header for SurrogateControl {
+    fn name() -> &'static HeaderName {
+        &SURROGATE_CONTROL
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        CacheControl::decode(values).map(SurrogateControl)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        self.0.encode(values)
+    }
+}
+
+impl SurrogateControl {
+    /// Create a `Surrogate-Control` header with the given directives.
+    pub fn new(directives: CacheControl) -> Self {
+        SurrogateControl(directives)
+    }
+
+    /// Get the directives of this header.
+    pub fn directives(&self) -> &CacheControl {
+        &self.0
+    }
+}
+
+impl From<CacheControl> for SurrogateControl {
+    fn from(directives: CacheControl) -> Self {
+        SurrogateControl(directives)
+    }
+}
+
+impl From<SurrogateControl> for CacheControl {
+    fn from(sc: SurrogateControl) -> Self {
+        sc.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let sc = test_decode::<SurrogateControl>(&["max-age=60, content=\"ESI/1.0\""]).unwrap();
+        assert_eq!(sc.directives().max_age(), Some(Duration::from_secs(60)));
+        assert_eq!(sc.directives().extension("content"), Some(Some("ESI/1.0")));
+
+        let headers = test_encode(sc);
+        assert_eq!(
+            headers["surrogate-control"],
+            "max-age=60, content=\"ESI/1.0\""
+        );
+    }
+
+    bench_header!(bench, SurrogateControl, "max-age=3600, no-store-remote");
+}
@@ -40,4 +40,11 @@ impl Te {
     pub fn trailers() -> Self {
         Te(HeaderValue::from_static("trailers").into())
     }
+
+    /// Check if the client is willing to accept trailer fields.
+    pub fn accepts_trailers(&self) -> bool {
+        self.0
+            .iter()
+            .any(|coding| coding.eq_ignore_ascii_case("trailers"))
+    }
 }
@@ -0,0 +1,448 @@
+use std::fmt;
+use std::iter::FromIterator;
+use std::net::Ipv6Addr;
+
+use http::{HeaderMap, HeaderName, HeaderValue, Version};
+
+use crate::util::{self, is_ows, is_tchar, is_token};
+use crate::{Error, Header};
+
+/// `Via` header, defined in
+/// [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-7.6.3)
+///
+/// The `Via` header field indicates the presence of intermediate protocols
+/// and recipients between the user agent and the server, on requests, and
+/// between the origin server and the client, on responses. Each
+/// intermediary appends an entry with the protocol it received the message
+/// with, and its own name.
+///
+/// # ABNF
+///
+/// ```text
+/// Via = #( received-protocol RWS received-by [ RWS comment ] )
+///
+/// received-protocol = [ protocol-name "/" ] protocol-version
+/// received-by       = pseudonym [ ":" port ]
+/// ```
+///
+/// # Example values
+///
+/// * `1.1 proxy.example`
+/// * `1.0 fred, 1.1 p.example.net (Apache/1.1)`
+/// * `HTTP/2 edge:8080`
+///
+/// # Example
+///
+/// ```
+/// use headers::{HeaderMap, HeaderMapExt, Via, ViaEntry};
+///
+/// let mut req = HeaderMap::new();
+/// req.insert("via", "1.1 edge".parse().unwrap());
+///
+/// let via = req.typed_get::<Via>().unwrap();
+/// if via.contains("gateway") {
+///     // This request already went through us.
+/// }
+/// Via::append(&mut req, ViaEntry::from_version(http::Version::HTTP_11, "gateway"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Via(Vec<ViaEntry>);
+
+/// An entry of a `Via` header, added by a single intermediary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ViaEntry {
+    protocol_name: Option<String>,
+    protocol_version: String,
+    received_by: String,
+    comment: Option<String>,
+}
+
+impl Header for Via {
+    fn name() -> &'static HeaderName {
+        &::http::header::VIA
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let mut entries = Vec::new();
+        for value in values {
+            let value = value.to_str().map_err(|_| Error::invalid())?;
+            parse_entries(value, &mut entries)?;
+        }
+
+        if entries.is_empty() {
+            Err(Error::invalid())
+        } else {
+            Ok(Via(entries))
+        }
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let value = self
+            .0
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        values.extend(::std::iter::once(util::fmt(value)));
+    }
+}
+
+impl Via {
+    /// Create a `Via` header with a single entry.
+    pub fn new(entry: ViaEntry) -> Self {
+        Via(vec![entry])
+    }
+
+    /// Iterate the entries of this header, in the order the intermediaries
+    /// were passed.
+    pub fn entries(&self) -> impl Iterator<Item = &ViaEntry> {
+        self.0.iter()
+    }
+
+    /// Add the entry of another intermediary after the existing ones.
+    pub fn push(&mut self, entry: ViaEntry) {
+        self.0.push(entry);
+    }
+
+    /// Check if an intermediary with the given name already forwarded the
+    /// message, such as before adding our own entry.
+    ///
+    /// Names are compared case-insensitively.
+    pub fn contains(&self, received_by: &str) -> bool {
+        self.0
+            .iter()
+            .any(|entry| entry.received_by.eq_ignore_ascii_case(received_by))
+    }
+
+    /// Check if the same intermediary appears more than once, meaning the
+    /// message is being forwarded in a loop.
+    pub fn has_loop(&self) -> bool {
+        self.0.iter().enumerate().any(|(i, entry)| {
+            self.0[..i]
+                .iter()
+                .any(|prev| prev.received_by.eq_ignore_ascii_case(&entry.received_by))
+        })
+    }
+
+    /// Append our own entry to the `Via` of a message, when forwarding it.
+    ///
+    /// The entry is added as a new field line, so the entries of previous
+    /// intermediaries are left exactly as they were received.
+    pub fn append(headers: &mut HeaderMap, entry: ViaEntry) {
+        headers.append(::http::header::VIA, util::fmt(entry));
+    }
+}
+
+impl From<ViaEntry> for Via {
+    fn from(entry: ViaEntry) -> Self {
+        Via::new(entry)
+    }
+}
+
+impl FromIterator<ViaEntry> for Via {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = ViaEntry>,
+    {
+        Via(iter.into_iter().collect())
+    }
+}
+
+fn parse_entries(s: &str, entries: &mut Vec<ViaEntry>) -> Result<(), Error> {
+    let mut rest = s;
+    loop {
+        rest = rest.trim_start_matches(is_ows);
+        if rest.is_empty() {
+            return Ok(());
+        }
+        if let Some(after) = rest.strip_prefix(',') {
+            rest = after;
+            continue;
+        }
+
+        let (protocol, after) = split_at(rest, |c| c.is_ascii() && is_tchar(c as u8) || c == '/');
+        let (protocol_name, protocol_version) = match protocol.find('/') {
+            Some(idx) => (Some(&protocol[..idx]), &protocol[idx + 1..]),
+            None => (None, protocol),
+        };
+        if !protocol_name.map(is_token).unwrap_or(true) || !is_token(protocol_version) {
+            return Err(Error::invalid());
+        }
+
+        let after_rws = after.trim_start_matches(is_ows);
+        if after_rws.len() == after.len() {
+            return Err(Error::invalid());
+        }
+
+        let (received_by, after) =
+            split_at(after_rws, |c| c.is_ascii_graphic() && c != ',' && c != '(');
+        if !is_received_by(received_by) {
+            return Err(Error::invalid());
+        }
+
+        let after_ows = after.trim_start_matches(is_ows);
+        let comment = if after_ows.starts_with('(') && after_ows.len() != after.len() {
+            let len = comment_len(after_ows).ok_or_else(Error::invalid)?;
+            rest = &after_ows[len..];
+            Some(after_ows[1..len - 1].to_owned())
+        } else {
+            rest = after;
+            None
+        };
+
+        let next = rest.trim_start_matches(is_ows);
+        if !(next.is_empty() || next.starts_with(',')) {
+            return Err(Error::invalid());
+        }
+
+        entries.push(ViaEntry {
+            protocol_name: protocol_name.map(str::to_owned),
+            protocol_version: protocol_version.to_owned(),
+            received_by: received_by.to_owned(),
+            comment,
+        });
+    }
+}
+
+/// Split `s` after the longest prefix of characters matching `pred`.
+fn split_at<F: Fn(char) -> bool>(s: &str, pred: F) -> (&str, &str) {
+    let len = s.find(|c| !pred(c)).unwrap_or(s.len());
+    s.split_at(len)
+}
+
+/// Get the length of the comment at the start of `s`, including its
+/// parentheses. Comments can be nested, and contain quoted pairs.
+fn comment_len(s: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut escaped = false;
+    for (idx, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx + 1);
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// received-by = pseudonym [ ":" port ]
+///
+/// Older senders use a host instead of a pseudonym, which may be a
+/// bracketed IPv6 address.
+fn is_received_by(s: &str) -> bool {
+    let (name, port) = match s.rfind(':') {
+        Some(idx) if !s.ends_with(']') => (&s[..idx], Some(&s[idx + 1..])),
+        _ => (s, None),
+    };
+    let name_ok = is_token(name)
+        || name
+            .strip_prefix('[')
+            .and_then(|name| name.strip_suffix(']'))
+            .map(|addr| addr.parse::<Ipv6Addr>().is_ok())
+            .unwrap_or(false);
+    let port_ok = port
+        .map(|port| !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()))
+        .unwrap_or(true);
+    name_ok && port_ok
+}
+
+impl ViaEntry {
+    /// Create an entry for an intermediary named `received_by` that
+    /// received the message with the given HTTP version, such as `"1.1"`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the version is not a token, or if the name is not a
+    /// pseudonym or bracketed IPv6 address with an optional port.
+    pub fn new(protocol_version: &str, received_by: &str) -> Self {
+        assert!(is_token(protocol_version), "invalid protocol version");
+        assert!(is_received_by(received_by), "invalid received-by");
+        ViaEntry {
+            protocol_name: None,
+            protocol_version: protocol_version.to_owned(),
+            received_by: received_by.to_owned(),
+            comment: None,
+        }
+    }
+
+    /// Create an entry for an intermediary named `received_by` that
+    /// received the message with the given HTTP version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not a pseudonym with an optional port.
+    pub fn from_version(version: Version, received_by: &str) -> Self {
+        let version = match version {
+            Version::HTTP_09 => "0.9",
+            Version::HTTP_10 => "1.0",
+            Version::HTTP_2 => "2",
+            Version::HTTP_3 => "3",
+            _ => "1.1",
+        };
+        ViaEntry::new(version, received_by)
+    }
+
+    /// Set the protocol name, for protocols other than HTTP.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not a token.
+    pub fn with_protocol_name(mut self, name: &str) -> Self {
+        assert!(is_token(name), "invalid protocol name");
+        self.protocol_name = Some(name.to_owned());
+        self
+    }
+
+    /// Set the comment, such as the software of the intermediary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the comment contains parentheses, backslashes or
+    /// characters other than visible ASCII and whitespace.
+    pub fn with_comment(mut self, comment: &str) -> Self {
+        assert!(
+            comment
+                .bytes()
+                .all(|b| (b == b'\t' || (0x20..0x7f).contains(&b))
+                    && !matches!(b, b'(' | b')' | b'\\')),
+            "invalid comment"
+        );
+        self.comment = Some(comment.to_owned());
+        self
+    }
+
+    /// Get the name of the protocol the message was received with, which
+    /// defaults to `HTTP`.
+    pub fn protocol_name(&self) -> &str {
+        self.protocol_name.as_deref().unwrap_or("HTTP")
+    }
+
+    /// Get the version of the protocol the message was received with, such
+    /// as `1.1`.
+    pub fn protocol_version(&self) -> &str {
+        &self.protocol_version
+    }
+
+    /// Get the name of the intermediary, with its port if any.
+    pub fn received_by(&self) -> &str {
+        &self.received_by
+    }
+
+    /// Get the comment of this entry, without the parentheses.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+}
+
+impl fmt::Display for ViaEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref name) = self.protocol_name {
+            write!(f, "{}/", name)?;
+        }
+        write!(f, "{} {}", self.protocol_version, self.received_by)?;
+        if let Some(ref comment) = self.comment {
+            write!(f, " ({})", comment)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+    use crate::HeaderMapExt;
+
+    #[test]
+    fn decode() {
+        let via = test_decode::<Via>(&[
+            "1.0 fred, 1.1 p.example.net (Apache/1.1 (Unix), mod_x)",
+            "HTTP/2 edge:8080,,IRC/6.9 [::1]",
+        ])
+        .unwrap();
+        let entries = via.entries().collect::<Vec<_>>();
+        assert_eq!(entries.len(), 4);
+
+        assert_eq!(entries[0].protocol_name(), "HTTP");
+        assert_eq!(entries[0].protocol_version(), "1.0");
+        assert_eq!(entries[0].received_by(), "fred");
+        assert_eq!(entries[0].comment(), None);
+
+        assert_eq!(entries[1].received_by(), "p.example.net");
+        assert_eq!(entries[1].comment(), Some("Apache/1.1 (Unix), mod_x"));
+
+        assert_eq!(entries[2].protocol_version(), "2");
+        assert_eq!(entries[2].received_by(), "edge:8080");
+
+        assert_eq!(entries[3].protocol_name(), "IRC");
+        assert_eq!(entries[3].received_by(), "[::1]");
+    }
+
+    #[test]
+    fn decode_invalid() {
+        for s in &[
+            "",
+            "1.1",
+            "1.1edge",
+            "1.1 edge (unterminated",
+            "1.1 edge extra",
+            "/1.1 edge",
+            "1.1 edge:port",
+            "1.1 [edge]",
+        ] {
+            assert_eq!(test_decode::<Via>(&[s]), None, "{:?} should be invalid", s);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid received-by")]
+    fn new_invalid_received_by() {
+        let _ = ViaEntry::new("1.1", "[\n]");
+    }
+
+    #[test]
+    fn encode() {
+        let via = vec![
+            ViaEntry::new("1.0", "fred"),
+            ViaEntry::from_version(Version::HTTP_2, "edge:8080")
+                .with_protocol_name("HTTP")
+                .with_comment("cache v1"),
+        ]
+        .into_iter()
+        .collect::<Via>();
+        assert_eq!(
+            test_encode(via)["via"],
+            "1.0 fred, HTTP/2 edge:8080 (cache v1)"
+        );
+    }
+
+    #[test]
+    fn append_and_loops() {
+        let mut headers = HeaderMap::new();
+        headers.insert("via", "1.0 fred (a (b)), 1.1 Edge".parse().unwrap());
+
+        let via = headers.typed_get::<Via>().unwrap();
+        assert!(via.contains("edge"));
+        assert!(!via.contains("gateway"));
+        assert!(!via.has_loop());
+
+        Via::append(&mut headers, ViaEntry::new("1.1", "gateway"));
+        Via::append(&mut headers, ViaEntry::new("1.1", "edge"));
+        let values = headers.get_all("via").iter().collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            ["1.0 fred (a (b)), 1.1 Edge", "1.1 gateway", "1.1 edge"]
+        );
+
+        assert!(headers.typed_get::<Via>().unwrap().has_loop());
+    }
+
+    bench_header!(bench, Via, "1.0 fred, 1.1 p.example.net (Apache/1.1)");
+}
@@ -0,0 +1,251 @@
+use std::iter::FromIterator;
+
+use http::{HeaderName, HeaderValue};
+
+use super::authorization::{parse_challenges, Challenge};
+use crate::util;
+use crate::{Error, Header};
+
+/// `WWW-Authenticate` header, defined in
+/// [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-11.6.1)
+///
+/// The `WWW-Authenticate` header field indicates the authentication
+/// schemes and parameters applicable to the target resource. It is sent in
+/// 401 (Unauthorized) responses, with at least one challenge.
+///
+/// A single value can contain several challenges, and the parameters of a
+/// challenge are also separated by commas, so quoted strings may contain
+/// commas too. Each challenge is encoded as a separate field line.
+///
+/// # ABNF
+///
+/// ```text
+/// WWW-Authenticate = #challenge
+///
+/// challenge  = auth-scheme [ 1*SP ( token68 / #auth-param ) ]
+/// auth-param = token BWS "=" BWS ( token / quoted-string )
+/// ```
+///
+/// # Example values
+///
+/// * `Basic realm="api", charset="UTF-8"`
+/// * `Newauth realm="apps", type=1, title="Login to \"apps\"", Basic realm="simple"`
+/// * `Bearer realm="example", error="invalid_token"`
+///
+/// # Example
+///
+/// ```
+/// use headers::authorization::Challenge;
+/// use headers::WwwAuthenticate;
+///
+/// let www_auth = vec![
+///     Challenge::bearer().with_realm("api"),
+///     Challenge::basic("api").with_charset("UTF-8"),
+/// ]
+/// .into_iter()
+/// .collect::<WwwAuthenticate>();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WwwAuthenticate(Vec<Challenge>);
+
+impl Header for WwwAuthenticate {
+    fn name() -> &'static HeaderName {
+        &::http::header::WWW_AUTHENTICATE
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        decode_challenges(values).map(WwwAuthenticate)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(self.0.iter().map(util::fmt));
+    }
+}
+
+impl WwwAuthenticate {
+    /// Create a `WWW-Authenticate` header with a single challenge.
+    pub fn new(challenge: Challenge) -> Self {
+        WwwAuthenticate(vec![challenge])
+    }
+
+    /// Iterate the challenges of this header.
+    pub fn challenges(&self) -> impl Iterator<Item = &Challenge> {
+        self.0.iter()
+    }
+
+    /// Get the first challenge with the given authentication scheme.
+    ///
+    /// Schemes are compared case-insensitively.
+    pub fn find(&self, scheme: &str) -> Option<&Challenge> {
+        find_challenge(&self.0, scheme)
+    }
+
+    /// Add a challenge after the existing ones.
+    pub fn push(&mut self, challenge: Challenge) {
+        self.0.push(challenge);
+    }
+}
+
+impl From<Challenge> for WwwAuthenticate {
+    fn from(challenge: Challenge) -> Self {
+        WwwAuthenticate::new(challenge)
+    }
+}
+
+impl FromIterator<Challenge> for WwwAuthenticate {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Challenge>,
+    {
+        WwwAuthenticate(iter.into_iter().collect())
+    }
+}
+
+pub(super) fn decode_challenges<'i, I>(values: &mut I) -> Result<Vec<Challenge>, Error>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let mut challenges = Vec::new();
+    for value in values {
+        let value = value.to_str().map_err(|_| Error::invalid())?;
+        parse_challenges(value, &mut challenges).ok_or_else(Error::invalid)?;
+    }
+
+    if challenges.is_empty() {
+        Err(Error::invalid())
+    } else {
+        Ok(challenges)
+    }
+}
+
+pub(super) fn find_challenge<'a>(
+    challenges: &'a [Challenge],
+    scheme: &str,
+) -> Option<&'a Challenge> {
+    challenges
+        .iter()
+        .find(|challenge| challenge.scheme().eq_ignore_ascii_case(scheme))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn decode_several_challenges() {
+        let www_auth = test_decode::<WwwAuthenticate>(&[
+            r#"Newauth realm="apps", type=1, title="Login to \"apps\", now", Basic realm="simple""#,
+            "Negotiate a87421000492aa874209af8bc028==,, Bearer",
+        ])
+        .unwrap();
+        let challenges = www_auth.challenges().collect::<Vec<_>>();
+        assert_eq!(challenges.len(), 4);
+
+        assert_eq!(challenges[0].scheme(), "Newauth");
+        assert_eq!(
+            challenges[0].params().collect::<Vec<_>>(),
+            [
+                ("realm", "apps"),
+                ("type", "1"),
+                ("title", "Login to \"apps\", now")
+            ]
+        );
+        assert_eq!(challenges[1].realm(), Some("simple"));
+        assert_eq!(
+            challenges[2].token68(),
+            Some("a87421000492aa874209af8bc028==")
+        );
+        assert_eq!(challenges[3].scheme(), "Bearer");
+        assert_eq!(challenges[3].params().count(), 0);
+
+        assert_eq!(www_auth.find("basic"), Some(challenges[1]));
+        assert_eq!(www_auth.find("Digest"), None);
+    }
+
+    #[test]
+    fn decode_bws_and_case() {
+        let www_auth =
+            test_decode::<WwwAuthenticate>(&["basic REALM = \"x\" ,charset=UTF-8"]).unwrap();
+        let basic = www_auth.find("Basic").unwrap();
+        assert_eq!(basic.realm(), Some("x"));
+        assert_eq!(basic.param("Charset"), Some("UTF-8"));
+    }
+
+    #[test]
+    fn decode_invalid() {
+        for s in &[
+            "",
+            ", ,",
+            "=realm",
+            "Basic realm=\"a\"b",
+            "Basic realm=\"unterminated",
+            "Basic realm=\"a\" charset=b",
+            "Basic realm=a, realm=b",
+            "Basic abc== def",
+        ] {
+            assert_eq!(
+                test_decode::<WwwAuthenticate>(&[s]),
+                None,
+                "{:?} should be invalid",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn encode() {
+        let www_auth = vec![
+            Challenge::basic("api").with_charset("UTF-8"),
+            Challenge::bearer()
+                .with_realm("api")
+                .with_error("invalid_token")
+                .with_error_description("The \"token\" expired")
+                .with_scope(vec!["read", "write"]),
+            Challenge::new("Negotiate").with_token68("YII="),
+        ]
+        .into_iter()
+        .collect::<WwwAuthenticate>();
+
+        let headers = test_encode(www_auth.clone());
+        let values = headers
+            .get_all("www-authenticate")
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [
+                r#"Basic realm="api", charset="UTF-8""#,
+                r#"Bearer realm="api", error="invalid_token", error_description="The \"token\" expired", scope="read write""#,
+                "Negotiate YII=",
+            ]
+        );
+
+        let decoded = test_decode::<WwwAuthenticate>(
+            &values
+                .iter()
+                .map(|v| v.to_str().unwrap())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert_eq!(decoded, www_auth);
+    }
+
+    #[test]
+    fn with_param_replaces() {
+        let challenge = Challenge::basic("a").with_realm("b");
+        assert_eq!(challenge.to_string(), r#"Basic realm="b""#);
+    }
+
+    #[test]
+    #[should_panic(expected = "a challenge cannot have both a token68 and parameters")]
+    fn token68_and_params() {
+        let _ = Challenge::basic("api").with_token68("abc");
+    }
+
+    bench_header!(
+        bench,
+        WwwAuthenticate,
+        r#"Basic realm="api", charset="UTF-8""#
+    );
+}
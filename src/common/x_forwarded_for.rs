@@ -0,0 +1,153 @@
+use std::iter::FromIterator;
+use std::net::{IpAddr, SocketAddr};
+
+use http::{HeaderName, HeaderValue};
+
+use crate::util;
+use crate::{Error, Header};
+
+static X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// `X-Forwarded-For` header
+///
+/// The `X-Forwarded-For` header field is the de-facto standard predecessor
+/// of [`Forwarded`](super::Forwarded). It is a list of IP addresses, with
+/// the client first, and each proxy appending the address it received the
+/// request from.
+///
+/// Ports are accepted after an address, as some proxies send them, but
+/// they are not kept. A value that is not an IP address, such as
+/// `unknown`, makes the whole header invalid.
+///
+/// The values in this header are only as trustworthy as the proxies that
+/// added them. To find the address of the client, see
+/// [`TrustedProxies`](crate::proxy::TrustedProxies).
+///
+/// # Example values
+///
+/// * `203.0.113.195`
+/// * `203.0.113.195, 2001:db8:85a3:8d3:1319:8a2e:370:7348, 198.51.100.178`
+///
+/// # Example
+///
+/// ```
+/// use std::net::{IpAddr, Ipv4Addr};
+/// use headers::XForwardedFor;
+///
+/// let xff = vec![IpAddr::V4(Ipv4Addr::new(203, 0, 113, 195))]
+///     .into_iter()
+///     .collect::<XForwardedFor>();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XForwardedFor(Vec<IpAddr>);
+
+impl Header for XForwardedFor {
+    fn name() -> &'static HeaderName {
+        &X_FORWARDED_FOR
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let mut addrs = Vec::new();
+        for value in values {
+            let value = value.to_str().map_err(|_| Error::invalid())?;
+            for item in value.split(',').map(str::trim) {
+                if !item.is_empty() {
+                    addrs.push(parse_addr(item)?);
+                }
+            }
+        }
+
+        if addrs.is_empty() {
+            Err(Error::invalid())
+        } else {
+            Ok(XForwardedFor(addrs))
+        }
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let value = self
+            .0
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        values.extend(::std::iter::once(util::fmt(value)));
+    }
+}
+
+impl XForwardedFor {
+    /// Iterate the addresses of this header, from the client to the proxy
+    /// closest to the server.
+    pub fn iter(&self) -> impl Iterator<Item = IpAddr> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Append the address of another node.
+    pub fn push(&mut self, addr: IpAddr) {
+        self.0.push(addr);
+    }
+}
+
+impl FromIterator<IpAddr> for XForwardedFor {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = IpAddr>,
+    {
+        XForwardedFor(iter.into_iter().collect())
+    }
+}
+
+fn parse_addr(s: &str) -> Result<IpAddr, Error> {
+    if let Ok(ip) = s.parse::<IpAddr>() {
+        return Ok(ip);
+    }
+    if let Ok(addr) = s.parse::<SocketAddr>() {
+        return Ok(addr.ip());
+    }
+    s.strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .and_then(|s| s.parse().ok())
+        .map(IpAddr::V6)
+        .ok_or_else(Error::invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn decode() {
+        let xff = test_decode::<XForwardedFor>(&[
+            "203.0.113.195,2001:db8::1",
+            " 198.51.100.178:4711, [2001:db8::2]:80 , [2001:db8::3]",
+        ])
+        .unwrap();
+        let addrs = xff.iter().map(|ip| ip.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            addrs,
+            [
+                "203.0.113.195",
+                "2001:db8::1",
+                "198.51.100.178",
+                "2001:db8::2",
+                "2001:db8::3"
+            ]
+        );
+
+        assert_eq!(test_decode::<XForwardedFor>(&["1.2.3.4, unknown"]), None);
+        assert_eq!(test_decode::<XForwardedFor>(&[" , "]), None);
+    }
+
+    #[test]
+    fn encode() {
+        let mut xff = test_decode::<XForwardedFor>(&["203.0.113.195"]).unwrap();
+        xff.push("2001:db8::1".parse().unwrap());
+        assert_eq!(
+            test_encode(xff)["x-forwarded-for"],
+            "203.0.113.195, 2001:db8::1"
+        );
+    }
+
+    bench_header!(bench, XForwardedFor, "203.0.113.195, 198.51.100.178");
+}
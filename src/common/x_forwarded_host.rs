@@ -0,0 +1,106 @@
+use std::fmt;
+
+use http::uri::Authority;
+use http::{HeaderName, HeaderValue};
+
+use super::forwarded::parse_host;
+use crate::{Error, Header};
+
+static X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
+
+/// `X-Forwarded-Host` header
+///
+/// The `X-Forwarded-Host` header field is the de-facto standard
+/// predecessor of the `host` parameter of [`Forwarded`](super::Forwarded).
+/// It holds the `Host` header of the request the client made to a proxy.
+///
+/// Some proxies append their own value instead of replacing it. The last
+/// one is used, since it was added by the nearest proxy, while the earlier
+/// ones may have been sent by the client itself. It can only be relied on
+/// when the peer of the connection is a trusted proxy, as checked with
+/// [`TrustedProxies::is_trusted`](crate::proxy::TrustedProxies::is_trusted).
+///
+/// # Example values
+///
+/// * `example.com`
+/// * `example.com:8080`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct XForwardedHost(Authority);
+
+impl XForwardedHost {
+    /// Get the hostname, such as example.domain.
+    pub fn hostname(&self) -> &str {
+        self.0.host()
+    }
+
+    /// Get the optional port number.
+    pub fn port(&self) -> Option<u16> {
+        self.0.port_u16()
+    }
+}
+
+impl Header for XForwardedHost {
+    fn name() -> &'static HeaderName {
+        &X_FORWARDED_HOST
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let value = values.last().ok_or_else(Error::invalid)?;
+        let value = value.to_str().map_err(|_| Error::invalid())?;
+        let host = value.rsplit(',').next().unwrap_or("");
+        parse_host(host.trim()).map(XForwardedHost)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let bytes = self.0.as_str().as_bytes();
+        let val = HeaderValue::from_bytes(bytes).expect("Authority is a valid HeaderValue");
+
+        values.extend(::std::iter::once(val));
+    }
+}
+
+impl From<Authority> for XForwardedHost {
+    fn from(auth: Authority) -> XForwardedHost {
+        XForwardedHost(auth)
+    }
+}
+
+impl fmt::Display for XForwardedHost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn decode() {
+        let host = test_decode::<XForwardedHost>(&["example.com:8080"]).unwrap();
+        assert_eq!(host.hostname(), "example.com");
+        assert_eq!(host.port(), Some(8080));
+
+        // The nearest proxy appended the last value.
+        let host = test_decode::<XForwardedHost>(&["spoofed.example, proxy.local"]).unwrap();
+        assert_eq!(host.hostname(), "proxy.local");
+
+        // A last value that isn't ASCII doesn't fall back to an earlier one.
+        assert_eq!(
+            test_decode::<XForwardedHost>(&["spoofed.example", "caf\u{e9}.example"]),
+            None
+        );
+
+        assert_eq!(test_decode::<XForwardedHost>(&[""]), None);
+        assert_eq!(test_decode::<XForwardedHost>(&["user@example.com"]), None);
+    }
+
+    #[test]
+    fn encode() {
+        let host = XForwardedHost::from(Authority::from_static("example.com"));
+        assert_eq!(test_encode(host)["x-forwarded-host"], "example.com");
+    }
+
+    bench_header!(bench, XForwardedHost, "example.com:8080");
+}
@@ -0,0 +1,115 @@
+use http::{HeaderName, HeaderValue};
+
+use crate::util::{self, is_scheme};
+use crate::{Error, Header};
+
+static X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+
+/// `X-Forwarded-Proto` header
+///
+/// The `X-Forwarded-Proto` header field is the de-facto standard
+/// predecessor of the `proto` parameter of [`Forwarded`](super::Forwarded).
+/// It holds the scheme of the request the client made to a proxy, such as
+/// `https`.
+///
+/// Some proxies append their own scheme instead of replacing the value. The
+/// last one is used, since it was added by the nearest proxy, while the
+/// earlier ones may have been sent by the client itself. Like the address
+/// in `X-Forwarded-For`, it can only be relied on when the peer of the
+/// connection is a trusted proxy, as checked with
+/// [`TrustedProxies::is_trusted`](crate::proxy::TrustedProxies::is_trusted).
+///
+/// # Example values
+///
+/// * `https`
+///
+/// # Example
+///
+/// ```
+/// use headers::XForwardedProto;
+///
+/// let proto = XForwardedProto::new("https");
+/// assert!(proto.is_https());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct XForwardedProto(String);
+
+impl Header for XForwardedProto {
+    fn name() -> &'static HeaderName {
+        &X_FORWARDED_PROTO
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        let value = values.last().ok_or_else(Error::invalid)?;
+        let value = value.to_str().map_err(|_| Error::invalid())?;
+        value
+            .rsplit(',')
+            .next()
+            .map(str::trim)
+            .filter(|proto| is_scheme(proto))
+            .map(|proto| XForwardedProto(proto.to_ascii_lowercase()))
+            .ok_or_else(Error::invalid)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(::std::iter::once(util::fmt(&self.0)));
+    }
+}
+
+impl XForwardedProto {
+    /// Create an `X-Forwarded-Proto` header with the given scheme.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scheme is not valid.
+    pub fn new(proto: &str) -> Self {
+        assert!(is_scheme(proto), "invalid proto");
+        XForwardedProto(proto.to_ascii_lowercase())
+    }
+
+    /// Get the scheme, in lowercase.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Check if the scheme is `https`.
+    pub fn is_https(&self) -> bool {
+        self.0 == "https"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{test_decode, test_encode};
+    use super::*;
+
+    #[test]
+    fn decode() {
+        let proto = test_decode::<XForwardedProto>(&["HTTPS"]).unwrap();
+        assert_eq!(proto.as_str(), "https");
+        assert!(proto.is_https());
+
+        // The nearest proxy appended the last value.
+        let proto = test_decode::<XForwardedProto>(&["https, http"]).unwrap();
+        assert_eq!(proto.as_str(), "http");
+        let proto = test_decode::<XForwardedProto>(&["http", "https"]).unwrap();
+        assert_eq!(proto.as_str(), "https");
+
+        // A last value that isn't ASCII doesn't fall back to an earlier one.
+        assert_eq!(
+            test_decode::<XForwardedProto>(&["https", "caf\u{e9}"]),
+            None
+        );
+
+        assert_eq!(test_decode::<XForwardedProto>(&[""]), None);
+        assert_eq!(test_decode::<XForwardedProto>(&["1http"]), None);
+    }
+
+    #[test]
+    fn encode() {
+        let headers = test_encode(XForwardedProto::new("http"));
+        assert_eq!(headers["x-forwarded-proto"], "http");
+    }
+
+    bench_header!(bench, XForwardedProto, "https");
+}
@@ -0,0 +1,359 @@
+//! Evaluation of conditional requests.
+//!
+//! A request may carry several preconditions at once. [RFC 9110 section
+//! 13.2.2](https://www.rfc-editor.org/rfc/rfc9110#section-13.2.2) defines
+//! the order in which they are evaluated, and which of them are ignored
+//! when another one is present. [`Preconditions`] applies that order.
+//!
+//! # Example
+//!
+//! ```
+//! use headers::conditional::{Outcome, Preconditions};
+//! use headers::{ETag, HeaderMap, HeaderMapExt, IfNoneMatch};
+//! use http::Method;
+//!
+//! let etag = "\"xyzzy\"".parse::<ETag>().unwrap();
+//!
+//! let mut req = HeaderMap::new();
+//! req.typed_insert(IfNoneMatch::from(etag.clone()));
+//!
+//! let preconditions = Preconditions::from_request(&Method::GET, &req);
+//! assert_eq!(preconditions.evaluate(Some(&etag), None), Outcome::NotModified);
+//! ```
+
+use std::time::SystemTime;
+
+use http::{HeaderMap, Method};
+
+use crate::{
+    ETag, HeaderMapExt, IfMatch, IfModifiedSince, IfNoneMatch, IfRange, IfUnmodifiedSince,
+    LastModified, Range,
+};
+
+/// The preconditions of a request.
+///
+/// Build it from the request headers with
+/// [`from_request`](Preconditions::from_request), or set each header
+/// yourself, and then evaluate it against the current state of the target
+/// resource.
+#[derive(Clone, Debug)]
+pub struct Preconditions {
+    method: Method,
+    if_match: Option<IfMatch>,
+    if_none_match: Option<IfNoneMatch>,
+    if_modified_since: Option<IfModifiedSince>,
+    if_unmodified_since: Option<IfUnmodifiedSince>,
+    if_range: Option<IfRange>,
+}
+
+/// The result of evaluating the [`Preconditions`] of a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// All preconditions passed, perform the requested method.
+    Proceed,
+    /// Perform the requested method, but ignore the `Range` header and send
+    /// the full representation, as `If-Range` did not match.
+    IgnoreRange,
+    /// Respond with `304 Not Modified`.
+    NotModified,
+    /// Respond with `412 Precondition Failed`.
+    PreconditionFailed,
+}
+
+impl Preconditions {
+    /// Create preconditions for a request with the given method, and without
+    /// any conditional headers.
+    pub fn new(method: Method) -> Preconditions {
+        Preconditions {
+            method,
+            if_match: None,
+            if_none_match: None,
+            if_modified_since: None,
+            if_unmodified_since: None,
+            if_range: None,
+        }
+    }
+
+    /// Read the preconditions of a request from its headers.
+    ///
+    /// Headers that fail to parse are ignored, as required by the
+    /// specification. `If-Range` is only kept if the request also has a
+    /// `Range` header.
+    pub fn from_request(method: &Method, headers: &HeaderMap) -> Preconditions {
+        let if_range = if headers.typed_get::<Range>().is_some() {
+            headers.typed_get()
+        } else {
+            None
+        };
+
+        Preconditions {
+            method: method.clone(),
+            if_match: headers.typed_get(),
+            if_none_match: headers.typed_get(),
+            if_modified_since: headers.typed_get(),
+            if_unmodified_since: headers.typed_get(),
+            if_range,
+        }
+    }
+
+    /// Set the `If-Match` header.
+    pub fn if_match(mut self, if_match: IfMatch) -> Self {
+        self.if_match = Some(if_match);
+        self
+    }
+
+    /// Set the `If-None-Match` header.
+    pub fn if_none_match(mut self, if_none_match: IfNoneMatch) -> Self {
+        self.if_none_match = Some(if_none_match);
+        self
+    }
+
+    /// Set the `If-Modified-Since` header.
+    pub fn if_modified_since(mut self, if_modified_since: IfModifiedSince) -> Self {
+        self.if_modified_since = Some(if_modified_since);
+        self
+    }
+
+    /// Set the `If-Unmodified-Since` header.
+    pub fn if_unmodified_since(mut self, if_unmodified_since: IfUnmodifiedSince) -> Self {
+        self.if_unmodified_since = Some(if_unmodified_since);
+        self
+    }
+
+    /// Set the `If-Range` header, for a request that also has a `Range`
+    /// header.
+    pub fn if_range(mut self, if_range: IfRange) -> Self {
+        self.if_range = Some(if_range);
+        self
+    }
+
+    /// Evaluate the preconditions against a resource that has a current
+    /// representation, with the given validators.
+    pub fn evaluate(&self, etag: Option<&ETag>, last_modified: Option<&LastModified>) -> Outcome {
+        let is_get_or_head = self.method == Method::GET || self.method == Method::HEAD;
+        let last_modified_time = last_modified.map(|time| SystemTime::from(*time));
+
+        // Step 1 and 2: `If-Match`, else `If-Unmodified-Since`.
+        if let Some(ref if_match) = self.if_match {
+            let passes = match etag {
+                Some(etag) => if_match.precondition_passes(etag),
+                None => if_match.is_any(),
+            };
+            if !passes {
+                return Outcome::PreconditionFailed;
+            }
+        } else if let (Some(since), Some(time)) = (self.if_unmodified_since, last_modified_time) {
+            if !since.precondition_passes(time) {
+                return Outcome::PreconditionFailed;
+            }
+        }
+
+        // Step 3 and 4: `If-None-Match`, else `If-Modified-Since`.
+        if let Some(ref if_none_match) = self.if_none_match {
+            let passes = match etag {
+                Some(etag) => if_none_match.precondition_passes(etag),
+                None => !if_none_match.is_any(),
+            };
+            if !passes {
+                return if is_get_or_head {
+                    Outcome::NotModified
+                } else {
+                    Outcome::PreconditionFailed
+                };
+            }
+        } else if let (true, Some(since), Some(time)) =
+            (is_get_or_head, self.if_modified_since, last_modified_time)
+        {
+            if !since.is_modified(time) {
+                return Outcome::NotModified;
+            }
+        }
+
+        // Step 5: `If-Range`, only for `GET`.
+        if let Some(ref if_range) = self.if_range {
+            if self.method == Method::GET && if_range.is_modified(etag, last_modified) {
+                return Outcome::IgnoreRange;
+            }
+        }
+
+        Outcome::Proceed
+    }
+
+    /// Evaluate the preconditions against a resource that has no current
+    /// representation, such as a `PUT` that would create it.
+    ///
+    /// `If-Match` always fails, and `If-None-Match` always passes.
+    pub fn evaluate_missing(&self) -> Outcome {
+        if self.if_match.is_some() {
+            Outcome::PreconditionFailed
+        } else {
+            Outcome::Proceed
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn etag(s: &str) -> ETag {
+        s.parse().unwrap()
+    }
+
+    fn time(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn no_preconditions() {
+        let pre = Preconditions::new(Method::GET);
+        assert_eq!(pre.evaluate(None, None), Outcome::Proceed);
+        assert_eq!(pre.evaluate_missing(), Outcome::Proceed);
+    }
+
+    #[test]
+    fn if_match() {
+        let current = etag("\"a\"");
+        let pre = Preconditions::new(Method::PUT).if_match(IfMatch::from(etag("\"a\"")));
+        assert_eq!(pre.evaluate(Some(&current), None), Outcome::Proceed);
+
+        // `If-Match` uses the strong comparison.
+        let weak = etag("W/\"a\"");
+        assert_eq!(pre.evaluate(Some(&weak), None), Outcome::PreconditionFailed);
+
+        let pre = Preconditions::new(Method::PUT).if_match(IfMatch::from(etag("\"b\"")));
+        assert_eq!(
+            pre.evaluate(Some(&current), None),
+            Outcome::PreconditionFailed
+        );
+        assert_eq!(pre.evaluate(None, None), Outcome::PreconditionFailed);
+    }
+
+    #[test]
+    fn if_match_any() {
+        let pre = Preconditions::new(Method::PUT).if_match(IfMatch::any());
+        assert_eq!(pre.evaluate(None, None), Outcome::Proceed);
+        assert_eq!(pre.evaluate_missing(), Outcome::PreconditionFailed);
+    }
+
+    #[test]
+    fn if_unmodified_since() {
+        let modified = LastModified::from(time(100));
+        let pre = Preconditions::new(Method::DELETE)
+            .if_unmodified_since(IfUnmodifiedSince::from(time(100)));
+        assert_eq!(pre.evaluate(None, Some(&modified)), Outcome::Proceed);
+
+        let modified = LastModified::from(time(200));
+        assert_eq!(
+            pre.evaluate(None, Some(&modified)),
+            Outcome::PreconditionFailed
+        );
+
+        // Ignored without a modification date.
+        assert_eq!(pre.evaluate(None, None), Outcome::Proceed);
+    }
+
+    #[test]
+    fn if_match_takes_precedence_over_if_unmodified_since() {
+        let current = etag("\"a\"");
+        let modified = LastModified::from(time(200));
+        let pre = Preconditions::new(Method::PUT)
+            .if_match(IfMatch::from(current.clone()))
+            .if_unmodified_since(IfUnmodifiedSince::from(time(100)));
+        assert_eq!(
+            pre.evaluate(Some(&current), Some(&modified)),
+            Outcome::Proceed
+        );
+    }
+
+    #[test]
+    fn if_none_match() {
+        let current = etag("\"a\"");
+        let pre = Preconditions::new(Method::GET).if_none_match(IfNoneMatch::from(etag("W/\"a\"")));
+        assert_eq!(pre.evaluate(Some(&current), None), Outcome::NotModified);
+
+        let pre = Preconditions::new(Method::HEAD).if_none_match(IfNoneMatch::from(etag("\"a\"")));
+        assert_eq!(pre.evaluate(Some(&current), None), Outcome::NotModified);
+
+        let pre = Preconditions::new(Method::POST).if_none_match(IfNoneMatch::from(etag("\"a\"")));
+        assert_eq!(
+            pre.evaluate(Some(&current), None),
+            Outcome::PreconditionFailed
+        );
+
+        let pre = Preconditions::new(Method::GET).if_none_match(IfNoneMatch::from(etag("\"b\"")));
+        assert_eq!(pre.evaluate(Some(&current), None), Outcome::Proceed);
+    }
+
+    #[test]
+    fn if_none_match_any() {
+        let pre = Preconditions::new(Method::PUT).if_none_match(IfNoneMatch::any());
+        assert_eq!(pre.evaluate(None, None), Outcome::PreconditionFailed);
+        assert_eq!(pre.evaluate_missing(), Outcome::Proceed);
+    }
+
+    #[test]
+    fn if_modified_since() {
+        let modified = LastModified::from(time(100));
+        let pre =
+            Preconditions::new(Method::GET).if_modified_since(IfModifiedSince::from(time(100)));
+        assert_eq!(pre.evaluate(None, Some(&modified)), Outcome::NotModified);
+
+        let modified = LastModified::from(time(200));
+        assert_eq!(pre.evaluate(None, Some(&modified)), Outcome::Proceed);
+
+        // Only for `GET` and `HEAD`.
+        let modified = LastModified::from(time(100));
+        let pre =
+            Preconditions::new(Method::POST).if_modified_since(IfModifiedSince::from(time(100)));
+        assert_eq!(pre.evaluate(None, Some(&modified)), Outcome::Proceed);
+    }
+
+    #[test]
+    fn if_none_match_takes_precedence_over_if_modified_since() {
+        let current = etag("\"a\"");
+        let modified = LastModified::from(time(100));
+        let pre = Preconditions::new(Method::GET)
+            .if_none_match(IfNoneMatch::from(etag("\"b\"")))
+            .if_modified_since(IfModifiedSince::from(time(100)));
+        assert_eq!(
+            pre.evaluate(Some(&current), Some(&modified)),
+            Outcome::Proceed
+        );
+    }
+
+    #[test]
+    fn if_range() {
+        let current = etag("\"a\"");
+        let pre = Preconditions::new(Method::GET).if_range(IfRange::etag(current.clone()));
+        assert_eq!(pre.evaluate(Some(&current), None), Outcome::Proceed);
+
+        let other = etag("\"b\"");
+        assert_eq!(pre.evaluate(Some(&other), None), Outcome::IgnoreRange);
+
+        // Only for `GET`.
+        let pre = Preconditions::new(Method::HEAD).if_range(IfRange::etag(current));
+        assert_eq!(pre.evaluate(Some(&other), None), Outcome::Proceed);
+    }
+
+    #[test]
+    fn from_request() {
+        let mut headers = HeaderMap::new();
+        headers.insert("if-none-match", "\"a\"".parse().unwrap());
+        headers.insert("if-range", "\"b\"".parse().unwrap());
+        let current = etag("\"a\"");
+
+        let pre = Preconditions::from_request(&Method::GET, &headers);
+        assert_eq!(pre.evaluate(Some(&current), None), Outcome::NotModified);
+
+        // `If-Range` is ignored without `Range`.
+        let current = etag("\"c\"");
+        assert_eq!(pre.evaluate(Some(&current), None), Outcome::Proceed);
+
+        headers.insert("range", "bytes=0-10".parse().unwrap());
+        let pre = Preconditions::from_request(&Method::GET, &headers);
+        assert_eq!(pre.evaluate(Some(&current), None), Outcome::IgnoreRange);
+    }
+}
@@ -0,0 +1,379 @@
+//! Freshness of stored responses.
+//!
+//! [`Freshness`] combines the `Cache-Control`, `Age`, `Date`, `Expires`
+//! and `Last-Modified` headers of a stored response to compute its
+//! freshness lifetime and current age, as defined in [RFC 9111 section
+//! 4.2](https://www.rfc-editor.org/rfc/rfc9111#section-4.2), and decides
+//! whether it can be used to satisfy a request.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::{Duration, SystemTime};
+//!
+//! use headers::freshness::{Freshness, Status};
+//! use headers::{CacheControl, Date, HeaderMap, HeaderMapExt};
+//!
+//! let response_time = SystemTime::now();
+//! let request_time = response_time - Duration::from_secs(1);
+//!
+//! let mut res = HeaderMap::new();
+//! res.typed_insert(Date::from(response_time));
+//! res.typed_insert(CacheControl::new().with_max_age(Duration::from_secs(60)));
+//!
+//! let freshness = Freshness::from_response(&res, request_time, response_time);
+//! assert_eq!(freshness.lifetime(), Duration::from_secs(60));
+//!
+//! let now = response_time + Duration::from_secs(30);
+//! assert_eq!(freshness.status(now, None), Status::Fresh);
+//! ```
+
+use std::time::{Duration, SystemTime};
+
+use http::HeaderMap;
+
+use crate::{Age, CacheControl, Date, Expires, HeaderMapExt, LastModified};
+
+/// The fraction of the time since `Last-Modified` used as heuristic
+/// freshness lifetime.
+const HEURISTIC_FRACTION: u32 = 10;
+
+/// The freshness information of a stored response.
+#[derive(Clone, Debug)]
+pub struct Freshness {
+    cache_control: CacheControl,
+    date: Option<SystemTime>,
+    expires: Option<Option<SystemTime>>,
+    last_modified: Option<SystemTime>,
+    age: Option<Duration>,
+    request_time: SystemTime,
+    response_time: SystemTime,
+    shared: bool,
+}
+
+/// Whether a stored response can be used to satisfy a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The response is fresh, and can be used without contacting the origin.
+    Fresh,
+    /// The response is stale, but the request allows it to be used anyway.
+    StaleServable,
+    /// The response must be validated with the origin before it is used.
+    MustRevalidate,
+}
+
+impl Freshness {
+    /// Read the freshness information of a response from its headers.
+    ///
+    /// `request_time` is the time the request that produced this response
+    /// was sent, and `response_time` is the time the response was received.
+    pub fn from_response(
+        headers: &HeaderMap,
+        request_time: SystemTime,
+        response_time: SystemTime,
+    ) -> Freshness {
+        // An invalid `Expires` date, such as `0`, represents a time in the
+        // past.
+        let expires = if headers.contains_key(http::header::EXPIRES) {
+            Some(headers.typed_get::<Expires>().map(SystemTime::from))
+        } else {
+            None
+        };
+
+        Freshness {
+            cache_control: headers.typed_get().unwrap_or_else(CacheControl::new),
+            date: headers.typed_get::<Date>().map(SystemTime::from),
+            expires,
+            last_modified: headers.typed_get::<LastModified>().map(SystemTime::from),
+            age: headers.typed_get::<Age>().map(Duration::from),
+            request_time,
+            response_time,
+            shared: false,
+        }
+    }
+
+    /// Set whether the response is stored by a shared cache, which obeys
+    /// `s-maxage`.
+    ///
+    /// Defaults to `false`, for a private cache.
+    pub fn shared(mut self, shared: bool) -> Self {
+        self.shared = shared;
+        self
+    }
+
+    /// The freshness lifetime of the response.
+    ///
+    /// Without an explicit expiration time, a heuristic lifetime of 10% of
+    /// the time since `Last-Modified` is used. It is zero if neither is
+    /// available.
+    pub fn lifetime(&self) -> Duration {
+        self.explicit_lifetime()
+            .or_else(|| self.heuristic_lifetime())
+            .unwrap_or_default()
+    }
+
+    /// Returns whether the freshness lifetime is heuristic, because the
+    /// response has no explicit expiration time.
+    ///
+    /// Caches should only use a heuristic lifetime for responses with a
+    /// status code that is heuristically cacheable, or that are marked
+    /// `public`.
+    pub fn is_heuristic(&self) -> bool {
+        self.explicit_lifetime().is_none() && self.heuristic_lifetime().is_some()
+    }
+
+    /// The current age of the response at `now`.
+    ///
+    /// This is calculated as in [RFC 9111 section
+    /// 4.2.3](https://www.rfc-editor.org/rfc/rfc9111#section-4.2.3).
+    pub fn current_age(&self, now: SystemTime) -> Duration {
+        let date = self.date.unwrap_or(self.response_time);
+        let apparent_age = since(self.response_time, date);
+        let response_delay = since(self.response_time, self.request_time);
+        let corrected_age_value = self.age.unwrap_or_default() + response_delay;
+        let corrected_initial_age = apparent_age.max(corrected_age_value);
+        let resident_time = since(now, self.response_time);
+        corrected_initial_age + resident_time
+    }
+
+    /// Returns whether the response is fresh at `now`.
+    pub fn is_fresh(&self, now: SystemTime) -> bool {
+        self.lifetime() > self.current_age(now)
+    }
+
+    /// Decide whether the response can be used at `now` to satisfy a request
+    /// with the given `Cache-Control` directives.
+    ///
+    /// The request's `max-age`, `min-fresh` and `max-stale` directives
+    /// tighten or relax the freshness requirement, while `no-cache` on
+    /// either the request or the response always requires validation. A
+    /// `max-stale` without a value accepts a stale response of any age.
+    /// Stale responses with `must-revalidate`, or with `proxy-revalidate` or
+    /// `s-maxage` in a shared cache, are never served.
+    ///
+    /// A response `no-cache` listing header fields, such as
+    /// `no-cache="Set-Cookie"`, doesn't require validation. Instead, the
+    /// caller must remove the fields listed in
+    /// [`CacheControl::no_cache_fields`] before using the stored response
+    /// without validating it.
+    pub fn status(&self, now: SystemTime, request: Option<&CacheControl>) -> Status {
+        let no_cache = request.map(CacheControl::no_cache).unwrap_or(false);
+        if no_cache || self.cache_control.no_cache() {
+            return Status::MustRevalidate;
+        }
+
+        let lifetime = self.lifetime();
+        let age = self.current_age(now);
+
+        if let Some(max_age) = request.and_then(CacheControl::max_age) {
+            if age > max_age {
+                return Status::MustRevalidate;
+            }
+        }
+
+        let min_fresh = request
+            .and_then(CacheControl::min_fresh)
+            .unwrap_or_default();
+        if lifetime > age + min_fresh {
+            return Status::Fresh;
+        }
+
+        let must_revalidate = self.cache_control.must_revalidate()
+            || (self.shared
+                && (self.cache_control.proxy_revalidate()
+                    || self.cache_control.s_max_age().is_some()));
+        if must_revalidate {
+            return Status::MustRevalidate;
+        }
+
+        if request.map(CacheControl::any_stale).unwrap_or(false) {
+            return Status::StaleServable;
+        }
+
+        match request.and_then(CacheControl::max_stale) {
+            Some(max_stale) if age.checked_sub(lifetime).unwrap_or_default() <= max_stale => {
+                Status::StaleServable
+            }
+            _ => Status::MustRevalidate,
+        }
+    }
+
+    fn explicit_lifetime(&self) -> Option<Duration> {
+        if self.shared {
+            if let Some(s_max_age) = self.cache_control.s_max_age() {
+                return Some(s_max_age);
+            }
+        }
+
+        if let Some(max_age) = self.cache_control.max_age() {
+            return Some(max_age);
+        }
+
+        self.expires.map(|expires| match expires {
+            Some(expires) => since(expires, self.date.unwrap_or(self.response_time)),
+            None => Duration::ZERO,
+        })
+    }
+
+    fn heuristic_lifetime(&self) -> Option<Duration> {
+        let last_modified = self.last_modified?;
+        let date = self.date.unwrap_or(self.response_time);
+        Some(since(date, last_modified) / HEURISTIC_FRACTION)
+    }
+}
+
+/// The time elapsed from `earlier` to `later`, or zero if `earlier` is later.
+fn since(later: SystemTime, earlier: SystemTime) -> Duration {
+    later.duration_since(earlier).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    fn freshness(headers: &[(&str, &str)]) -> Freshness {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(
+                name.parse::<http::HeaderName>().unwrap(),
+                value.parse().unwrap(),
+            );
+        }
+        Freshness::from_response(&map, time(1_000_000), time(1_000_000))
+    }
+
+    const DATE: &str = "Mon, 12 Jan 1970 13:46:40 GMT";
+
+    #[test]
+    fn lifetime_max_age() {
+        let fresh = freshness(&[("cache-control", "max-age=60, s-maxage=120")]);
+        assert_eq!(fresh.lifetime(), secs(60));
+        assert!(!fresh.is_heuristic());
+        assert_eq!(fresh.shared(true).lifetime(), secs(120));
+    }
+
+    #[test]
+    fn lifetime_expires() {
+        let fresh = freshness(&[
+            ("date", DATE),
+            ("expires", "Mon, 12 Jan 1970 13:47:40 GMT"),
+            ("cache-control", "public"),
+        ]);
+        assert_eq!(fresh.lifetime(), secs(60));
+
+        // `max-age` takes precedence over `Expires`.
+        let fresh = freshness(&[
+            ("date", DATE),
+            ("expires", "Mon, 12 Jan 1970 13:47:40 GMT"),
+            ("cache-control", "max-age=10"),
+        ]);
+        assert_eq!(fresh.lifetime(), secs(10));
+
+        let fresh = freshness(&[("date", DATE), ("expires", "0")]);
+        assert_eq!(fresh.lifetime(), secs(0));
+        assert!(!fresh.is_heuristic());
+    }
+
+    #[test]
+    fn lifetime_heuristic() {
+        let fresh = freshness(&[
+            ("date", DATE),
+            ("last-modified", "Mon, 12 Jan 1970 13:30:00 GMT"),
+        ]);
+        assert_eq!(fresh.lifetime(), secs(100));
+        assert!(fresh.is_heuristic());
+
+        let fresh = freshness(&[("date", DATE)]);
+        assert_eq!(fresh.lifetime(), secs(0));
+        assert!(!fresh.is_heuristic());
+    }
+
+    #[test]
+    fn current_age() {
+        let mut map = HeaderMap::new();
+        map.insert("date", DATE.parse().unwrap());
+        map.insert("age", "10".parse().unwrap());
+
+        // The response took 2 seconds to arrive.
+        let fresh = Freshness::from_response(&map, time(999_998), time(1_000_000));
+        assert_eq!(fresh.current_age(time(1_000_000)), secs(12));
+        assert_eq!(fresh.current_age(time(1_000_030)), secs(42));
+
+        // The apparent age is used if it is larger, due to clock skew.
+        let fresh = Freshness::from_response(&map, time(1_000_050), time(1_000_050));
+        assert_eq!(fresh.current_age(time(1_000_050)), secs(50));
+    }
+
+    #[test]
+    fn status_fresh_and_stale() {
+        let fresh = freshness(&[("date", DATE), ("cache-control", "max-age=60")]);
+        assert!(fresh.is_fresh(time(1_000_059)));
+        assert_eq!(fresh.status(time(1_000_059), None), Status::Fresh);
+        assert!(!fresh.is_fresh(time(1_000_060)));
+        assert_eq!(fresh.status(time(1_000_060), None), Status::MustRevalidate);
+    }
+
+    #[test]
+    fn status_request_directives() {
+        let fresh = freshness(&[("date", DATE), ("cache-control", "max-age=60")]);
+        let now = time(1_000_030);
+
+        let max_age = CacheControl::new().with_max_age(secs(10));
+        assert_eq!(fresh.status(now, Some(&max_age)), Status::MustRevalidate);
+
+        let min_fresh = CacheControl::new().with_min_fresh(secs(40));
+        assert_eq!(fresh.status(now, Some(&min_fresh)), Status::MustRevalidate);
+
+        let min_fresh = CacheControl::new().with_min_fresh(secs(20));
+        assert_eq!(fresh.status(now, Some(&min_fresh)), Status::Fresh);
+
+        let no_cache = CacheControl::new().with_no_cache();
+        assert_eq!(fresh.status(now, Some(&no_cache)), Status::MustRevalidate);
+
+        let max_stale = CacheControl::new().with_max_stale(secs(30));
+        let now = time(1_000_090);
+        assert_eq!(fresh.status(now, Some(&max_stale)), Status::StaleServable);
+        let now = time(1_000_091);
+        assert_eq!(fresh.status(now, Some(&max_stale)), Status::MustRevalidate);
+
+        let any_stale = CacheControl::new().with_any_stale();
+        let now = time(2_000_000);
+        assert_eq!(fresh.status(now, Some(&any_stale)), Status::StaleServable);
+    }
+
+    #[test]
+    fn status_response_directives() {
+        let max_stale = CacheControl::new().with_max_stale(secs(30));
+        let now = time(1_000_070);
+
+        let fresh = freshness(&[
+            ("date", DATE),
+            ("cache-control", "max-age=60, must-revalidate"),
+        ]);
+        assert_eq!(fresh.status(now, Some(&max_stale)), Status::MustRevalidate);
+
+        let fresh = freshness(&[
+            ("date", DATE),
+            ("cache-control", "max-age=60, proxy-revalidate"),
+        ]);
+        assert_eq!(fresh.status(now, Some(&max_stale)), Status::StaleServable);
+        let fresh = fresh.shared(true);
+        assert_eq!(fresh.status(now, Some(&max_stale)), Status::MustRevalidate);
+
+        let fresh = freshness(&[("date", DATE), ("cache-control", "max-age=60, no-cache")]);
+        assert_eq!(fresh.status(time(1_000_000), None), Status::MustRevalidate);
+
+        let fresh = freshness(&[("date", DATE), ("cache-control", "max-age=60, s-maxage=60")]);
+        assert_eq!(fresh.status(now, Some(&max_stale)), Status::StaleServable);
+        let fresh = fresh.shared(true);
+        assert_eq!(fresh.status(now, Some(&max_stale)), Status::MustRevalidate);
+    }
+}
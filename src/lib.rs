@@ -86,8 +86,17 @@ pub use http::header::{HeaderName, HeaderValue};
 
 #[macro_use]
 mod util;
+pub mod byte_ranges;
 mod common;
+pub mod conditional;
+pub mod freshness;
 mod map_ext;
+pub mod proxy;
+pub mod storage;
+pub mod structured;
 
 pub use self::common::*;
 pub use self::map_ext::HeaderMapExt;
+pub use self::util::{
+    Charset, Encoding, ExtendedValue, LanguageRange, LanguageTag, Quality, QualityValue,
+};
@@ -0,0 +1,424 @@
+//! Helpers for proxies and gateways.
+//!
+//! # Hop-by-hop headers
+//!
+//! Some headers only apply to a single connection, and must not be
+//! forwarded. [`remove_hop_by_hop_headers`] removes them from a message
+//! before it is sent to the next hop.
+//!
+//! # Client addresses
+//!
+//! Each proxy records the address it received a request from in the
+//! `Forwarded` or `X-Forwarded-For` header. Clients can send these headers
+//! too, so only the addresses recorded by trusted proxies can be relied
+//! on. [`TrustedProxies`] walks the recorded addresses from the right,
+//! starting at the peer address of the connection, and stops at the first
+//! address that isn't a trusted proxy: that is the client.
+//!
+//! # Example
+//!
+//! ```
+//! use std::net::IpAddr;
+//!
+//! use headers::proxy::{ForwardedHeader, TrustedProxies};
+//! use headers::HeaderMap;
+//!
+//! let proxies = TrustedProxies::new()
+//!     .with_network("10.0.0.0".parse().unwrap(), 8)
+//!     .with_header(ForwardedHeader::XForwardedFor);
+//!
+//! let mut req = HeaderMap::new();
+//! req.insert("x-forwarded-for", "1.2.3.4, 203.0.113.7, 10.1.2.3".parse().unwrap());
+//!
+//! let peer: IpAddr = "10.0.0.1".parse().unwrap();
+//! let client = proxies.client_addr(peer, &req);
+//! assert_eq!(client, "203.0.113.7".parse::<IpAddr>().unwrap());
+//! ```
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use http::header::{self, HeaderName};
+use http::{HeaderMap, HeaderValue};
+
+use crate::{Connection, Forwarded, HeaderMapExt, Te, XForwardedFor};
+
+/// The headers that are always hop-by-hop, even when not listed in
+/// `Connection`.
+static HOP_BY_HOP: [HeaderName; 8] = [
+    header::CONNECTION,
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+    header::TE,
+    header::TRAILER,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+    HeaderName::from_static("keep-alive"),
+];
+
+static PROXY_CONNECTION: HeaderName = HeaderName::from_static("proxy-connection");
+
+/// Remove the hop-by-hop headers of a message, before forwarding it, as
+/// described in [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-7.6.1).
+///
+/// This removes every header named in `Connection`, `Connection` itself,
+/// and the headers that are hop-by-hop by definition: `Keep-Alive`,
+/// `Proxy-Connection`, `TE`, `Transfer-Encoding`, `Upgrade`,
+/// `Proxy-Authorization`, `Proxy-Authenticate` and `Trailer`.
+///
+/// If `TE` contains `trailers`, it is replaced with `TE: trailers`, even if
+/// it was named in `Connection`. This tells the next hop that trailer
+/// fields will be forwarded, which some protocols such as gRPC require.
+/// The other transfer codings are removed, since they only apply to the
+/// connection they were received on.
+///
+/// # Example
+///
+/// ```
+/// use headers::proxy::remove_hop_by_hop_headers;
+/// use headers::HeaderMap;
+///
+/// let mut req = HeaderMap::new();
+/// req.insert("connection", "keep-alive, x-trace".parse().unwrap());
+/// req.insert("keep-alive", "timeout=5".parse().unwrap());
+/// req.insert("x-trace", "abc".parse().unwrap());
+/// req.insert("te", "trailers, deflate".parse().unwrap());
+/// req.insert("accept", "*/*".parse().unwrap());
+///
+/// remove_hop_by_hop_headers(&mut req);
+///
+/// assert_eq!(req.len(), 2);
+/// assert_eq!(req["te"], "trailers");
+/// assert_eq!(req["accept"], "*/*");
+/// ```
+pub fn remove_hop_by_hop_headers(headers: &mut HeaderMap) {
+    let trailers = headers
+        .typed_get::<Te>()
+        .map(|te| te.accepts_trailers())
+        .unwrap_or(false);
+
+    if let Some(connection) = headers.typed_get::<Connection>() {
+        for option in connection.iter() {
+            if let Ok(name) = HeaderName::from_bytes(option.as_bytes()) {
+                headers.remove(name);
+            }
+        }
+    }
+    for name in &HOP_BY_HOP {
+        headers.remove(name);
+    }
+    headers.remove(&PROXY_CONNECTION);
+
+    if trailers {
+        headers.insert(header::TE, HeaderValue::from_static("trailers"));
+    }
+}
+
+/// The header that trusted proxies record addresses in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ForwardedHeader {
+    /// The `for` parameters of the `Forwarded` header.
+    Forwarded,
+    /// The `X-Forwarded-For` header.
+    XForwardedFor,
+}
+
+/// The set of proxies whose forwarding headers are trusted.
+///
+/// Only the header the proxies actually write should be used, which is
+/// `Forwarded` by default. Otherwise a client could send the other one,
+/// and have it passed along unchanged.
+#[derive(Clone, Debug)]
+pub struct TrustedProxies {
+    networks: Vec<(IpAddr, u8)>,
+    header: ForwardedHeader,
+}
+
+impl TrustedProxies {
+    /// Create an empty set of trusted proxies, using the `Forwarded`
+    /// header.
+    pub fn new() -> TrustedProxies {
+        TrustedProxies {
+            networks: Vec::new(),
+            header: ForwardedHeader::Forwarded,
+        }
+    }
+
+    /// Trust a proxy with the given address.
+    pub fn with_proxy(self, addr: IpAddr) -> TrustedProxies {
+        let prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        self.with_network(addr, prefix_len)
+    }
+
+    /// Trust the proxies in a network, such as `10.0.0.0/8`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the prefix length is greater than 32 for an IPv4 network,
+    /// or 128 for an IPv6 network, or less than 96 for an IPv4-mapped IPv6
+    /// network.
+    ///
+    /// An IPv4-mapped IPv6 network, such as `::ffff:10.0.0.0/104`, is
+    /// converted to IPv4, so its prefix length must be at least 96.
+    pub fn with_network(mut self, addr: IpAddr, prefix_len: u8) -> TrustedProxies {
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        assert!(prefix_len <= max, "invalid prefix length");
+        let network = normalize(addr);
+        let prefix_len = if addr.is_ipv6() && network.is_ipv4() {
+            prefix_len
+                .checked_sub(96)
+                .expect("invalid prefix length for an IPv4-mapped network")
+        } else {
+            prefix_len
+        };
+        self.networks.push((network, prefix_len));
+        self
+    }
+
+    /// Set the header that the proxies record addresses in.
+    pub fn with_header(mut self, header: ForwardedHeader) -> TrustedProxies {
+        self.header = header;
+        self
+    }
+
+    /// Check if an address is a trusted proxy.
+    pub fn is_trusted(&self, addr: IpAddr) -> bool {
+        let addr = normalize(addr);
+        self.networks
+            .iter()
+            .any(|&(network, prefix_len)| contains(network, prefix_len, addr))
+    }
+
+    /// Find the address of the client, given the peer address of the
+    /// connection and the headers of the request.
+    ///
+    /// If the peer isn't a trusted proxy, it is the client. If a trusted
+    /// proxy didn't record a usable address, such as `unknown` or an
+    /// obfuscated node, or the header is invalid, the address of that
+    /// proxy is returned.
+    pub fn client_addr(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let peer = normalize(peer);
+        if !self.is_trusted(peer) {
+            return peer;
+        }
+
+        let hops: Vec<Option<IpAddr>> = match self.header {
+            ForwardedHeader::Forwarded => match headers.typed_get::<Forwarded>() {
+                Some(forwarded) => forwarded
+                    .elements()
+                    .map(|element| element.forwarded_for().and_then(|node| node.ip()))
+                    .collect(),
+                None => Vec::new(),
+            },
+            ForwardedHeader::XForwardedFor => match headers.typed_get::<XForwardedFor>() {
+                Some(xff) => xff.iter().map(Some).collect(),
+                None => Vec::new(),
+            },
+        };
+
+        let mut addr = peer;
+        for hop in hops.into_iter().rev() {
+            if !self.is_trusted(addr) {
+                break;
+            }
+            match hop {
+                Some(hop) => addr = normalize(hop),
+                None => break,
+            }
+        }
+        addr
+    }
+}
+
+impl Default for TrustedProxies {
+    fn default() -> TrustedProxies {
+        TrustedProxies::new()
+    }
+}
+
+/// Convert IPv4-mapped IPv6 addresses, such as `::ffff:192.0.2.1`, to IPv4.
+fn normalize(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => match v6.segments() {
+            [0, 0, 0, 0, 0, 0xffff, high, low] => IpAddr::V4(Ipv4Addr::new(
+                (high >> 8) as u8,
+                high as u8,
+                (low >> 8) as u8,
+                low as u8,
+            )),
+            _ => addr,
+        },
+        IpAddr::V4(_) => addr,
+    }
+}
+
+fn contains(network: IpAddr, prefix_len: u8, addr: IpAddr) -> bool {
+    match (network, addr) {
+        (IpAddr::V4(network), IpAddr::V4(addr)) => match 32u32.checked_sub(prefix_len.into()) {
+            Some(shift) => {
+                let mask = u32::MAX.checked_shl(shift).unwrap_or(0);
+                u32::from(network) & mask == u32::from(addr) & mask
+            }
+            None => false,
+        },
+        (IpAddr::V6(network), IpAddr::V6(addr)) => match 128u32.checked_sub(prefix_len.into()) {
+            Some(shift) => {
+                let mask = u128::MAX.checked_shl(shift).unwrap_or(0);
+                u128::from(network) & mask == u128::from(addr) & mask
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn headers(name: &'static str, value: &'static str) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        map.insert(name, value.parse().unwrap());
+        map
+    }
+
+    #[test]
+    fn hop_by_hop_headers() {
+        let mut req = HeaderMap::new();
+        req.insert("host", "example.com".parse().unwrap());
+        req.append("connection", "Upgrade".parse().unwrap());
+        req.append("connection", "X-Custom, , invalid header".parse().unwrap());
+        req.insert("upgrade", "websocket".parse().unwrap());
+        req.insert("x-custom", "1".parse().unwrap());
+        req.insert("proxy-connection", "keep-alive".parse().unwrap());
+        req.insert("proxy-authorization", "Basic Zm9vOmJhcg==".parse().unwrap());
+        req.insert("transfer-encoding", "chunked".parse().unwrap());
+        req.insert("trailer", "x-checksum".parse().unwrap());
+        req.insert("te", "deflate".parse().unwrap());
+
+        remove_hop_by_hop_headers(&mut req);
+        assert_eq!(req.keys().collect::<Vec<_>>(), ["host"]);
+
+        let mut res = HeaderMap::new();
+        res.insert("proxy-authenticate", "Basic".parse().unwrap());
+        res.insert("content-length", "3".parse().unwrap());
+        remove_hop_by_hop_headers(&mut res);
+        assert_eq!(res.keys().collect::<Vec<_>>(), ["content-length"]);
+    }
+
+    #[test]
+    fn hop_by_hop_te_trailers() {
+        let mut req = HeaderMap::new();
+        req.insert("connection", "te".parse().unwrap());
+        req.insert("te", "gzip;q=0.5, Trailers".parse().unwrap());
+        remove_hop_by_hop_headers(&mut req);
+        assert_eq!(req.len(), 1);
+        assert_eq!(req["te"], "trailers");
+    }
+
+    #[test]
+    fn networks() {
+        let proxies = TrustedProxies::new()
+            .with_network(ip("10.0.0.0"), 8)
+            .with_network(ip("2001:db8::"), 32)
+            .with_proxy(ip("192.0.2.1"));
+
+        assert!(proxies.is_trusted(ip("10.255.0.1")));
+        assert!(proxies.is_trusted(ip("::ffff:10.0.0.1")));
+        assert!(proxies.is_trusted(ip("2001:db8:1::1")));
+        assert!(proxies.is_trusted(ip("192.0.2.1")));
+        assert!(!proxies.is_trusted(ip("192.0.2.2")));
+        assert!(!proxies.is_trusted(ip("11.0.0.1")));
+        assert!(!proxies.is_trusted(ip("2001:db9::1")));
+
+        let all = TrustedProxies::new().with_network(ip("0.0.0.0"), 0);
+        assert!(all.is_trusted(ip("203.0.113.1")));
+        assert!(!all.is_trusted(ip("2001:db8::1")));
+    }
+
+    #[test]
+    fn mapped_networks() {
+        let proxies = TrustedProxies::new()
+            .with_network(ip("::ffff:10.0.0.0"), 104)
+            .with_proxy(ip("::ffff:192.0.2.1"));
+
+        assert!(proxies.is_trusted(ip("10.1.2.3")));
+        assert!(proxies.is_trusted(ip("::ffff:10.1.2.3")));
+        assert!(proxies.is_trusted(ip("192.0.2.1")));
+        assert!(!proxies.is_trusted(ip("11.0.0.1")));
+        assert!(!proxies.is_trusted(ip("192.0.2.2")));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid prefix length for an IPv4-mapped network")]
+    fn mapped_network_short_prefix() {
+        let _ = TrustedProxies::new().with_network(ip("::ffff:10.0.0.0"), 95);
+    }
+
+    #[test]
+    fn untrusted_peer() {
+        let proxies = TrustedProxies::new().with_proxy(ip("10.0.0.1"));
+        let req = headers("forwarded", "for=1.2.3.4");
+        assert_eq!(
+            proxies.client_addr(ip("203.0.113.9"), &req),
+            ip("203.0.113.9")
+        );
+    }
+
+    #[test]
+    fn forwarded() {
+        let proxies = TrustedProxies::new().with_network(ip("10.0.0.0"), 8);
+        let req = headers(
+            "forwarded",
+            "for=1.2.3.4, for=\"[2001:db8::7]:4711\", for=10.0.0.2",
+        );
+        assert_eq!(proxies.client_addr(ip("10.0.0.1"), &req), ip("2001:db8::7"));
+
+        // An unknown node stops the walk at the proxy that added it.
+        let req = headers("forwarded", "for=1.2.3.4, for=unknown;by=10.0.0.2");
+        assert_eq!(proxies.client_addr(ip("10.0.0.1"), &req), ip("10.0.0.1"));
+
+        // All hops are trusted.
+        let req = headers("forwarded", "for=10.0.0.3");
+        assert_eq!(proxies.client_addr(ip("10.0.0.1"), &req), ip("10.0.0.3"));
+
+        // Invalid or missing headers.
+        let req = headers("forwarded", "for=1.2.3.4;for=5.6.7.8");
+        assert_eq!(proxies.client_addr(ip("10.0.0.1"), &req), ip("10.0.0.1"));
+        assert_eq!(
+            proxies.client_addr(ip("10.0.0.1"), &HeaderMap::new()),
+            ip("10.0.0.1")
+        );
+    }
+
+    #[test]
+    fn only_the_configured_header_is_used() {
+        let forwarded = TrustedProxies::new().with_proxy(ip("10.0.0.1"));
+        let xff = forwarded
+            .clone()
+            .with_header(ForwardedHeader::XForwardedFor);
+
+        let req = headers("x-forwarded-for", "1.2.3.4");
+        assert_eq!(forwarded.client_addr(ip("10.0.0.1"), &req), ip("10.0.0.1"));
+        assert_eq!(xff.client_addr(ip("10.0.0.1"), &req), ip("1.2.3.4"));
+
+        let req = headers("forwarded", "for=1.2.3.4");
+        assert_eq!(xff.client_addr(ip("10.0.0.1"), &req), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn spoofed_addresses_are_skipped() {
+        let proxies = TrustedProxies::new()
+            .with_network(ip("10.0.0.0"), 8)
+            .with_header(ForwardedHeader::XForwardedFor);
+        // The client sent "10.9.9.9, 127.0.0.1" itself.
+        let req = headers("x-forwarded-for", "10.9.9.9, 127.0.0.1, 198.51.100.4");
+        assert_eq!(
+            proxies.client_addr(ip("::ffff:10.0.0.1"), &req),
+            ip("198.51.100.4")
+        );
+    }
+}
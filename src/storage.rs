@@ -0,0 +1,347 @@
+//! Storing responses in caches.
+//!
+//! [`is_storable`] decides whether a response may be stored at all, as
+//! defined in [RFC 9111 section
+//! 3](https://www.rfc-editor.org/rfc/rfc9111#section-3). A stored response
+//! is found again by its primary key, usually the method and target URI,
+//! and by the [`SecondaryKey`] derived from its `Vary` header.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use headers::storage::{is_storable, SecondaryKey};
+//! use headers::{CacheControl, HeaderMap, HeaderMapExt, Vary};
+//! use http::{Method, StatusCode};
+//!
+//! let mut req = HeaderMap::new();
+//! req.insert("accept-encoding", "gzip".parse().unwrap());
+//!
+//! let mut res = HeaderMap::new();
+//! res.typed_insert(CacheControl::new().with_max_age(Duration::from_secs(60)));
+//! res.typed_insert(Vary::from(http::header::ACCEPT_ENCODING));
+//!
+//! assert!(is_storable(&Method::GET, StatusCode::OK, &req, &res, true));
+//!
+//! let vary = res.typed_get::<Vary>();
+//! let key = SecondaryKey::new(vary.as_ref(), &req);
+//! assert!(key.is_some());
+//! ```
+
+use http::{HeaderMap, HeaderName, Method, StatusCode};
+
+use crate::util::FlatCsv;
+use crate::{CacheControl, HeaderMapExt, Vary};
+
+/// Decide whether a response may be stored by a cache.
+///
+/// `request` and `response` are the headers of the request and its
+/// response, and `shared` is whether the cache is shared between users,
+/// such as a proxy, rather than private to a single user agent.
+///
+/// Only responses to `GET` and `HEAD` are stored, and `206 Partial Content`
+/// and `304 Not Modified` responses are not, as they can only be combined
+/// with or used to update a stored response.
+pub fn is_storable(
+    method: &Method,
+    status: StatusCode,
+    request: &HeaderMap,
+    response: &HeaderMap,
+    shared: bool,
+) -> bool {
+    if method != Method::GET && method != Method::HEAD {
+        return false;
+    }
+
+    if status.is_informational()
+        || status == StatusCode::PARTIAL_CONTENT
+        || status == StatusCode::NOT_MODIFIED
+    {
+        return false;
+    }
+
+    let request_cc = request.typed_get::<CacheControl>();
+    if request_cc.map(|cc| cc.no_store()).unwrap_or(false) {
+        return false;
+    }
+
+    let cc = response
+        .typed_get::<CacheControl>()
+        .unwrap_or_else(CacheControl::new);
+
+    // A cache that understands the status code ignores `no-store` when
+    // `must-understand` is present.
+    if cc.must_understand() {
+        if !is_understood(status) {
+            return false;
+        }
+    } else if cc.no_store() {
+        return false;
+    }
+
+    if shared && cc.private() {
+        return false;
+    }
+
+    let s_max_age = shared && cc.s_max_age().is_some();
+
+    // Shared caches only store authenticated responses that are explicitly
+    // allowed to be shared.
+    if shared
+        && request.contains_key(http::header::AUTHORIZATION)
+        && !(cc.must_revalidate() || cc.public() || s_max_age)
+    {
+        return false;
+    }
+
+    cc.public()
+        || (!shared && cc.private())
+        || response.contains_key(http::header::EXPIRES)
+        || cc.max_age().is_some()
+        || s_max_age
+        || is_heuristically_cacheable(status)
+}
+
+/// Returns whether a status code is heuristically cacheable, as listed in
+/// [RFC 9110 section 15.1](https://www.rfc-editor.org/rfc/rfc9110#section-15.1).
+pub fn is_heuristically_cacheable(status: StatusCode) -> bool {
+    matches!(
+        status.as_u16(),
+        200 | 203 | 204 | 206 | 300 | 301 | 308 | 404 | 405 | 410 | 414 | 501
+    )
+}
+
+/// The status codes whose caching semantics are understood, for
+/// `must-understand`.
+fn is_understood(status: StatusCode) -> bool {
+    is_heuristically_cacheable(status)
+        || matches!(
+            status.as_u16(),
+            302 | 303 | 307 | 400 | 403 | 500 | 502 | 503 | 504
+        )
+}
+
+/// The secondary cache key of a stored response.
+///
+/// It holds the normalized values of the request headers named by the
+/// `Vary` header of the response. A stored response can only be used for a
+/// later request with an equal key.
+///
+/// Header names are compared case-insensitively, and the values of each
+/// header are combined into a single list, ignoring the whitespace around
+/// its elements.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SecondaryKey {
+    fields: Vec<(HeaderName, Option<Vec<u8>>)>,
+}
+
+impl SecondaryKey {
+    /// Build the secondary key of a request, for a response with the given
+    /// `Vary` header.
+    ///
+    /// Returns `None` for `Vary: *`, which never matches a later request.
+    pub fn new(vary: Option<&Vary>, request: &HeaderMap) -> Option<SecondaryKey> {
+        let vary = match vary {
+            Some(vary) if vary.is_any() => return None,
+            Some(vary) => vary,
+            None => return Some(SecondaryKey { fields: Vec::new() }),
+        };
+
+        let mut names = vary
+            .iter_strs()
+            .filter_map(|name| name.parse::<HeaderName>().ok())
+            .collect::<Vec<_>>();
+        names.sort_unstable_by(|a, b| a.as_str().cmp(b.as_str()));
+        names.dedup();
+
+        let fields = names
+            .into_iter()
+            .map(|name| {
+                let value = normalize(request, &name);
+                (name, value)
+            })
+            .collect();
+
+        Some(SecondaryKey { fields })
+    }
+
+    /// Returns whether a later request matches this key.
+    ///
+    /// Only the headers named by `Vary` are compared, so the same `Vary`
+    /// must be used as when this key was built.
+    pub fn matches(&self, vary: Option<&Vary>, request: &HeaderMap) -> bool {
+        SecondaryKey::new(vary, request).as_ref() == Some(self)
+    }
+}
+
+/// Combine the values of a request header, distinguishing an absent header
+/// from an empty one.
+fn normalize(request: &HeaderMap, name: &HeaderName) -> Option<Vec<u8>> {
+    if !request.contains_key(name) {
+        return None;
+    }
+
+    let csv: FlatCsv = request.get_all(name).iter().collect();
+    if csv.value.to_str().is_err() {
+        return Some(csv.value.as_bytes().to_vec());
+    }
+
+    let value = csv
+        .iter()
+        .filter(|item| !item.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(value.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in pairs {
+            map.append(name.parse::<HeaderName>().unwrap(), value.parse().unwrap());
+        }
+        map
+    }
+
+    fn storable(status: u16, req: &[(&str, &str)], res: &[(&str, &str)], shared: bool) -> bool {
+        is_storable(
+            &Method::GET,
+            StatusCode::from_u16(status).unwrap(),
+            &headers(req),
+            &headers(res),
+            shared,
+        )
+    }
+
+    #[test]
+    fn methods_and_status() {
+        assert!(!is_storable(
+            &Method::POST,
+            StatusCode::OK,
+            &HeaderMap::new(),
+            &HeaderMap::new(),
+            false
+        ));
+        assert!(storable(200, &[], &[], false));
+        assert!(storable(404, &[], &[], true));
+        assert!(!storable(206, &[], &[], false));
+        assert!(!storable(304, &[], &[], false));
+
+        // Not heuristically cacheable, unless explicitly allowed.
+        assert!(!storable(302, &[], &[], false));
+        assert!(storable(
+            302,
+            &[],
+            &[("cache-control", "max-age=10")],
+            false
+        ));
+        assert!(storable(302, &[], &[("expires", "0")], false));
+    }
+
+    #[test]
+    fn no_store() {
+        assert!(!storable(200, &[], &[("cache-control", "no-store")], false));
+        assert!(!storable(200, &[("cache-control", "no-store")], &[], false));
+        assert!(storable(
+            200,
+            &[],
+            &[("cache-control", "no-store, must-understand")],
+            true
+        ));
+        assert!(!storable(
+            299,
+            &[],
+            &[("cache-control", "max-age=10, must-understand")],
+            true
+        ));
+    }
+
+    #[test]
+    fn private() {
+        assert!(storable(302, &[], &[("cache-control", "private")], false));
+        assert!(!storable(200, &[], &[("cache-control", "private")], true));
+    }
+
+    #[test]
+    fn authorization() {
+        let auth = &[("authorization", "Basic Zm9vOmJhcg==")];
+        assert!(!storable(200, auth, &[], true));
+        assert!(storable(200, auth, &[], false));
+        assert!(storable(200, auth, &[("cache-control", "public")], true));
+        assert!(storable(
+            200,
+            auth,
+            &[("cache-control", "s-maxage=10")],
+            true
+        ));
+        assert!(storable(
+            200,
+            auth,
+            &[("cache-control", "must-revalidate")],
+            true
+        ));
+
+        let auth = &[("authorization", "Custom abc")];
+        assert!(!storable(200, auth, &[], true));
+    }
+
+    #[test]
+    fn secondary_key() {
+        let vary = Vary::from(http::header::ACCEPT_ENCODING);
+        let req = headers(&[("accept-encoding", "gzip,br")]);
+        let key = SecondaryKey::new(Some(&vary), &req).unwrap();
+
+        assert!(key.matches(
+            Some(&vary),
+            &headers(&[("accept-encoding", "gzip"), ("accept-encoding", " br")])
+        ));
+        assert!(!key.matches(Some(&vary), &headers(&[("accept-encoding", "br")])));
+        assert!(!key.matches(Some(&vary), &HeaderMap::new()));
+
+        // Absent and empty headers differ.
+        let absent = SecondaryKey::new(Some(&vary), &HeaderMap::new());
+        let empty = SecondaryKey::new(Some(&vary), &headers(&[("accept-encoding", "")]));
+        assert_ne!(absent, empty);
+    }
+
+    #[test]
+    fn secondary_key_names_are_normalized() {
+        let a = headers(&[("vary", "Accept-Language, accept-encoding")]);
+        let b = headers(&[("vary", "accept-encoding,ACCEPT-LANGUAGE, accept-encoding")]);
+        let req = headers(&[("accept-language", "en"), ("accept-encoding", "gzip")]);
+
+        let a = SecondaryKey::new(a.typed_get::<Vary>().as_ref(), &req);
+        let b = SecondaryKey::new(b.typed_get::<Vary>().as_ref(), &req);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn secondary_key_vary_any() {
+        assert_eq!(
+            SecondaryKey::new(Some(&Vary::any()), &HeaderMap::new()),
+            None
+        );
+        assert!(!SecondaryKey::new(None, &HeaderMap::new())
+            .unwrap()
+            .matches(Some(&Vary::any()), &HeaderMap::new()));
+    }
+
+    #[test]
+    fn with_typed_headers() {
+        let mut res = HeaderMap::new();
+        res.typed_insert(CacheControl::new().with_max_age(Duration::from_secs(1)));
+        assert!(is_storable(
+            &Method::HEAD,
+            StatusCode::FOUND,
+            &HeaderMap::new(),
+            &res,
+            true
+        ));
+    }
+}
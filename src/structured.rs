@@ -0,0 +1,985 @@
+//! Structured Field Values for HTTP.
+//!
+//! Many newer header fields are defined as a structured field, defined in
+//! [RFC 9651](https://www.rfc-editor.org/rfc/rfc9651), which obsoletes
+//! [RFC 8941](https://www.rfc-editor.org/rfc/rfc8941). The value of such a
+//! field is an [`Item`], a [`List`] or a [`Dictionary`], and this module
+//! parses and serializes each of them.
+//!
+//! Parsing is strict: a field value that doesn't follow the syntax fails as
+//! a whole, as the specification requires, instead of skipping the members
+//! that don't parse. Serializing produces the canonical form of a value, so
+//! parsing and serializing again normalizes a field value.
+//!
+//! # Example
+//!
+//! ```
+//! use headers::structured::{self, BareItem, Item, Member};
+//! use headers::HeaderValue;
+//!
+//! let value = HeaderValue::from_static("u=1,   i");
+//! let mut dict = structured::parse_dictionary(std::iter::once(&value)).unwrap();
+//! assert_eq!(dict[0], ("u".to_owned(), Member::from(Item::new(BareItem::Integer(1)))));
+//!
+//! dict.push(("x".to_owned(), Item::new(BareItem::Token("y".to_owned())).into()));
+//! assert_eq!(structured::serialize_dictionary(&dict).unwrap(), "u=1, i, x=y");
+//! ```
+
+use std::fmt;
+
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig, STANDARD};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use http::HeaderValue;
+
+use crate::util;
+use crate::Error;
+
+/// The largest absolute value of an integer.
+pub(crate) const MAX_INTEGER: i64 = 999_999_999_999_999;
+
+/// Some implementations don't pad byte sequences, which should be accepted.
+const INDIFFERENT_PADDING: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// A bare item, without parameters.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BareItem {
+    /// An integer, with at most 15 digits.
+    Integer(i64),
+    /// A decimal, in thousandths, with at most 12 digits before the decimal
+    /// point.
+    Decimal(i64),
+    /// A string of visible ASCII characters and spaces.
+    String(String),
+    /// A token, such as `foo` or `text/html`.
+    Token(String),
+    /// A byte sequence, sent as base64.
+    ByteSequence(Vec<u8>),
+    /// A boolean, `?1` or `?0`.
+    Boolean(bool),
+    /// A date, in seconds since the Unix epoch, such as `@1659578233`.
+    Date(i64),
+    /// A display string, of any Unicode characters, such as
+    /// `%"f%c3%bc%c3%bc"`.
+    DisplayString(String),
+}
+
+/// The parameters of an item or an inner list, in order.
+///
+/// A key appears at most once.
+pub type Parameters = Vec<(String, BareItem)>;
+
+/// An item, with its parameters.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Item {
+    /// The value of this item.
+    pub bare_item: BareItem,
+    /// The parameters of this item.
+    pub params: Parameters,
+}
+
+/// An inner list of items, with its parameters, such as `(a b);q=1`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InnerList {
+    /// The items of this inner list.
+    pub items: Vec<Item>,
+    /// The parameters of this inner list.
+    pub params: Parameters,
+}
+
+/// A member of a list or a dictionary.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Member {
+    /// An item.
+    Item(Item),
+    /// An inner list.
+    InnerList(InnerList),
+}
+
+/// A list of members.
+pub type List = Vec<Member>;
+
+/// A dictionary of members, in order.
+///
+/// A key appears at most once.
+pub type Dictionary = Vec<(String, Member)>;
+
+impl Item {
+    /// Create an item without parameters.
+    pub fn new(bare_item: BareItem) -> Item {
+        Item {
+            bare_item,
+            params: Vec::new(),
+        }
+    }
+}
+
+impl InnerList {
+    /// Create an inner list without parameters.
+    pub fn new(items: Vec<Item>) -> InnerList {
+        InnerList {
+            items,
+            params: Vec::new(),
+        }
+    }
+}
+
+impl From<Item> for Member {
+    fn from(item: Item) -> Member {
+        Member::Item(item)
+    }
+}
+
+impl From<InnerList> for Member {
+    fn from(list: InnerList) -> Member {
+        Member::InnerList(list)
+    }
+}
+
+/// Set a key of a dictionary or parameters, overwriting any previous value
+/// in place.
+pub(crate) fn insert<V>(map: &mut Vec<(String, V)>, key: String, value: V) {
+    match map.iter_mut().find(|(k, _)| *k == key) {
+        Some(entry) => entry.1 = value,
+        None => map.push((key, value)),
+    }
+}
+
+/// Parse the field lines of an item field.
+pub fn parse_item<'i, I>(values: I) -> Result<Item, Error>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let input = combine(values);
+    let mut parser = Parser::new(&input)?;
+    let item = parser.item()?;
+    if parser.is_empty() {
+        Ok(item)
+    } else {
+        Err(Error::invalid())
+    }
+}
+
+/// Parse the field lines of a list field.
+///
+/// Multiple field lines are combined into a single list.
+pub fn parse_list<'i, I>(values: I) -> Result<List, Error>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let input = combine(values);
+    let mut parser = Parser::new(&input)?;
+    let mut list = Vec::new();
+    while !parser.is_empty() {
+        list.push(parser.member()?);
+        if !parser.next_list_member()? {
+            break;
+        }
+    }
+    Ok(list)
+}
+
+/// Parse the field lines of a dictionary field.
+///
+/// Multiple field lines are combined into a single dictionary. When a key
+/// appears more than once, the last value is kept, in the position of the
+/// first one.
+pub fn parse_dictionary<'i, I>(values: I) -> Result<Dictionary, Error>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let input = combine(values);
+    let mut parser = Parser::new(&input)?;
+    let mut dict = Vec::new();
+    while !parser.is_empty() {
+        let key = parser.key()?;
+        let member = if parser.eat(b'=') {
+            parser.member()?
+        } else {
+            Member::Item(Item {
+                bare_item: BareItem::Boolean(true),
+                params: parser.params()?,
+            })
+        };
+        insert(&mut dict, key, member);
+        if !parser.next_list_member()? {
+            break;
+        }
+    }
+    Ok(dict)
+}
+
+/// Serialize an item field.
+///
+/// Fails if the item contains a value that can't be serialized, such as an
+/// integer that is too large or a key with uppercase letters.
+pub fn serialize_item(item: &Item) -> Result<HeaderValue, Error> {
+    validate_item(item)?;
+    Ok(util::fmt(FmtItem(item)))
+}
+
+/// Serialize a list field.
+///
+/// Fails if a member contains a value that can't be serialized, or if the
+/// list is empty, in which case the field should be omitted.
+pub fn serialize_list(list: &[Member]) -> Result<HeaderValue, Error> {
+    if list.is_empty() {
+        return Err(Error::invalid());
+    }
+    for member in list {
+        validate_member(member)?;
+    }
+    Ok(util::fmt(FmtList(list)))
+}
+
+/// Serialize a dictionary field.
+///
+/// Fails if a key or member contains a value that can't be serialized, if
+/// a key appears more than once, or if the dictionary is empty, in which
+/// case the field should be omitted.
+pub fn serialize_dictionary(dict: &[(String, Member)]) -> Result<HeaderValue, Error> {
+    if dict.is_empty() {
+        return Err(Error::invalid());
+    }
+    validate_keys(dict)?;
+    for (_, member) in dict {
+        validate_member(member)?;
+    }
+    Ok(util::fmt(FmtDictionary(dict)))
+}
+
+fn validate_keys<V>(map: &[(String, V)]) -> Result<(), Error> {
+    for (i, (key, _)) in map.iter().enumerate() {
+        if !is_key(key) || map[..i].iter().any(|(k, _)| k == key) {
+            return Err(Error::invalid());
+        }
+    }
+    Ok(())
+}
+
+fn validate_bare_item(bare_item: &BareItem) -> Result<(), Error> {
+    let valid = match *bare_item {
+        BareItem::Integer(value) | BareItem::Decimal(value) | BareItem::Date(value) => {
+            (-MAX_INTEGER..=MAX_INTEGER).contains(&value)
+        }
+        BareItem::String(ref value) => is_string(value),
+        BareItem::Token(ref value) => is_token(value),
+        BareItem::ByteSequence(_) | BareItem::Boolean(_) | BareItem::DisplayString(_) => true,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::invalid())
+    }
+}
+
+fn validate_params(params: &Parameters) -> Result<(), Error> {
+    validate_keys(params)?;
+    for (_, value) in params {
+        validate_bare_item(value)?;
+    }
+    Ok(())
+}
+
+fn validate_item(item: &Item) -> Result<(), Error> {
+    validate_bare_item(&item.bare_item)?;
+    validate_params(&item.params)
+}
+
+fn validate_member(member: &Member) -> Result<(), Error> {
+    match *member {
+        Member::Item(ref item) => validate_item(item),
+        Member::InnerList(ref list) => {
+            for item in &list.items {
+                validate_item(item)?;
+            }
+            validate_params(&list.params)
+        }
+    }
+}
+
+/// Combine multiple field lines into one, as if they were sent on a single
+/// line separated by commas.
+fn combine<'i, I>(values: I) -> Vec<u8>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let mut input = Vec::new();
+    for value in values {
+        if !input.is_empty() {
+            input.extend_from_slice(b", ");
+        }
+        input.extend_from_slice(value.as_bytes());
+    }
+    input
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a [u8]) -> Result<Parser<'a>, Error> {
+        if !input.is_ascii() {
+            return Err(Error::invalid());
+        }
+        let mut parser = Parser { input };
+        parser.skip_sp();
+        while parser.input.last() == Some(&b' ') {
+            parser.input = &parser.input[..parser.input.len() - 1];
+        }
+        Ok(parser)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.first().copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.input = &self.input[1..];
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_sp(&mut self) {
+        while self.eat(b' ') {}
+    }
+
+    fn skip_ows(&mut self) {
+        while self.eat(b' ') || self.eat(b'\t') {}
+    }
+
+    /// Skip to the next member of a list or dictionary, returning whether
+    /// there is one.
+    fn next_list_member(&mut self) -> Result<bool, Error> {
+        self.skip_ows();
+        if self.is_empty() {
+            return Ok(false);
+        }
+        if !self.eat(b',') {
+            return Err(Error::invalid());
+        }
+        self.skip_ows();
+        if self.is_empty() {
+            // A trailing comma is not allowed.
+            return Err(Error::invalid());
+        }
+        Ok(true)
+    }
+
+    fn member(&mut self) -> Result<Member, Error> {
+        if self.peek() == Some(b'(') {
+            self.inner_list().map(Member::InnerList)
+        } else {
+            self.item().map(Member::Item)
+        }
+    }
+
+    fn inner_list(&mut self) -> Result<InnerList, Error> {
+        if !self.eat(b'(') {
+            return Err(Error::invalid());
+        }
+        let mut items = Vec::new();
+        loop {
+            self.skip_sp();
+            if self.eat(b')') {
+                let params = self.params()?;
+                return Ok(InnerList { items, params });
+            }
+            items.push(self.item()?);
+            match self.peek() {
+                Some(b' ') | Some(b')') => {}
+                _ => return Err(Error::invalid()),
+            }
+        }
+    }
+
+    fn item(&mut self) -> Result<Item, Error> {
+        let bare_item = self.bare_item()?;
+        let params = self.params()?;
+        Ok(Item { bare_item, params })
+    }
+
+    fn params(&mut self) -> Result<Parameters, Error> {
+        let mut params = Vec::new();
+        while self.eat(b';') {
+            self.skip_sp();
+            let key = self.key()?;
+            let value = if self.eat(b'=') {
+                self.bare_item()?
+            } else {
+                BareItem::Boolean(true)
+            };
+            insert(&mut params, key, value);
+        }
+        Ok(params)
+    }
+
+    fn key(&mut self) -> Result<String, Error> {
+        match self.peek() {
+            Some(b) if b.is_ascii_lowercase() || b == b'*' => {}
+            _ => return Err(Error::invalid()),
+        }
+        let len = self
+            .input
+            .iter()
+            .position(|&b| !is_key_char(b))
+            .unwrap_or(self.input.len());
+        Ok(self.take(len).to_owned())
+    }
+
+    fn bare_item(&mut self) -> Result<BareItem, Error> {
+        match self.peek() {
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            Some(b'"') => self.string().map(BareItem::String),
+            Some(b':') => self.byte_sequence().map(BareItem::ByteSequence),
+            Some(b'?') => self.boolean().map(BareItem::Boolean),
+            Some(b'@') => self.date().map(BareItem::Date),
+            Some(b'%') => self.display_string().map(BareItem::DisplayString),
+            Some(b) if b.is_ascii_alphabetic() || b == b'*' => {
+                let len = self
+                    .input
+                    .iter()
+                    .position(|&b| !is_token_char(b))
+                    .unwrap_or(self.input.len());
+                Ok(BareItem::Token(self.take(len).to_owned()))
+            }
+            _ => Err(Error::invalid()),
+        }
+    }
+
+    fn number(&mut self) -> Result<BareItem, Error> {
+        let negative = self.eat(b'-');
+        let len = self
+            .input
+            .iter()
+            .position(|&b| !b.is_ascii_digit() && b != b'.')
+            .unwrap_or(self.input.len());
+        let num = self.take(len);
+
+        let (integer, fraction) = match num.find('.') {
+            Some(idx) => (&num[..idx], Some(&num[idx + 1..])),
+            None => (num, None),
+        };
+        if integer.is_empty() || !integer.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::invalid());
+        }
+        let sign = if negative { -1 } else { 1 };
+
+        match fraction {
+            None if integer.len() <= 15 => {
+                let value = integer.parse::<i64>().map_err(|_| Error::invalid())?;
+                Ok(BareItem::Integer(sign * value))
+            }
+            Some(fraction)
+                if integer.len() <= 12
+                    && !fraction.is_empty()
+                    && fraction.len() <= 3
+                    && fraction.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                let integer = integer.parse::<i64>().map_err(|_| Error::invalid())?;
+                let mut thousandths = fraction.parse::<i64>().map_err(|_| Error::invalid())?;
+                for _ in fraction.len()..3 {
+                    thousandths *= 10;
+                }
+                Ok(BareItem::Decimal(sign * (integer * 1000 + thousandths)))
+            }
+            _ => Err(Error::invalid()),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        if !self.eat(b'"') {
+            return Err(Error::invalid());
+        }
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                Some(b'\\') => {
+                    self.input = &self.input[1..];
+                    match self.peek() {
+                        Some(b @ b'"') | Some(b @ b'\\') => value.push(b as char),
+                        _ => return Err(Error::invalid()),
+                    }
+                }
+                Some(b'"') => {
+                    self.input = &self.input[1..];
+                    return Ok(value);
+                }
+                Some(b @ 0x20..=0x7e) => value.push(b as char),
+                _ => return Err(Error::invalid()),
+            }
+            self.input = &self.input[1..];
+        }
+    }
+
+    fn byte_sequence(&mut self) -> Result<Vec<u8>, Error> {
+        if !self.eat(b':') {
+            return Err(Error::invalid());
+        }
+        let len = self
+            .input
+            .iter()
+            .position(|&b| b == b':')
+            .ok_or_else(Error::invalid)?;
+        let encoded = self.take(len);
+        self.input = &self.input[1..];
+
+        let valid = encoded
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/' || b == b'=');
+        if !valid {
+            return Err(Error::invalid());
+        }
+        INDIFFERENT_PADDING
+            .decode(encoded)
+            .map_err(|_| Error::invalid())
+    }
+
+    fn boolean(&mut self) -> Result<bool, Error> {
+        if !self.eat(b'?') {
+            return Err(Error::invalid());
+        }
+        if self.eat(b'1') {
+            Ok(true)
+        } else if self.eat(b'0') {
+            Ok(false)
+        } else {
+            Err(Error::invalid())
+        }
+    }
+
+    fn date(&mut self) -> Result<i64, Error> {
+        if !self.eat(b'@') {
+            return Err(Error::invalid());
+        }
+        match self.number()? {
+            BareItem::Integer(value) => Ok(value),
+            _ => Err(Error::invalid()),
+        }
+    }
+
+    fn display_string(&mut self) -> Result<String, Error> {
+        if !self.eat(b'%') || !self.eat(b'"') {
+            return Err(Error::invalid());
+        }
+        let mut value = Vec::new();
+        loop {
+            match self.peek() {
+                Some(b'%') => {
+                    let hex = self.input.get(1..3).ok_or_else(Error::invalid)?;
+                    match (lower_hex_digit(hex[0]), lower_hex_digit(hex[1])) {
+                        (Some(high), Some(low)) => value.push(high << 4 | low),
+                        _ => return Err(Error::invalid()),
+                    }
+                    self.input = &self.input[3..];
+                }
+                Some(b'"') => {
+                    self.input = &self.input[1..];
+                    return String::from_utf8(value).map_err(|_| Error::invalid());
+                }
+                Some(b @ 0x20..=0x7e) => {
+                    value.push(b);
+                    self.input = &self.input[1..];
+                }
+                _ => return Err(Error::invalid()),
+            }
+        }
+    }
+
+    fn take(&mut self, len: usize) -> &'a str {
+        let (taken, rest) = self.input.split_at(len);
+        self.input = rest;
+        std::str::from_utf8(taken).expect("input is ascii")
+    }
+}
+
+fn lower_hex_digit(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        _ => None,
+    }
+}
+
+fn is_key_char(b: u8) -> bool {
+    b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'_' | b'-' | b'.' | b'*')
+}
+
+fn is_token_char(b: u8) -> bool {
+    util::is_tchar(b) || b == b':' || b == b'/'
+}
+
+/// Returns whether a string can be serialized as a key.
+pub(crate) fn is_key(s: &str) -> bool {
+    match s.as_bytes().first() {
+        Some(&b) if b.is_ascii_lowercase() || b == b'*' => s.bytes().all(is_key_char),
+        _ => false,
+    }
+}
+
+/// Returns whether a string can be serialized as a token.
+pub(crate) fn is_token(s: &str) -> bool {
+    match s.as_bytes().first() {
+        Some(&b) if b.is_ascii_alphabetic() || b == b'*' => s.bytes().all(is_token_char),
+        _ => false,
+    }
+}
+
+/// Returns whether a string can be serialized as a string.
+pub(crate) fn is_string(s: &str) -> bool {
+    s.bytes().all(|b| (0x20..=0x7e).contains(&b))
+}
+
+// Formatting doesn't check that values can be serialized, which is left to
+// the callers.
+
+/// Format a bare item.
+pub(crate) struct FmtBareItem<'a>(pub(crate) &'a BareItem);
+
+impl fmt::Display for FmtBareItem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self.0 {
+            BareItem::Integer(value) => {
+                debug_assert!(value.abs() <= MAX_INTEGER);
+                write!(f, "{}", value)
+            }
+            BareItem::Decimal(value) => {
+                let sign = if value < 0 { "-" } else { "" };
+                let value = value.abs();
+                let fraction = format!("{:03}", value % 1000);
+                let fraction = fraction.trim_end_matches('0');
+                let fraction = if fraction.is_empty() { "0" } else { fraction };
+                write!(f, "{}{}.{}", sign, value / 1000, fraction)
+            }
+            BareItem::String(ref value) => {
+                f.write_str("\"")?;
+                for c in value.chars() {
+                    if c == '"' || c == '\\' {
+                        f.write_str("\\")?;
+                    }
+                    write!(f, "{}", c)?;
+                }
+                f.write_str("\"")
+            }
+            BareItem::Token(ref value) => f.write_str(value),
+            BareItem::ByteSequence(ref value) => write!(f, ":{}:", STANDARD.encode(value)),
+            BareItem::Boolean(value) => f.write_str(if value { "?1" } else { "?0" }),
+            BareItem::Date(value) => write!(f, "@{}", value),
+            BareItem::DisplayString(ref value) => {
+                f.write_str("%\"")?;
+                for &b in value.as_bytes() {
+                    if b == b'%' || b == b'"' || !(0x20..=0x7e).contains(&b) {
+                        write!(f, "%{:02x}", b)?;
+                    } else {
+                        write!(f, "{}", b as char)?;
+                    }
+                }
+                f.write_str("\"")
+            }
+        }
+    }
+}
+
+struct FmtParams<'a>(&'a Parameters);
+
+impl fmt::Display for FmtParams<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (key, value) in self.0 {
+            write!(f, ";{}", key)?;
+            if *value != BareItem::Boolean(true) {
+                write!(f, "={}", FmtBareItem(value))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Format an item as a field value.
+pub(crate) struct FmtItem<'a>(pub(crate) &'a Item);
+
+impl fmt::Display for FmtItem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            FmtBareItem(&self.0.bare_item),
+            FmtParams(&self.0.params)
+        )
+    }
+}
+
+struct FmtMember<'a>(&'a Member);
+
+impl fmt::Display for FmtMember<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self.0 {
+            Member::Item(ref item) => fmt::Display::fmt(&FmtItem(item), f),
+            Member::InnerList(ref list) => {
+                f.write_str("(")?;
+                for (i, item) in list.items.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(" ")?;
+                    }
+                    write!(f, "{}", FmtItem(item))?;
+                }
+                write!(f, "){}", FmtParams(&list.params))
+            }
+        }
+    }
+}
+
+/// Format a list as a field value.
+pub(crate) struct FmtList<'a>(pub(crate) &'a [Member]);
+
+impl fmt::Display for FmtList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, member) in self.0.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", FmtMember(member))?;
+        }
+        Ok(())
+    }
+}
+
+/// Format a dictionary as a field value.
+pub(crate) struct FmtDictionary<'a>(pub(crate) &'a [(String, Member)]);
+
+impl fmt::Display for FmtDictionary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (key, member)) in self.0.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(key)?;
+            match *member {
+                Member::Item(Item {
+                    bare_item: BareItem::Boolean(true),
+                    ref params,
+                }) => write!(f, "{}", FmtParams(params))?,
+                _ => write!(f, "={}", FmtMember(member))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(s: &str) -> Result<List, Error> {
+        parse_list(Some(&HeaderValue::from_str(s).unwrap()).into_iter())
+    }
+
+    fn dict(s: &str) -> Result<Dictionary, Error> {
+        parse_dictionary(Some(&HeaderValue::from_str(s).unwrap()).into_iter())
+    }
+
+    fn item(bare_item: BareItem) -> Member {
+        Member::Item(Item::new(bare_item))
+    }
+
+    #[test]
+    fn parse_bare_items() {
+        assert_eq!(
+            list("42, -17, 4.5, -0.25, \"hi \\\"x\\\"\", foo/bar:1, *t, :cHJldGVuZA==:, ?1, ?0")
+                .unwrap(),
+            vec![
+                item(BareItem::Integer(42)),
+                item(BareItem::Integer(-17)),
+                item(BareItem::Decimal(4500)),
+                item(BareItem::Decimal(-250)),
+                item(BareItem::String("hi \"x\"".into())),
+                item(BareItem::Token("foo/bar:1".into())),
+                item(BareItem::Token("*t".into())),
+                item(BareItem::ByteSequence(b"pretend".to_vec())),
+                item(BareItem::Boolean(true)),
+                item(BareItem::Boolean(false)),
+            ]
+        );
+        assert_eq!(
+            list(":cHJldGVuZA:").unwrap(),
+            vec![item(BareItem::ByteSequence(b"pretend".to_vec()))]
+        );
+    }
+
+    #[test]
+    fn parse_invalid_bare_items() {
+        for s in &[
+            "1234567890123456",
+            "1234567890123.0",
+            "1.1234",
+            "1.",
+            "-",
+            "\"\\n\"",
+            "\"unterminated",
+            ":cHJl*GVuZA==:",
+            "?2",
+            "#",
+            "a, ",
+            "a,,b",
+            "a b",
+        ] {
+            assert!(list(s).is_err(), "{:?} should be invalid", s);
+        }
+    }
+
+    #[test]
+    fn parse_inner_lists_and_params() {
+        assert_eq!(
+            list("(\"foo\" \"bar\");lvl=5, (), abc;a=1;b").unwrap(),
+            vec![
+                Member::InnerList(InnerList {
+                    items: vec![
+                        Item::new(BareItem::String("foo".into())),
+                        Item::new(BareItem::String("bar".into())),
+                    ],
+                    params: vec![("lvl".into(), BareItem::Integer(5))],
+                }),
+                Member::InnerList(InnerList {
+                    items: vec![],
+                    params: vec![],
+                }),
+                Member::Item(Item {
+                    bare_item: BareItem::Token("abc".into()),
+                    params: vec![
+                        ("a".into(), BareItem::Integer(1)),
+                        ("b".into(), BareItem::Boolean(true)),
+                    ],
+                }),
+            ]
+        );
+        assert!(list("(a b").is_err());
+        assert!(list("(a,b)").is_err());
+    }
+
+    #[test]
+    fn parse_dictionaries() {
+        assert_eq!(
+            dict("a=1, b, c=?0;x, a=2").unwrap(),
+            vec![
+                ("a".into(), item(BareItem::Integer(2))),
+                ("b".into(), item(BareItem::Boolean(true))),
+                (
+                    "c".into(),
+                    Member::Item(Item {
+                        bare_item: BareItem::Boolean(false),
+                        params: vec![("x".into(), BareItem::Boolean(true))],
+                    })
+                ),
+            ]
+        );
+        assert!(dict("A=1").is_err());
+        assert!(dict("a=").is_err());
+    }
+
+    #[test]
+    fn parse_multiple_lines() {
+        let values = [
+            HeaderValue::from_static("a=1"),
+            HeaderValue::from_static(" b"),
+        ];
+        assert_eq!(parse_dictionary(values.iter()).unwrap().len(), 2);
+        assert_eq!(parse_list(std::iter::empty()).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn serialize() {
+        for s in &[
+            "42, -17, 4.5, -0.25, 1.0, \"hi \\\"x\\\"\", foo/bar:1, :cHJldGVuZA==:, ?1, ?0",
+            "(\"foo\" \"bar\");lvl=5, (), abc;a=1;b",
+        ] {
+            assert_eq!(serialize_list(&list(s).unwrap()).unwrap(), *s);
+        }
+        assert_eq!(
+            serialize_list(&list("4.50,1.000").unwrap()).unwrap(),
+            "4.5, 1.0"
+        );
+
+        let s = "a=1, b, c=?0;x, d;y=\"z\"";
+        assert_eq!(serialize_dictionary(&dict(s).unwrap()).unwrap(), s);
+    }
+
+    #[test]
+    fn dates_and_display_strings() {
+        assert_eq!(
+            list("@1659578233, @-1, %\"f%c3%bc%c3%bc %22x%22\"").unwrap(),
+            vec![
+                item(BareItem::Date(1659578233)),
+                item(BareItem::Date(-1)),
+                item(BareItem::DisplayString("füü \"x\"".into())),
+            ]
+        );
+        for s in &[
+            "@1.5",
+            "@",
+            "%\"%C3%BC\"",
+            "%\"%c3\"",
+            "%\"%g0\"",
+            "%\"a",
+            "%a",
+        ] {
+            assert!(list(s).is_err(), "{:?} should be invalid", s);
+        }
+
+        let s = "@1659578233, %\"f%c3%bc%c3%bc %22%25\"";
+        assert_eq!(serialize_list(&list(s).unwrap()).unwrap(), s);
+    }
+
+    #[test]
+    fn items() {
+        let value = HeaderValue::from_static("  abc;a=1 ");
+        assert_eq!(
+            parse_item(std::iter::once(&value)).unwrap(),
+            Item {
+                bare_item: BareItem::Token("abc".into()),
+                params: vec![("a".into(), BareItem::Integer(1))],
+            }
+        );
+        let values = [HeaderValue::from_static("1"), HeaderValue::from_static("2")];
+        assert!(parse_item(values.iter()).is_err());
+        assert!(parse_item(std::iter::empty()).is_err());
+
+        let item = Item::new(BareItem::DisplayString("ü".into()));
+        assert_eq!(serialize_item(&item).unwrap(), "%\"%c3%bc\"");
+    }
+
+    #[test]
+    fn serialize_invalid() {
+        let invalid = vec![
+            BareItem::Integer(MAX_INTEGER + 1),
+            BareItem::Decimal(-MAX_INTEGER - 1),
+            BareItem::Date(i64::MIN),
+            BareItem::String("\n".into()),
+            BareItem::Token("1a".into()),
+        ];
+        for bare_item in invalid {
+            assert!(serialize_item(&Item::new(bare_item)).is_err());
+        }
+
+        let mut item = Item::new(BareItem::Boolean(true));
+        item.params.push(("Key".into(), BareItem::Integer(1)));
+        assert!(serialize_list(&[item.into()]).is_err());
+
+        assert!(serialize_list(&[]).is_err());
+        assert!(serialize_dictionary(&[]).is_err());
+
+        let member = Member::from(InnerList::new(vec![]));
+        let dict = vec![("a".to_owned(), member.clone()), ("a".to_owned(), member)];
+        assert!(serialize_dictionary(&dict).is_err());
+    }
+}
@@ -1,20 +1,28 @@
 use std::fmt;
 use std::str::FromStr;
 
+use super::is_token;
+use crate::Error;
+
 /// A Mime charset.
 ///
-/// The string representation is normalised to upper case.
+/// Known charsets are displayed with their preferred IANA name, and all
+/// charsets are compared case-insensitively. Any other token is kept as is,
+/// without validating it against the IANA registry.
 ///
 /// See [http://www.iana.org/assignments/character-sets/character-sets.xhtml][url].
 ///
 /// [url]: http://www.iana.org/assignments/character-sets/character-sets.xhtml
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Charset(Charset_);
 
 impl Charset {
     /// US ASCII
     pub const US_ASCII: Charset = Charset(Charset_::Us_Ascii);
 
+    /// UTF-8
+    pub const UTF_8: Charset = Charset(Charset_::Utf_8);
+
     /// ISO-8859-1
     pub const ISO_8859_1: Charset = Charset(Charset_::Iso_8859_1);
 
@@ -55,7 +63,7 @@ impl Charset {
     pub const ISO_2022_KR: Charset = Charset(Charset_::Iso_2022_Kr);
 
     /// EUC-KR
-    pub const EUC_KR: Charset: Charset(Charset_::Euc_Kr);
+    pub const EUC_KR: Charset = Charset(Charset_::Euc_Kr);
 
     /// ISO-2022-JP
     pub const ISO_2022_JP: Charset = Charset(Charset_::Iso_2022_Jp);
@@ -83,13 +91,18 @@ impl Charset {
 
     /// KOI8-R
     pub const KOI8_R: Charset = Charset(Charset_::Koi8_R);
+
+    /// The wildcard `*`, only meaningful in `Accept-Charset`.
+    pub const ANY: Charset = Charset(Charset_::Any);
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 #[allow(non_camel_case_types)]
 enum Charset_ {
     /// US ASCII
     Us_Ascii,
+    /// UTF-8
+    Utf_8,
     /// ISO-8859-1
     Iso_8859_1,
     /// ISO-8859-2
@@ -136,14 +149,47 @@ enum Charset_ {
     Big5,
     /// KOI8-R
     Koi8_R,
-
-    _Unknown,
+    /// The `*` wildcard
+    Any,
+    /// Any other charset token
+    Ext(String),
 }
 
+const KNOWN: &[Charset] = &[
+    Charset::US_ASCII,
+    Charset::UTF_8,
+    Charset::ISO_8859_1,
+    Charset::ISO_8859_2,
+    Charset::ISO_8859_3,
+    Charset::ISO_8859_4,
+    Charset::ISO_8859_5,
+    Charset::ISO_8859_6,
+    Charset::ISO_8859_7,
+    Charset::ISO_8859_8,
+    Charset::ISO_8859_9,
+    Charset::ISO_8859_10,
+    Charset::SHIFT_JIS,
+    Charset::EUC_JP,
+    Charset::ISO_2022_KR,
+    Charset::EUC_KR,
+    Charset::ISO_2022_JP,
+    Charset::ISO_2022_JP_2,
+    Charset::ISO_8859_6_E,
+    Charset::ISO_8859_6_I,
+    Charset::ISO_8859_8_E,
+    Charset::ISO_8859_8_I,
+    Charset::GB_2312,
+    Charset::BIG_5,
+    Charset::KOI8_R,
+    Charset::ANY,
+];
+
 impl Charset {
-    fn name(&self) -> &'static str {
+    /// Get the name of this charset as a string slice.
+    pub fn as_str(&self) -> &str {
         match self.0 {
             Charset_::Us_Ascii => "US-ASCII",
+            Charset_::Utf_8 => "UTF-8",
             Charset_::Iso_8859_1 => "ISO-8859-1",
             Charset_::Iso_8859_2 => "ISO-8859-2",
             Charset_::Iso_8859_3 => "ISO-8859-3",
@@ -154,7 +200,7 @@ impl Charset {
             Charset_::Iso_8859_8 => "ISO-8859-8",
             Charset_::Iso_8859_9 => "ISO-8859-9",
             Charset_::Iso_8859_10 => "ISO-8859-10",
-            Charset_::Shift_Jis => "Shift-JIS",
+            Charset_::Shift_Jis => "Shift_JIS",
             Charset_::Euc_Jp => "EUC-JP",
             Charset_::Iso_2022_Kr => "ISO-2022-KR",
             Charset_::Euc_Kr => "EUC-KR",
@@ -165,65 +211,82 @@ impl Charset {
             Charset_::Iso_8859_8_E => "ISO-8859-8-E",
             Charset_::Iso_8859_8_I => "ISO-8859-8-I",
             Charset_::Gb2312 => "GB2312",
-            Charset_::Big5 => "5",
+            Charset_::Big5 => "Big5",
             Charset_::Koi8_R => "KOI8-R",
-            Charset_::_Unknown => unreachable!("Charset::_Unknown"),
+            Charset_::Any => "*",
+            Charset_::Ext(ref s) => s,
         }
     }
+
+    pub(crate) fn is_any(&self) -> bool {
+        matches!(self.0, Charset_::Any)
+    }
 }
 
+impl PartialEq for Charset {
+    fn eq(&self, other: &Charset) -> bool {
+        self.as_str().eq_ignore_ascii_case(other.as_str())
+    }
+}
+
+impl Eq for Charset {}
+
 impl fmt::Display for Charset {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(self.name())
+        f.write_str(self.as_str())
     }
 }
 
-#[derive(Debug)]
-pub struct CharsetFromStrError(());
-
 impl FromStr for Charset {
-    type Err = CharsetFromStrError;
+    type Err = Error;
+
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Charset(match s.to_ascii_uppercase().as_ref() {
-            "US-ASCII" => Charset_::Us_Ascii,
-            "ISO-8859-1" => Charset_::Iso_8859_1,
-            "ISO-8859-2" => Charset_::Iso_8859_2,
-            "ISO-8859-3" => Charset_::Iso_8859_3,
-            "ISO-8859-4" => Charset_::Iso_8859_4,
-            "ISO-8859-5" => Charset_::Iso_8859_5,
-            "ISO-8859-6" => Charset_::Iso_8859_6,
-            "ISO-8859-7" => Charset_::Iso_8859_7,
-            "ISO-8859-8" => Charset_::Iso_8859_8,
-            "ISO-8859-9" => Charset_::Iso_8859_9,
-            "ISO-8859-10" => Charset_::Iso_8859_10,
-            "SHIFT-JIS" => Charset_::Shift_Jis,
-            "EUC-JP" => Charset_::Euc_Jp,
-            "ISO-2022-KR" => Charset_::Iso_2022_Kr,
-            "EUC-KR" => Charset_::Euc_Kr,
-            "ISO-2022-JP" => Charset_::Iso_2022_Jp,
-            "ISO-2022-JP-2" => Charset_::Iso_2022_Jp_2,
-            "ISO-8859-6-E" => Charset_::Iso_8859_6_E,
-            "ISO-8859-6-I" => Charset_::Iso_8859_6_I,
-            "ISO-8859-8-E" => Charset_::Iso_8859_8_E,
-            "ISO-8859-8-I" => Charset_::Iso_8859_8_I,
-            "GB2312" => Charset_::Gb2312,
-            "5" => Charset_::Big5,
-            "KOI8-R" => Charset_::Koi8_R,
-            _unknown => return Err(CharsetFromStrError(())),
-        }))
+        if let Some(charset) = KNOWN
+            .iter()
+            .find(|charset| charset.as_str().eq_ignore_ascii_case(s))
+        {
+            return Ok(charset.clone());
+        }
+
+        if s.eq_ignore_ascii_case("Shift-JIS") {
+            Ok(Charset::SHIFT_JIS)
+        } else if is_token(s) {
+            Ok(Charset(Charset_::Ext(s.to_owned())))
+        } else {
+            Err(Error::invalid())
+        }
     }
 }
 
-#[test]
-fn test_parse() {
-    assert_eq!(Charset::US_ASCII,"us-ascii".parse().unwrap());
-    assert_eq!(Charset::US_ASCII,"US-Ascii".parse().unwrap());
-    assert_eq!(Charset::US_ASCII,"US-ASCII".parse().unwrap());
-    assert_eq!(Charset::SHIFT_JIS,"Shift-JIS".parse().unwrap());
-    assert!("abcd".parse(::<Charset>().is_err());
-}
+#[cfg(test)]
+mod tests {
+    use super::Charset;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Charset::US_ASCII, "us-ascii".parse().unwrap());
+        assert_eq!(Charset::US_ASCII, "US-Ascii".parse().unwrap());
+        assert_eq!(Charset::US_ASCII, "US-ASCII".parse().unwrap());
+        assert_eq!(Charset::SHIFT_JIS, "Shift-JIS".parse().unwrap());
+        assert_eq!(Charset::SHIFT_JIS, "shift_jis".parse().unwrap());
+        assert_eq!(Charset::UTF_8, "utf-8".parse().unwrap());
+        assert_eq!(Charset::BIG_5, "big5".parse().unwrap());
+        assert!("*".parse::<Charset>().unwrap().is_any());
+        assert_eq!("abcd".parse::<Charset>().unwrap().to_string(), "abcd");
+        assert!("".parse::<Charset>().is_err());
+        assert!("utf 8".parse::<Charset>().is_err());
+    }
 
-#[test]
-fn test_display() {
-    assert_eq!("US-ASCII", format!("{}", Charset::US_ASCII));
+    #[test]
+    fn test_display() {
+        assert_eq!("US-ASCII", format!("{}", Charset::US_ASCII));
+        assert_eq!("Big5", format!("{}", Charset::BIG_5));
+    }
+
+    #[test]
+    fn ext_eq_ignores_case() {
+        let a = "unicode-1-1".parse::<Charset>().unwrap();
+        let b = "UNICODE-1-1".parse::<Charset>().unwrap();
+        assert_eq!(a, b);
+    }
 }
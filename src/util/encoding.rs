@@ -0,0 +1,131 @@
+use std::fmt;
+use std::str;
+
+use super::is_token;
+use crate::Error;
+
+/// A value to represent an encoding used in `Transfer-Encoding`
+/// or `Accept-Encoding` header.
+///
+/// Codings are compared case-insensitively, and the deprecated `x-gzip` and
+/// `x-compress` aliases are parsed as `Gzip` and `Compress`.
+#[derive(Clone, Debug)]
+pub enum Encoding {
+    /// The `chunked` encoding.
+    Chunked,
+    /// The `br` encoding.
+    Brotli,
+    /// The `gzip` encoding.
+    Gzip,
+    /// The `deflate` encoding.
+    Deflate,
+    /// The `compress` encoding.
+    Compress,
+    /// The `zstd` encoding.
+    Zstd,
+    /// The `identity` encoding.
+    Identity,
+    /// The `trailers` encoding.
+    Trailers,
+    /// Some other encoding that is less common, can be any token.
+    ///
+    /// In `Accept-Encoding`, the wildcard `*` is represented as
+    /// `EncodingExt("*")`.
+    EncodingExt(String),
+}
+
+impl Encoding {
+    /// Get the coding as a string slice.
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Encoding::Chunked => "chunked",
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+            Encoding::Compress => "compress",
+            Encoding::Zstd => "zstd",
+            Encoding::Identity => "identity",
+            Encoding::Trailers => "trailers",
+            Encoding::EncodingExt(ref s) => s.as_ref(),
+        }
+    }
+
+    pub(crate) fn is_any(&self) -> bool {
+        self.as_str() == "*"
+    }
+}
+
+impl PartialEq for Encoding {
+    fn eq(&self, other: &Encoding) -> bool {
+        self.as_str().eq_ignore_ascii_case(other.as_str())
+    }
+}
+
+impl Eq for Encoding {}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl str::FromStr for Encoding {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Encoding, Error> {
+        let known = [
+            Encoding::Chunked,
+            Encoding::Brotli,
+            Encoding::Gzip,
+            Encoding::Deflate,
+            Encoding::Compress,
+            Encoding::Zstd,
+            Encoding::Identity,
+            Encoding::Trailers,
+        ];
+
+        if let Some(enc) = known
+            .iter()
+            .find(|enc| enc.as_str().eq_ignore_ascii_case(s))
+        {
+            return Ok(enc.clone());
+        }
+
+        if s.eq_ignore_ascii_case("x-gzip") {
+            Ok(Encoding::Gzip)
+        } else if s.eq_ignore_ascii_case("x-compress") {
+            Ok(Encoding::Compress)
+        } else if is_token(s) {
+            Ok(Encoding::EncodingExt(s.to_owned()))
+        } else {
+            Err(Error::invalid())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Encoding;
+
+    #[test]
+    fn parse() {
+        assert_eq!("gzip".parse::<Encoding>().unwrap(), Encoding::Gzip);
+        assert_eq!("GZip".parse::<Encoding>().unwrap(), Encoding::Gzip);
+        assert_eq!("x-gzip".parse::<Encoding>().unwrap(), Encoding::Gzip);
+        assert_eq!("br".parse::<Encoding>().unwrap(), Encoding::Brotli);
+        assert_eq!("zstd".parse::<Encoding>().unwrap(), Encoding::Zstd);
+        assert_eq!(
+            "*".parse::<Encoding>().unwrap(),
+            Encoding::EncodingExt("*".to_owned())
+        );
+        assert!("".parse::<Encoding>().is_err());
+        assert!("gz ip".parse::<Encoding>().is_err());
+    }
+
+    #[test]
+    fn ext_eq_ignores_case() {
+        assert_eq!(
+            Encoding::EncodingExt("AES128GCM".to_owned()),
+            Encoding::EncodingExt("aes128gcm".to_owned())
+        );
+    }
+}
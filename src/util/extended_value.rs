@@ -0,0 +1,227 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Charset, LanguageTag};
+use crate::Error;
+
+/// An extended parameter value, tagged with a character set and optionally a
+/// language, as defined in [RFC8187](https://www.rfc-editor.org/rfc/rfc8187#section-3.2).
+///
+/// Extended values are used in parameters whose names end with `*`, such as
+/// `title*`, to carry text that isn't limited to ASCII.
+///
+/// # ABNF
+///
+/// ```text
+/// ext-value     = charset  "'" [ language ] "'" value-chars
+///
+/// charset       = "UTF-8" / mime-charset
+///
+/// value-chars   = *( pct-encoded / attr-char )
+///
+/// pct-encoded   = "%" HEXDIG HEXDIG
+///
+/// attr-char     = ALPHA / DIGIT
+///               / "!" / "#" / "$" / "&" / "+" / "-" / "."
+///               / "^" / "_" / "`" / "|" / "~"
+///               ; token except ( "*" / "'" / "%" )
+/// ```
+///
+/// # Example
+///
+/// ```
+/// use headers::{Charset, ExtendedValue};
+///
+/// let title = "UTF-8'de'n%c3%a4chstes%20Kapitel".parse::<ExtendedValue>().unwrap();
+/// assert_eq!(title.charset, Charset::UTF_8);
+/// assert_eq!(title.text().unwrap(), "nächstes Kapitel");
+/// assert_eq!(title.to_string(), "UTF-8'de'n%C3%A4chstes%20Kapitel");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtendedValue {
+    /// The character set that is used to encode the `value` to a string.
+    pub charset: Charset,
+    /// The human language details of the `value`, if available.
+    pub language_tag: Option<LanguageTag>,
+    /// The parameter value, as expressed in octets.
+    pub value: Vec<u8>,
+}
+
+impl ExtendedValue {
+    /// Create a UTF-8 encoded extended value, without a language.
+    pub fn utf8(text: &str) -> ExtendedValue {
+        ExtendedValue {
+            charset: Charset::UTF_8,
+            language_tag: None,
+            value: text.as_bytes().to_vec(),
+        }
+    }
+
+    /// Decode the value as text.
+    ///
+    /// Returns `None` if the charset is neither UTF-8 nor ISO-8859-1, or if
+    /// the value isn't valid in its charset.
+    pub fn text(&self) -> Option<String> {
+        if self.charset == Charset::UTF_8 {
+            String::from_utf8(self.value.clone()).ok()
+        } else if self.charset == Charset::ISO_8859_1 {
+            Some(self.value.iter().map(|&b| char::from(b)).collect())
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for ExtendedValue {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<ExtendedValue, Error> {
+        // Break into three pieces separated by the single-quote character
+        let mut parts = s.splitn(3, '\'');
+
+        let charset = parts
+            .next()
+            .ok_or_else(Error::invalid)?
+            .parse::<Charset>()?;
+        if charset.is_any() {
+            return Err(Error::invalid());
+        }
+
+        let language_tag = match parts.next() {
+            None => return Err(Error::invalid()),
+            Some("") => None,
+            Some(tag) => Some(tag.parse()?),
+        };
+
+        let value = percent_decode(parts.next().ok_or_else(Error::invalid)?)?;
+
+        Ok(ExtendedValue {
+            charset,
+            language_tag,
+            value,
+        })
+    }
+}
+
+impl fmt::Display for ExtendedValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}'", self.charset)?;
+        if let Some(ref tag) = self.language_tag {
+            write!(f, "{}", tag)?;
+        }
+        f.write_str("'")?;
+        for &b in &self.value {
+            if is_attr_char(b) {
+                write!(f, "{}", char::from(b))?;
+            } else {
+                write!(f, "%{:02X}", b)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_attr_char(b: u8) -> bool {
+    matches!(
+        b,
+        b'!' | b'#' | b'$' | b'&' | b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~'
+    ) || b.is_ascii_alphanumeric()
+}
+
+fn percent_decode(s: &str) -> Result<Vec<u8>, Error> {
+    let mut bytes = s.bytes();
+    let mut value = Vec::with_capacity(s.len());
+    while let Some(b) = bytes.next() {
+        match b {
+            b'%' => {
+                let hex = |b: Option<u8>| {
+                    b.and_then(|b| char::from(b).to_digit(16))
+                        .ok_or_else(Error::invalid)
+                };
+                let hi = hex(bytes.next())?;
+                let lo = hex(bytes.next())?;
+                value.push((hi * 16 + lo) as u8);
+            }
+            b if is_attr_char(b) => value.push(b),
+            _ => return Err(Error::invalid()),
+        }
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_extended_value_with_encoding_and_language_tag() {
+        let expected_language_tag = "en".parse::<LanguageTag>().unwrap();
+        // RFC 5987, Section 3.2.2
+        // Extended notation, using the Unicode character U+00A3 (POUND SIGN)
+        let extended_value = "iso-8859-1'en'%A3%20rates"
+            .parse::<ExtendedValue>()
+            .unwrap();
+        assert_eq!(Charset::ISO_8859_1, extended_value.charset);
+        assert_eq!(Some(expected_language_tag), extended_value.language_tag);
+        assert_eq!(
+            vec![163, b' ', b'r', b'a', b't', b'e', b's'],
+            extended_value.value
+        );
+        assert_eq!(extended_value.text().unwrap(), "£ rates");
+    }
+
+    #[test]
+    fn test_parse_extended_value_with_encoding() {
+        // RFC 5987, Section 3.2.2
+        // Extended notation, using the Unicode characters U+00A3 (POUND SIGN)
+        // and U+20AC (EURO SIGN)
+        let extended_value = "UTF-8''%c2%a3%20and%20%e2%82%ac%20rates"
+            .parse::<ExtendedValue>()
+            .unwrap();
+        assert_eq!(Charset::UTF_8, extended_value.charset);
+        assert!(extended_value.language_tag.is_none());
+        assert_eq!(extended_value.text().unwrap(), "£ and € rates");
+    }
+
+    #[test]
+    fn test_parse_extended_value_missing_language_tag_and_encoding() {
+        // From: https://greenbytes.de/tech/tc2231/#attwithfn2231quot2
+        assert!("foo%20bar.html".parse::<ExtendedValue>().is_err());
+    }
+
+    #[test]
+    fn test_parse_extended_value_partially_formatted() {
+        assert!("UTF-8'missing third part".parse::<ExtendedValue>().is_err());
+    }
+
+    #[test]
+    fn test_parse_extended_value_partially_formatted_blank() {
+        assert!("blank second part'".parse::<ExtendedValue>().is_err());
+    }
+
+    #[test]
+    fn test_parse_extended_value_invalid_chars() {
+        assert!("UTF-8''two words".parse::<ExtendedValue>().is_err());
+        assert!("UTF-8''%2".parse::<ExtendedValue>().is_err());
+        assert!("UTF-8''%zz".parse::<ExtendedValue>().is_err());
+    }
+
+    #[test]
+    fn test_fmt_extended_value_with_encoding_and_language_tag() {
+        let extended_value = ExtendedValue {
+            charset: Charset::ISO_8859_1,
+            language_tag: Some("en".parse().expect("Could not parse language tag")),
+            value: vec![163, b' ', b'r', b'a', b't', b'e', b's'],
+        };
+        assert_eq!("ISO-8859-1'en'%A3%20rates", format!("{}", extended_value));
+    }
+
+    #[test]
+    fn test_fmt_extended_value_with_encoding() {
+        let extended_value = ExtendedValue::utf8("£ and € rates");
+        assert_eq!(
+            "UTF-8''%C2%A3%20and%20%E2%82%AC%20rates",
+            format!("{}", extended_value)
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Error;
+
+/// A language tag, such as `en-US`, defined in
+/// [RFC5646](https://www.rfc-editor.org/rfc/rfc5646).
+///
+/// Only the general shape of the tag is checked, which is a primary subtag of
+/// 1 to 8 letters, followed by any number of subtags of 1 to 8 letters or
+/// digits, separated by `-`. Tags are compared case-insensitively.
+///
+/// # Example
+///
+/// ```
+/// use headers::LanguageTag;
+///
+/// let tag = "de-CH-1996".parse::<LanguageTag>().unwrap();
+/// assert_eq!(tag, "DE-ch-1996".parse::<LanguageTag>().unwrap());
+/// ```
+#[derive(Clone, Debug)]
+pub struct LanguageTag(String);
+
+/// A language range, used to match language tags, defined in
+/// [RFC4647](https://www.rfc-editor.org/rfc/rfc4647#section-2).
+///
+/// Besides basic ranges like `en-US` and the wildcard `*`, this also accepts
+/// extended ranges where any subtag can be `*`, like `*-CH` or `de-*-DE`.
+///
+/// # Example
+///
+/// ```
+/// use headers::LanguageRange;
+///
+/// let range = "de-*-DE".parse::<LanguageRange>().unwrap();
+/// assert!(range.matches_extended("de-Latn-DE"));
+/// assert!(!range.matches_basic("de-Latn-DE"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LanguageRange(String);
+
+// ===== impl LanguageTag =====
+
+impl LanguageTag {
+    /// View this tag as a `&str`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for LanguageTag {
+    fn eq(&self, other: &LanguageTag) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for LanguageTag {}
+
+impl AsRef<str> for LanguageTag {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for LanguageTag {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if is_valid(s, false) {
+            Ok(LanguageTag(s.to_owned()))
+        } else {
+            Err(Error::invalid())
+        }
+    }
+}
+
+// ===== impl LanguageRange =====
+
+impl LanguageRange {
+    /// Check if this is the wildcard range `*`.
+    pub fn is_wildcard(&self) -> bool {
+        self.0 == "*"
+    }
+
+    /// View this range as a `&str`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Check if a language tag matches this range, using
+    /// [basic filtering](https://www.rfc-editor.org/rfc/rfc4647#section-3.3.1).
+    ///
+    /// A tag matches if it is equal to the range, or if it starts with the
+    /// range followed by a `-`. The range `*` matches every tag.
+    pub fn matches_basic(&self, tag: &str) -> bool {
+        if self.is_wildcard() {
+            return true;
+        }
+
+        let range = self.0.as_bytes();
+        let tag = tag.as_bytes();
+        if tag.len() < range.len() || !tag[..range.len()].eq_ignore_ascii_case(range) {
+            return false;
+        }
+        tag.len() == range.len() || tag[range.len()] == b'-'
+    }
+
+    /// Check if a language tag matches this range, using
+    /// [extended filtering](https://www.rfc-editor.org/rfc/rfc4647#section-3.3.2).
+    ///
+    /// Subtags of the range must appear in the tag in the same order, but
+    /// the tag may have extra subtags in between. A `*` subtag matches any
+    /// subtag.
+    pub fn matches_extended(&self, tag: &str) -> bool {
+        let mut range = self.0.split('-');
+        let mut tag = tag.split('-');
+
+        match (range.next(), tag.next()) {
+            (Some("*"), Some(_)) => (),
+            (Some(r), Some(t)) if r.eq_ignore_ascii_case(t) => (),
+            _ => return false,
+        }
+
+        let mut t = tag.next();
+        for r in range {
+            if r == "*" {
+                continue;
+            }
+
+            loop {
+                match t {
+                    None => return false,
+                    Some(sub) if sub.eq_ignore_ascii_case(r) => {
+                        t = tag.next();
+                        break;
+                    }
+                    // Singletons (like `x` in `x-private`) cannot be skipped.
+                    Some(sub) if sub.len() == 1 => return false,
+                    Some(_) => t = tag.next(),
+                }
+            }
+        }
+
+        true
+    }
+}
+
+impl PartialEq for LanguageRange {
+    fn eq(&self, other: &LanguageRange) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for LanguageRange {}
+
+impl AsRef<str> for LanguageRange {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for LanguageRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for LanguageRange {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if is_valid(s, true) {
+            Ok(LanguageRange(s.to_owned()))
+        } else {
+            Err(Error::invalid())
+        }
+    }
+}
+
+impl From<LanguageTag> for LanguageRange {
+    fn from(tag: LanguageTag) -> LanguageRange {
+        LanguageRange(tag.0)
+    }
+}
+
+// language-tag  = 1*8ALPHA *("-" 1*8alphanum)
+// language-range = (1*8ALPHA / "*") *("-" (1*8alphanum / "*"))
+fn is_valid(s: &str, wildcards: bool) -> bool {
+    s.split('-').enumerate().all(|(i, sub)| {
+        if wildcards && sub == "*" {
+            return true;
+        }
+        let valid_char = |b: u8| {
+            if i == 0 {
+                b.is_ascii_alphabetic()
+            } else {
+                b.is_ascii_alphanumeric()
+            }
+        };
+        (1..=8).contains(&sub.len()) && sub.bytes().all(valid_char)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(s: &str) -> LanguageRange {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_tag() {
+        assert!("en".parse::<LanguageTag>().is_ok());
+        assert!("zh-Hant-TW".parse::<LanguageTag>().is_ok());
+        assert!("x-private1".parse::<LanguageTag>().is_ok());
+        assert!("*".parse::<LanguageTag>().is_err());
+        assert!("".parse::<LanguageTag>().is_err());
+        assert!("en-".parse::<LanguageTag>().is_err());
+        assert!("1en".parse::<LanguageTag>().is_err());
+        assert!("en-toolongsubtag".parse::<LanguageTag>().is_err());
+    }
+
+    #[test]
+    fn parse_range() {
+        assert!(range("*").is_wildcard());
+        assert!("*-CH".parse::<LanguageRange>().is_ok());
+        assert!("de-*-DE".parse::<LanguageRange>().is_ok());
+        assert!("de-**".parse::<LanguageRange>().is_err());
+    }
+
+    #[test]
+    fn basic_filtering() {
+        assert!(range("de-de").matches_basic("de-DE"));
+        assert!(range("de-de").matches_basic("de-DE-1996"));
+        assert!(!range("de-de").matches_basic("de-Deva"));
+        assert!(!range("de-de").matches_basic("de-Latn-DE"));
+        assert!(!range("de").matches_basic("den"));
+        assert!(range("*").matches_basic("fr"));
+    }
+
+    #[test]
+    fn extended_filtering() {
+        // RFC 4647 Section 3.3.2
+        let de_de = range("de-*-DE");
+        assert!(de_de.matches_extended("de-DE"));
+        assert!(de_de.matches_extended("de-de"));
+        assert!(de_de.matches_extended("de-Latn-DE"));
+        assert!(de_de.matches_extended("de-Latf-DE"));
+        assert!(de_de.matches_extended("de-DE-x-goethe"));
+        assert!(de_de.matches_extended("de-Latn-DE-1996"));
+        assert!(de_de.matches_extended("de-Deva-DE"));
+        assert!(!de_de.matches_extended("de"));
+        assert!(!de_de.matches_extended("de-x-DE"));
+        assert!(!de_de.matches_extended("de-Deva"));
+
+        assert!(range("*-CH").matches_extended("fr-CH"));
+        assert!(range("*-CH").matches_extended("de-Latn-CH"));
+        assert!(!range("*-CH").matches_extended("de-DE"));
+    }
+}
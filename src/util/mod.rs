@@ -2,28 +2,37 @@ use http::HeaderValue;
 
 use crate::Error;
 
-//pub use self::charset::Charset;
-//pub use self::encoding::Encoding;
+pub use self::charset::Charset;
+pub use self::encoding::Encoding;
 pub(crate) use self::entity::{EntityTag, EntityTagRange};
+pub use self::extended_value::ExtendedValue;
 pub(crate) use self::flat_csv::{FlatCsv, SemiColon};
 pub(crate) use self::fmt::fmt;
 pub(crate) use self::http_date::HttpDate;
 pub(crate) use self::iter::IterExt;
-//pub use language_tags::LanguageTag;
-//pub use self::quality_value::{Quality, QualityValue};
+pub use self::language::{LanguageRange, LanguageTag};
+pub(crate) use self::param::{is_ows, parse_param};
+pub use self::quality_value::{Quality, QualityValue};
+pub(crate) use self::quoted_string::{fmt_quoted, fmt_token_or_quoted, is_quotable, parse_quoted};
 pub(crate) use self::seconds::Seconds;
+pub(crate) use self::token::{is_scheme, is_tchar, is_token};
 pub(crate) use self::value_string::HeaderValueString;
 
-//mod charset;
+mod charset;
 pub(crate) mod csv;
-//mod encoding;
+mod encoding;
 mod entity;
+mod extended_value;
 mod flat_csv;
 mod fmt;
 mod http_date;
 mod iter;
-//mod quality_value;
+mod language;
+mod param;
+mod quality_value;
+mod quoted_string;
 mod seconds;
+mod token;
 mod value_string;
 
 macro_rules! error_type {
@@ -0,0 +1,61 @@
+use super::{is_tchar, parse_quoted};
+use crate::Error;
+
+/// Parse a `token [ BWS "=" BWS ( token / quoted-string ) ]` parameter at
+/// the start of `s`, skipping leading whitespace.
+///
+/// Returns the name, the unquoted value if any, and the rest of `s`.
+/// Unquoted values are leniently allowed to contain any character except
+/// `;`, `,` and whitespace.
+pub(crate) fn parse_param(s: &str) -> Result<(&str, Option<String>, &str), Error> {
+    let s = s.trim_start_matches(is_ows);
+    let name_end = s.bytes().position(|b| !is_tchar(b)).unwrap_or(s.len());
+    if name_end == 0 {
+        return Err(Error::invalid());
+    }
+    let (name, rest) = s.split_at(name_end);
+
+    let rest = rest.trim_start_matches(is_ows);
+    let value = match rest.strip_prefix('=') {
+        Some(value) => value.trim_start_matches(is_ows),
+        None => return Ok((name, None, rest)),
+    };
+
+    if value.starts_with('"') {
+        let (value, rest) = parse_quoted(value).ok_or_else(Error::invalid)?;
+        Ok((name, Some(value), rest))
+    } else {
+        let end = value
+            .find(|c| c == ';' || c == ',' || is_ows(c))
+            .unwrap_or(value.len());
+        if end == 0 {
+            return Err(Error::invalid());
+        }
+        Ok((name, Some(value[..end].to_owned()), &value[end..]))
+    }
+}
+
+pub(crate) fn is_ows(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params() {
+        assert_eq!(
+            parse_param(" foo = bar; baz").unwrap(),
+            ("foo", Some("bar".to_owned()), "; baz")
+        );
+        assert_eq!(
+            parse_param("foo=\"a; b\", c").unwrap(),
+            ("foo", Some("a; b".to_owned()), ", c")
+        );
+        assert_eq!(parse_param("foo; bar").unwrap(), ("foo", None, "; bar"));
+        assert!(parse_param("=bar").is_err());
+        assert!(parse_param("foo=").is_err());
+        assert!(parse_param("foo=\"open").is_err());
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt;
+use std::str;
+
+use crate::Error;
+
+/// Represents a quality used in quality values.
+///
+/// # Implementation notes
+///
+/// The quality value is defined as a number between 0 and 1 with three decimal places. This means
+/// there are 1001 possible values. Since floating point numbers are not exact and the smallest
+/// floating point data type (`f32`) consumes four bytes, this uses an `u16` value to store the
+/// quality internally. The quality `q=0.532` is stored as `532`.
+///
+/// [RFC9110 Section 12.4.2](https://www.rfc-editor.org/rfc/rfc9110#section-12.4.2)
+/// gives more information on quality values in HTTP header fields.
+#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Hash)]
+pub struct Quality(u16);
+
+impl Quality {
+    /// The quality `q=0`, meaning "not acceptable".
+    pub const ZERO: Quality = Quality(0);
+
+    /// The quality `q=1`, the default when no weight is given.
+    pub const ONE: Quality = Quality(1000);
+
+    /// Create a `Quality` from thousandths, such that `500` is `q=0.5`.
+    ///
+    /// Returns `None` if the value is greater than `1000`.
+    pub fn from_u16(thousandths: u16) -> Option<Quality> {
+        if thousandths <= 1000 {
+            Some(Quality(thousandths))
+        } else {
+            None
+        }
+    }
+
+    /// Get this quality as thousandths, such that `q=0.5` is `500`.
+    pub fn as_u16(&self) -> u16 {
+        self.0
+    }
+
+    // qvalue = ( "0" [ "." 0*3DIGIT ] )
+    //        / ( "1" [ "." 0*3("0") ] )
+    fn parse(s: &str) -> Option<Quality> {
+        let (int, frac) = match s.find('.') {
+            Some(idx) => (&s[..idx], &s[idx + 1..]),
+            None => (s, ""),
+        };
+
+        if frac.len() > 3 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        match int {
+            "0" => {
+                let thousandths = frac
+                    .bytes()
+                    .chain(std::iter::repeat(b'0'))
+                    .take(3)
+                    .fold(0, |acc, b| acc * 10 + u16::from(b - b'0'));
+                Some(Quality(thousandths))
+            }
+            "1" if frac.bytes().all(|b| b == b'0') => Some(Quality::ONE),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Quality {
+    fn default() -> Quality {
+        Quality::ONE
+    }
+}
+
+impl fmt::Display for Quality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            1000 => f.write_str("1"),
+            0 => f.write_str("0"),
+            x => write!(f, "0.{}", format!("{:03}", x).trim_end_matches('0')),
+        }
+    }
+}
+
+/// Represents an item with a quality value as defined in
+/// [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-12.4.2).
+#[derive(Clone, PartialEq, Debug)]
+pub struct QualityValue<T> {
+    /// The actual contents of the field.
+    value: T,
+    /// The quality (client or server preference) for the value.
+    quality: Quality,
+}
+
+impl<T> QualityValue<T> {
+    /// Creates a new `QualityValue` from an item and a quality.
+    pub fn new(value: T, quality: Quality) -> QualityValue<T> {
+        QualityValue { value, quality }
+    }
+
+    /// Get a reference to the value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Get the quality of the value.
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    /// Consume this `QualityValue`, returning the value.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl<T> From<T> for QualityValue<T> {
+    fn from(value: T) -> QualityValue<T> {
+        QualityValue {
+            value,
+            quality: Quality::default(),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for QualityValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.value, f)?;
+        match self.quality.0 {
+            1000 => Ok(()),
+            _ => write!(f, "; q={}", self.quality),
+        }
+    }
+}
+
+impl<T: str::FromStr> str::FromStr for QualityValue<T> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<QualityValue<T>, Error> {
+        let mut quality = Quality::default();
+        let mut value_end = s.len();
+
+        // The `q` parameter separates the value (and its own parameters)
+        // from any `accept-ext` parameters, which are ignored.
+        let mut in_quotes = false;
+        let mut escaped = false;
+        for (idx, c) in s.char_indices() {
+            if in_quotes {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => in_quotes = false,
+                    _ => (),
+                }
+                continue;
+            }
+
+            match c {
+                '"' => in_quotes = true,
+                ';' => {
+                    let param = s[idx + 1..].trim_start();
+                    let is_weight = param
+                        .get(..2)
+                        .map(|name| name.eq_ignore_ascii_case("q="))
+                        .unwrap_or(false);
+                    if is_weight {
+                        let q = param[2..].split(';').next().unwrap_or("").trim();
+                        quality = Quality::parse(q).ok_or_else(Error::invalid)?;
+                        value_end = idx;
+                        break;
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        s[..value_end]
+            .trim()
+            .parse()
+            .map(|value| QualityValue::new(value, quality))
+            .map_err(|_| Error::invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn q(thousandths: u16) -> Quality {
+        Quality::from_u16(thousandths).unwrap()
+    }
+
+    #[test]
+    fn test_quality_item_fmt_q_1() {
+        let x = QualityValue::from("foo");
+        assert_eq!(format!("{}", x), "foo");
+    }
+
+    #[test]
+    fn test_quality_item_fmt_q_0001() {
+        let x = QualityValue::new("foo", q(1));
+        assert_eq!(format!("{}", x), "foo; q=0.001");
+    }
+
+    #[test]
+    fn test_quality_item_fmt_q_05() {
+        let x = QualityValue::new("foo", q(500));
+        assert_eq!(format!("{}", x), "foo; q=0.5");
+    }
+
+    #[test]
+    fn test_quality_item_fmt_q_0() {
+        let x = QualityValue::new("foo", Quality::ZERO);
+        assert_eq!(x.to_string(), "foo; q=0");
+    }
+
+    #[test]
+    fn test_quality_item_from_str1() {
+        let x: QualityValue<String> = "chunked".parse().unwrap();
+        assert_eq!(x, QualityValue::new("chunked".to_owned(), q(1000)));
+    }
+
+    #[test]
+    fn test_quality_item_from_str2() {
+        let x: QualityValue<String> = "chunked; q=1".parse().unwrap();
+        assert_eq!(x, QualityValue::new("chunked".to_owned(), q(1000)));
+    }
+
+    #[test]
+    fn test_quality_item_from_str3() {
+        let x: QualityValue<String> = "gzip; q=0.5".parse().unwrap();
+        assert_eq!(x, QualityValue::new("gzip".to_owned(), q(500)));
+    }
+
+    #[test]
+    fn test_quality_item_from_str4() {
+        let x: QualityValue<String> = "gzip; q=0.273".parse().unwrap();
+        assert_eq!(x, QualityValue::new("gzip".to_owned(), q(273)));
+    }
+
+    #[test]
+    fn test_quality_item_from_str5() {
+        assert!("gzip; q=0.2739999".parse::<QualityValue<String>>().is_err());
+    }
+
+    #[test]
+    fn test_quality_item_from_str6() {
+        assert!("gzip; q=2".parse::<QualityValue<String>>().is_err());
+        assert!("gzip; q=1.001".parse::<QualityValue<String>>().is_err());
+        assert!("gzip; q=-0".parse::<QualityValue<String>>().is_err());
+    }
+
+    #[test]
+    fn test_quality_item_params_and_ext() {
+        let x: QualityValue<String> = "text/html;level=1;Q=0.4;ext=\"a;q=0\"".parse().unwrap();
+        assert_eq!(x, QualityValue::new("text/html;level=1".to_owned(), q(400)));
+
+        let x: QualityValue<String> = "foo;bar=\"q=0.1\"".parse().unwrap();
+        assert_eq!(
+            x,
+            QualityValue::new("foo;bar=\"q=0.1\"".to_owned(), q(1000))
+        );
+    }
+
+    #[test]
+    fn test_quality_bounds() {
+        assert_eq!(Quality::from_u16(1000), Some(Quality::ONE));
+        assert_eq!(Quality::from_u16(1001), None);
+    }
+
+    #[test]
+    fn test_fuzzing_bugs() {
+        assert!("99999;q=".parse::<QualityValue<String>>().is_err());
+        assert!("\x0d;;;=\u{d6aa}==".parse::<QualityValue<String>>().is_ok())
+    }
+}
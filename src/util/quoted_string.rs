@@ -0,0 +1,101 @@
+use std::fmt;
+
+use super::is_token;
+
+/// Write a value as a `token` if it is one, otherwise as a `quoted-string`,
+/// as defined in [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-5.6.4).
+pub(crate) fn fmt_token_or_quoted(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    if is_token(value) {
+        f.write_str(value)
+    } else {
+        fmt_quoted(f, value)
+    }
+}
+
+/// Write a value as a `quoted-string`, escaping `"` and `\`.
+pub(crate) fn fmt_quoted(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    let mut start = 0;
+    for (idx, c) in value.char_indices() {
+        if c == '"' || c == '\\' {
+            f.write_str(&value[start..idx])?;
+            f.write_str("\\")?;
+            start = idx;
+        }
+    }
+    f.write_str(&value[start..])?;
+    f.write_str("\"")
+}
+
+/// Check if a value can be written as a `quoted-string`, meaning it has no
+/// control characters other than horizontal tab.
+pub(crate) fn is_quotable(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|b| b == b'\t' || (b >= 0x20 && b != 0x7f))
+}
+
+/// Parse a `quoted-string` at the start of `s`.
+///
+/// Returns the unescaped value, and the rest of `s` after the closing quote.
+pub(crate) fn parse_quoted(s: &str) -> Option<(String, &str)> {
+    let mut chars = s.char_indices();
+    if chars.next()?.1 != '"' {
+        return None;
+    }
+
+    let mut value = String::new();
+    let mut escaped = false;
+    for (idx, c) in chars {
+        match c {
+            _ if escaped => {
+                value.push(c);
+                escaped = false;
+            }
+            '\\' => escaped = true,
+            '"' => return Some((value, &s[idx + 1..])),
+            _ => value.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Quoted(&'static str);
+
+    impl fmt::Display for Quoted {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt_token_or_quoted(f, self.0)
+        }
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(Quoted("token").to_string(), "token");
+        assert_eq!(Quoted("two words").to_string(), "\"two words\"");
+        assert_eq!(Quoted("a \"b\" \\c").to_string(), r#""a \"b\" \\c""#);
+        assert_eq!(Quoted("").to_string(), "\"\"");
+    }
+
+    #[test]
+    fn quotable() {
+        assert!(is_quotable(""));
+        assert!(is_quotable("a \"b\"\tc"));
+        assert!(is_quotable("caf\u{e9}"));
+        assert!(!is_quotable("a\r\nb"));
+        assert!(!is_quotable("\x7f"));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            parse_quoted(r#""a \"b\", c"; rest"#),
+            Some((r#"a "b", c"#.to_owned(), "; rest"))
+        );
+        assert_eq!(parse_quoted("\"unterminated"), None);
+        assert_eq!(parse_quoted("token"), None);
+    }
+}
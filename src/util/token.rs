@@ -0,0 +1,73 @@
+/// Check if a string is a `token`, as defined in
+/// [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-5.6.2).
+///
+/// ```text
+/// token          = 1*tchar
+/// tchar          = "!" / "#" / "$" / "%" / "&" / "'" / "*"
+///                / "+" / "-" / "." / "^" / "_" / "`" / "|" / "~"
+///                / DIGIT / ALPHA
+/// ```
+pub(crate) fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(is_tchar)
+}
+
+pub(crate) fn is_tchar(b: u8) -> bool {
+    matches!(
+        b,
+        b'!' | b'#'
+            | b'$'
+            | b'%'
+            | b'&'
+            | b'\''
+            | b'*'
+            | b'+'
+            | b'-'
+            | b'.'
+            | b'^'
+            | b'_'
+            | b'`'
+            | b'|'
+            | b'~'
+    ) || b.is_ascii_alphanumeric()
+}
+
+/// Check if a string is a URI `scheme`, as defined in
+/// [RFC3986](https://www.rfc-editor.org/rfc/rfc3986#section-3.1).
+///
+/// ```text
+/// scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+/// ```
+pub(crate) fn is_scheme(s: &str) -> bool {
+    match s.as_bytes().first() {
+        Some(b) if b.is_ascii_alphabetic() => s
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.')),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens() {
+        assert!(is_token("gzip"));
+        assert!(is_token("x-custom_1.0"));
+        assert!(is_token("*"));
+        assert!(!is_token(""));
+        assert!(!is_token("a b"));
+        assert!(!is_token("a,b"));
+        assert!(!is_token("\"a\""));
+    }
+
+    #[test]
+    fn schemes() {
+        assert!(is_scheme("https"));
+        assert!(is_scheme("coap+tcp"));
+        assert!(is_scheme("x-a.b1"));
+        assert!(!is_scheme(""));
+        assert!(!is_scheme("1http"));
+        assert!(!is_scheme("ht tp"));
+    }
+}